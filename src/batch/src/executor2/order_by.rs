@@ -244,6 +244,7 @@ impl OrderByExecutor2 {
                             Bool,
                             Decimal,
                             Interval,
+                            Bytea,
                             NaiveDate,
                             NaiveTime,
                             NaiveDateTime
@@ -0,0 +1,228 @@
+// Copyright 2022 Singularity Data
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::hash::{Hash, Hasher};
+use std::iter;
+use std::mem::size_of;
+
+use itertools::Itertools;
+use risingwave_pb::data::buffer::CompressionType;
+use risingwave_pb::data::{Array as ProstArray, ArrayType, Buffer};
+
+use super::{Array, ArrayBuilder, ArrayIterator, ArrayMeta, NULL_VAL_FOR_HASH};
+use crate::array::ArrayBuilderImpl;
+use crate::buffer::{Bitmap, BitmapBuilder};
+use crate::error::Result;
+
+/// `BytesArray` is a collection of binary strings, i.e. arbitrary byte sequences.
+#[derive(Debug)]
+pub struct BytesArray {
+    offset: Vec<usize>,
+    bitmap: Bitmap,
+    data: Vec<u8>,
+}
+
+impl Array for BytesArray {
+    type Builder = BytesArrayBuilder;
+    type Iter<'a> = ArrayIterator<'a, Self>;
+    type OwnedItem = Box<[u8]>;
+    type RefItem<'a> = &'a [u8];
+
+    fn value_at(&self, idx: usize) -> Option<&[u8]> {
+        if !self.is_null(idx) {
+            Some(&self.data[self.offset[idx]..self.offset[idx + 1]])
+        } else {
+            None
+        }
+    }
+
+    unsafe fn value_at_unchecked(&self, idx: usize) -> Option<&[u8]> {
+        if !self.is_null_unchecked(idx) {
+            Some(&self.data[self.offset[idx]..self.offset[idx + 1]])
+        } else {
+            None
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.offset.len() - 1
+    }
+
+    fn iter(&self) -> ArrayIterator<'_, Self> {
+        ArrayIterator::new(self)
+    }
+
+    fn to_protobuf(&self) -> ProstArray {
+        // Same layout as `Utf8Array`: the offsets of non-null values, plus the end of the data.
+        let offset_buffer = self
+            .offset
+            .iter()
+            .zip_eq(self.null_bitmap().iter().chain(iter::once(true)))
+            .fold(
+                Vec::<u8>::with_capacity(self.offset.len() * size_of::<usize>()),
+                |mut buffer, (offset, not_null)| {
+                    if not_null {
+                        let offset = *offset as u64;
+                        buffer.extend_from_slice(&offset.to_be_bytes());
+                    }
+                    buffer
+                },
+            );
+
+        let values = vec![
+            Buffer {
+                compression: CompressionType::None as i32,
+                body: offset_buffer,
+            },
+            Buffer {
+                compression: CompressionType::None as i32,
+                body: self.data.clone(),
+            },
+        ];
+        let null_bitmap = self.null_bitmap().to_protobuf();
+        ProstArray {
+            null_bitmap: Some(null_bitmap),
+            values,
+            array_type: ArrayType::Bytea as i32,
+            struct_array_data: None,
+            list_array_data: None,
+        }
+    }
+
+    fn null_bitmap(&self) -> &Bitmap {
+        &self.bitmap
+    }
+
+    fn set_bitmap(&mut self, bitmap: Bitmap) {
+        self.bitmap = bitmap;
+    }
+
+    #[inline(always)]
+    fn hash_at<H: Hasher>(&self, idx: usize, state: &mut H) {
+        if !self.is_null(idx) {
+            self.data[self.offset[idx]..self.offset[idx + 1]].hash(state);
+        } else {
+            NULL_VAL_FOR_HASH.hash(state);
+        }
+    }
+
+    fn create_builder(&self, capacity: usize) -> Result<ArrayBuilderImpl> {
+        let array_builder = BytesArrayBuilder::new(capacity)?;
+        Ok(ArrayBuilderImpl::Bytea(array_builder))
+    }
+}
+
+impl BytesArray {
+    pub fn from_slice(data: &[Option<&[u8]>]) -> Result<Self> {
+        let mut builder = <Self as Array>::Builder::new(data.len())?;
+        for i in data {
+            builder.append(*i)?;
+        }
+        builder.finish()
+    }
+}
+
+/// `BytesArrayBuilder` use `&[u8]` to build a `BytesArray`.
+#[derive(Debug)]
+pub struct BytesArrayBuilder {
+    offset: Vec<usize>,
+    bitmap: BitmapBuilder,
+    data: Vec<u8>,
+}
+
+impl ArrayBuilder for BytesArrayBuilder {
+    type ArrayType = BytesArray;
+
+    fn with_meta(capacity: usize, _meta: ArrayMeta) -> Result<Self> {
+        let mut offset = Vec::with_capacity(capacity + 1);
+        offset.push(0);
+        Ok(Self {
+            offset,
+            data: Vec::with_capacity(capacity),
+            bitmap: BitmapBuilder::with_capacity(capacity),
+        })
+    }
+
+    fn append<'a>(&'a mut self, value: Option<&'a [u8]>) -> Result<()> {
+        match value {
+            Some(x) => {
+                self.bitmap.append(true);
+                self.data.extend_from_slice(x);
+                self.offset.push(self.data.len())
+            }
+            None => {
+                self.bitmap.append(false);
+                self.offset.push(self.data.len())
+            }
+        }
+        Ok(())
+    }
+
+    fn append_array(&mut self, other: &BytesArray) -> Result<()> {
+        for bit in other.bitmap.iter() {
+            self.bitmap.append(bit);
+        }
+        self.data.extend_from_slice(&other.data);
+        let start = *self.offset.last().unwrap();
+        for other_offset in &other.offset[1..] {
+            self.offset.push(*other_offset + start);
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<BytesArray> {
+        Ok(BytesArray {
+            bitmap: (self.bitmap).finish(),
+            data: self.data,
+            offset: self.offset,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn test_bytes_array() {
+        let input: Vec<Option<&[u8]>> = vec![
+            Some(b"\x00"),
+            Some(b"\xde\xad"),
+            None,
+            Some(b""),
+            None,
+            Some(b"\xff\xfe\xfd"),
+        ];
+
+        let array = BytesArray::from_slice(&input).unwrap();
+        assert_eq!(array.len(), input.len());
+        assert_eq!(array.data.len(), 6);
+        assert_eq!(input, array.iter().collect_vec());
+        // An empty value is not null.
+        assert!(!array.is_null(3));
+    }
+
+    #[test]
+    fn test_bytes_array_to_protobuf() {
+        let input: Vec<Option<&[u8]>> = vec![Some(b"\x01\x02"), None, Some(b"\x80")];
+        let array = BytesArray::from_slice(&input).unwrap();
+        let prost_array = array.to_protobuf();
+        assert_eq!(prost_array.array_type, ArrayType::Bytea as i32);
+
+        let decoded = crate::array::ArrayImpl::from_protobuf(&prost_array, input.len()).unwrap();
+        assert_eq!(decoded.as_bytea().iter().collect_vec(), input);
+    }
+}
@@ -15,7 +15,7 @@
 use risingwave_pb::expr::InputRefExpr;
 use risingwave_pb::plan_common::ColumnOrder;
 
-use super::{ColumnDesc, OrderedColumnDesc, TableId};
+use super::{ColumnDesc, OrderedColumnDesc, SchemaId, TableId};
 
 /// the table descriptor of table with cell based encoding in state store and include all
 /// information for compute node to access data of the table.
@@ -23,6 +23,8 @@ use super::{ColumnDesc, OrderedColumnDesc, TableId};
 pub struct TableDesc {
     /// Id of the table, to find in storage.
     pub table_id: TableId,
+    /// Schema of the table, along with the database of the schema.
+    pub schema_id: SchemaId,
     /// The keys used to sort in storage.
     pub order_desc: Vec<OrderedColumnDesc>,
     /// All columns in the table, noticed it is NOT sorted by columnId in the vec.
@@ -33,6 +35,8 @@ pub struct TableDesc {
     pub distribution_keys: Vec<usize>,
     /// Column indices for primary keys.
     pub pks: Vec<usize>,
+    /// Whether rows are only appended to the table, i.e. never updated or deleted.
+    pub appendonly: bool,
 }
 
 impl TableDesc {
@@ -70,6 +70,7 @@ pub enum DataType {
     Interval,
     Struct { fields: Arc<[DataType]> },
     List { datatype: Box<DataType> },
+    Bytea,
 }
 
 const DECIMAL_DEFAULT_PRECISION: u32 = 20;
@@ -112,6 +113,7 @@ impl From<&ProstDataType> for DataType {
             TypeName::List => DataType::List {
                 datatype: Box::new((&proto.field_type[0]).into()),
             },
+            TypeName::Bytea => DataType::Bytea,
         }
     }
 }
@@ -147,6 +149,7 @@ impl DataType {
                 },
             )?
             .into(),
+            DataType::Bytea => BytesArrayBuilder::new(capacity)?.into(),
         })
     }
 
@@ -167,6 +170,7 @@ impl DataType {
             DataType::Interval => TypeName::Interval,
             DataType::Struct { .. } => TypeName::Struct,
             DataType::List { .. } => TypeName::List,
+            DataType::Bytea => TypeName::Bytea,
         }
     }
 
@@ -208,6 +212,30 @@ impl DataType {
             DataType::Interval => DataSize::Variable,
             DataType::Struct { .. } => DataSize::Variable,
             DataType::List { .. } => DataSize::Variable,
+            DataType::Bytea => DataSize::Variable,
+        }
+    }
+
+    /// Returns the variant of `ScalarImpl` holding the values of the type.
+    pub fn scalar_variant(&self) -> ScalarVariant {
+        match self {
+            DataType::Boolean => ScalarVariant::Bool,
+            DataType::Int16 => ScalarVariant::Int16,
+            DataType::Int32 => ScalarVariant::Int32,
+            DataType::Int64 => ScalarVariant::Int64,
+            DataType::Float32 => ScalarVariant::Float32,
+            DataType::Float64 => ScalarVariant::Float64,
+            DataType::Decimal => ScalarVariant::Decimal,
+            DataType::Varchar => ScalarVariant::Utf8,
+            DataType::Date => ScalarVariant::NaiveDate,
+            DataType::Time => ScalarVariant::NaiveTime,
+            DataType::Timestamp => ScalarVariant::NaiveDateTime,
+            // Timestamps with time zone are stored as microseconds since epoch in UTC.
+            DataType::Timestampz => ScalarVariant::Int64,
+            DataType::Interval => ScalarVariant::Interval,
+            DataType::Struct { .. } => ScalarVariant::Struct,
+            DataType::List { .. } => ScalarVariant::List,
+            DataType::Bytea => ScalarVariant::Bytea,
         }
     }
 
@@ -294,6 +322,7 @@ macro_rules! for_all_scalar_variants {
             { Bool, bool, bool, bool },
             { Decimal, decimal, Decimal, Decimal  },
             { Interval, interval, IntervalUnit, IntervalUnit },
+            { Bytea, bytea, Box<[u8]>, &'scalar [u8] },
             { NaiveDate, naivedate, NaiveDateWrapper, NaiveDateWrapper },
             { NaiveDateTime, naivedatetime, NaiveDateTimeWrapper, NaiveDateTimeWrapper },
             { NaiveTime, naivetime, NaiveTimeWrapper, NaiveTimeWrapper },
@@ -318,6 +347,21 @@ macro_rules! scalar_impl_enum {
         pub enum ScalarRefImpl<'scalar> {
             $( $variant_name($scalar_ref) ),*
         }
+
+        /// `ScalarVariant` tags the variants of `ScalarImpl` without holding a scalar.
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        pub enum ScalarVariant {
+            $( $variant_name ),*
+        }
+
+        impl ScalarImpl {
+            /// Returns the tag of the variant of the scalar.
+            pub fn variant(&self) -> ScalarVariant {
+                match self {
+                    $( Self::$variant_name(_) => ScalarVariant::$variant_name, )*
+                }
+            }
+        }
     };
 }
 
@@ -566,6 +610,7 @@ impl std::hash::Hash for ScalarImpl {
                     Self::Utf8(s) => s.hash(state),
                     Self::Decimal(decimal) => decimal.hash(state),
                     Self::Interval(interval) => interval.hash(state),
+                    Self::Bytea(bytes) => bytes.hash(state),
                     Self::NaiveDate(naivedate) => naivedate.hash(state),
                     Self::NaiveDateTime(naivedatetime) => naivedatetime.hash(state),
                     Self::NaiveTime(naivetime) => naivetime.hash(state),
@@ -578,13 +623,32 @@ impl std::hash::Hash for ScalarImpl {
     }
 }
 
+/// Formats the scalar `$inner` of the variant `$variant_name` with its own `Display`, except that
+/// binary strings are formatted in the hex format of PostgreSQL, e.g. `\xdeadbeef`.
+macro_rules! display_scalar {
+    (Bytea, $inner:expr, $f:expr) => {
+        fmt_bytea($inner, $f)
+    };
+    ($variant_name:ident, $inner:expr, $f:expr) => {
+        Display::fmt($inner, $f)
+    };
+}
+
+fn fmt_bytea(bytes: &[u8], f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "\\x")?;
+    for byte in bytes {
+        write!(f, "{:02x}", byte)?;
+    }
+    Ok(())
+}
+
 impl Display for ScalarImpl {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         macro_rules! impl_display_fmt {
             ([], $( { $variant_name:ident, $suffix_name:ident, $scalar:ty, $scalar_ref:ty } ),*) => {
                 match self {
                     $( Self::$variant_name(ref inner) => {
-                        Display::fmt(inner, f)
+                        display_scalar!($variant_name, inner, f)
                     }, )*
                 }
             }
@@ -600,7 +664,7 @@ impl Display for ScalarRefImpl<'_> {
             ([], $( { $variant_name:ident, $suffix_name:ident, $scalar:ty, $scalar_ref:ty } ),*) => {
                 match self {
                     $( Self::$variant_name(inner) => {
-                        Display::fmt(inner, f)
+                        display_scalar!($variant_name, inner, f)
                     }, )*
                 }
             }
@@ -628,6 +692,7 @@ impl ScalarRefImpl<'_> {
                 ser.serialize_decimal(mantissa, scale)?;
             }
             Self::Interval(v) => v.serialize(ser)?,
+            &Self::Bytea(v) => serde::Serializer::serialize_bytes(&mut *ser, v)?,
             &Self::NaiveDate(v) => ser.serialize_naivedate(v.0.num_days_from_ce())?,
             &Self::NaiveDateTime(v) => {
                 ser.serialize_naivedatetime(v.0.timestamp(), v.0.timestamp_subsec_nanos())?
@@ -676,6 +741,7 @@ impl ScalarImpl {
                 }
             }),
             Ty::Interval => Self::Interval(IntervalUnit::deserialize(de)?),
+            Ty::Bytea => Self::Bytea(de.deserialize_bytes_owned()?.into_boxed_slice()),
             Ty::Time => Self::NaiveTime({
                 let (secs, nano) = de.deserialize_naivetime()?;
                 NaiveTimeWrapper::with_secs_nano(secs, nano)?
@@ -776,4 +842,24 @@ mod tests {
         assert!(decoded_floats.is_sorted());
         assert_eq!(floats, decoded_floats);
     }
+
+    #[test]
+    fn test_scalar_variant() {
+        assert_eq!(
+            ScalarImpl::Utf8("abc".to_string()).variant(),
+            DataType::Varchar.scalar_variant()
+        );
+        assert_eq!(
+            ScalarImpl::Int64(0).variant(),
+            DataType::Timestampz.scalar_variant()
+        );
+        assert_eq!(
+            ScalarImpl::Bytea(Box::new([])).variant(),
+            DataType::Bytea.scalar_variant()
+        );
+        assert_ne!(
+            ScalarImpl::Int32(0).variant(),
+            DataType::Int64.scalar_variant()
+        );
+    }
 }
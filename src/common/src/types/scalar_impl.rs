@@ -104,6 +104,30 @@ impl<'a> ScalarRef<'a> for &'a str {
     }
 }
 
+/// Implement `Scalar` for `Box<[u8]>`.
+/// `Box<[u8]>` could be converted to `&[u8]`.
+impl Scalar for Box<[u8]> {
+    type ScalarRefType<'a> = &'a [u8];
+
+    fn as_scalar_ref(&self) -> &[u8] {
+        self
+    }
+
+    fn to_scalar_value(self) -> ScalarImpl {
+        ScalarImpl::Bytea(self)
+    }
+}
+
+/// Implement `ScalarRef` for `Box<[u8]>`.
+/// `Box<[u8]>` could be converted to `&[u8]`.
+impl<'a> ScalarRef<'a> for &'a [u8] {
+    type ScalarType = Box<[u8]>;
+
+    fn to_owned_scalar(&self) -> Box<[u8]> {
+        (*self).into()
+    }
+}
+
 impl ScalarPartialOrd for Decimal {
     fn scalar_cmp(&self, other: Self) -> Option<std::cmp::Ordering> {
         self.partial_cmp(&other)
@@ -116,6 +140,12 @@ impl ScalarPartialOrd for String {
     }
 }
 
+impl ScalarPartialOrd for Box<[u8]> {
+    fn scalar_cmp(&self, other: &[u8]) -> Option<std::cmp::Ordering> {
+        self.as_ref().partial_cmp(other)
+    }
+}
+
 impl<T: PrimitiveArrayItemType + Scalar> ScalarPartialOrd for T {
     fn scalar_cmp(&self, other: Self) -> Option<std::cmp::Ordering> {
         self.partial_cmp(&other)
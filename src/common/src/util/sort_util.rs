@@ -187,6 +187,7 @@ pub fn compare_two_row(
                 Bool,
                 Decimal,
                 Interval,
+                Bytea,
                 NaiveDate,
                 NaiveDateTime,
                 NaiveTime
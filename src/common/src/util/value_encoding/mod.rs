@@ -55,6 +55,7 @@ fn serialize_value(value: ScalarRefImpl, mut buf: impl BufMut) {
         ScalarRefImpl::Bool(v) => buf.put_u8(v as u8),
         ScalarRefImpl::Decimal(v) => serialize_decimal(&v, buf),
         ScalarRefImpl::Interval(v) => serialize_interval(&v, buf),
+        ScalarRefImpl::Bytea(v) => serialize_str(v, buf),
         ScalarRefImpl::NaiveDate(v) => serialize_naivedate(v.0.num_days_from_ce(), buf),
         ScalarRefImpl::NaiveDateTime(v) => {
             serialize_naivedatetime(v.0.timestamp(), v.0.timestamp_subsec_nanos(), buf)
@@ -108,6 +109,7 @@ fn deserialize_value(ty: &DataType, mut data: impl Buf) -> Result<Datum> {
         DataType::Boolean => ScalarImpl::Bool(deserialize_bool(data)?),
         DataType::Decimal => ScalarImpl::Decimal(deserialize_decimal(data)?),
         DataType::Interval => ScalarImpl::Interval(deserialize_interval(data)?),
+        DataType::Bytea => ScalarImpl::Bytea(deserialize_bytes(data).into_boxed_slice()),
         DataType::Time => ScalarImpl::NaiveTime(deserialize_naivetime(data)?),
         DataType::Timestamp => ScalarImpl::NaiveDateTime(deserialize_naivedatetime(data)?),
         DataType::Timestampz => ScalarImpl::Int64(data.get_i64_le()),
@@ -118,11 +120,16 @@ fn deserialize_value(ty: &DataType, mut data: impl Buf) -> Result<Datum> {
     }))
 }
 
-fn deserialize_str(mut data: impl Buf) -> Result<String> {
+fn deserialize_str(data: impl Buf) -> Result<String> {
+    let bytes = deserialize_bytes(data);
+    Ok(String::from_utf8(bytes).map_err(ValueEncodingError::InvalidUtf8)?)
+}
+
+fn deserialize_bytes(mut data: impl Buf) -> Vec<u8> {
     let len = data.get_u32_le();
     let mut bytes = vec![0; len as usize];
     data.copy_to_slice(&mut bytes);
-    Ok(String::from_utf8(bytes).map_err(ValueEncodingError::InvalidUtf8)?)
+    bytes
 }
 
 fn deserialize_bool(mut data: impl Buf) -> Result<bool> {
@@ -103,6 +103,7 @@ fn literal_type_match(return_type: &DataType, literal: Option<&ScalarImpl>) -> b
                     | (DataType::Timestamp, ScalarImpl::NaiveDateTime(_))
                     | (DataType::Decimal, ScalarImpl::Decimal(_))
                     | (DataType::Interval, ScalarImpl::Interval(_))
+                    | (DataType::Bytea, ScalarImpl::Bytea(_))
                     | (DataType::Struct { .. }, ScalarImpl::Struct(_))
             )
         }
@@ -228,6 +229,7 @@ impl<'a> TryFrom<&'a ExprNode> for LiteralExpression {
                         prost.get_return_type()?.get_interval_type()?,
                     )?)
                 }
+                TypeName::Bytea => ScalarImpl::Bytea(prost_value.get_body().as_slice().into()),
                 _ => {
                     return Err(InternalError(format!(
                         "Unrecognized type name: {:?}",
@@ -309,37 +309,5 @@ impl Binder {
 }
 
 pub fn bind_data_type(data_type: &AstDataType) -> Result<DataType> {
-    let data_type = match data_type {
-        AstDataType::Boolean => DataType::Boolean,
-        AstDataType::SmallInt(None) => DataType::Int16,
-        AstDataType::Int(None) => DataType::Int32,
-        AstDataType::BigInt(None) => DataType::Int64,
-        AstDataType::Real | AstDataType::Float(Some(1..=24)) => DataType::Float32,
-        AstDataType::Double | AstDataType::Float(Some(25..=53) | None) => DataType::Float64,
-        AstDataType::Decimal(None, None) => DataType::Decimal,
-        AstDataType::Varchar(_) => DataType::Varchar,
-        AstDataType::Date => DataType::Date,
-        AstDataType::Time(false) => DataType::Time,
-        AstDataType::Timestamp(false) => DataType::Timestamp,
-        AstDataType::Timestamp(true) => DataType::Timestampz,
-        AstDataType::Interval => DataType::Interval,
-        AstDataType::Array(datatype) => DataType::List {
-            datatype: Box::new(bind_data_type(datatype)?),
-        },
-        AstDataType::Char(..) => {
-            return Err(ErrorCode::NotImplemented(
-                "CHAR is not supported, please use VARCHAR instead\n".to_string(),
-                None.into(),
-            )
-            .into())
-        }
-        _ => {
-            return Err(ErrorCode::NotImplemented(
-                format!("unsupported data type: {:?}", data_type),
-                None.into(),
-            )
-            .into())
-        }
-    };
-    Ok(data_type)
+    data_type.to_data_type()
 }
@@ -15,7 +15,10 @@
 use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
-use risingwave_common::catalog::{ColumnDesc, OrderedColumnDesc, TableDesc};
+use risingwave_common::catalog::{
+    ColumnDesc, DatabaseId as CommonDatabaseId, OrderedColumnDesc, SchemaId as CommonSchemaId,
+    TableDesc,
+};
 use risingwave_common::util::sort_util::OrderType;
 use risingwave_pb::catalog::table::OptionalAssociatedSourceId;
 use risingwave_pb::catalog::Table as ProstTable;
@@ -29,6 +32,12 @@ use crate::catalog::TableId;
 pub struct TableCatalog {
     pub id: TableId,
 
+    /// Schema and database of the table. They're not assigned until the table is created, see
+    /// `to_prost`.
+    pub schema_id: SchemaId,
+
+    pub database_id: DatabaseId,
+
     pub associated_source_id: Option<TableId>, // TODO: use SourceId
 
     pub name: String,
@@ -47,6 +56,9 @@ pub struct TableCatalog {
 
     /// If set to Some(TableId), then this table is an index on another table.
     pub is_index_on: Option<TableId>,
+
+    /// Whether rows are only appended to this table, i.e. never updated or deleted.
+    pub appendonly: bool,
 }
 
 impl TableCatalog {
@@ -75,10 +87,15 @@ impl TableCatalog {
     pub fn table_desc(&self) -> TableDesc {
         TableDesc {
             table_id: self.id,
+            schema_id: CommonSchemaId::new(
+                CommonDatabaseId::new(self.database_id as i32),
+                self.schema_id as i32,
+            ),
             order_desc: self.order_desc.clone(),
             pks: self.pks.clone(),
             columns: self.columns.iter().map(|c| c.column_desc.clone()).collect(),
             distribution_keys: self.distribution_keys.clone(),
+            appendonly: self.appendonly,
         }
     }
 
@@ -123,6 +140,7 @@ impl TableCatalog {
                 .iter()
                 .map(|k| *k as i32)
                 .collect_vec(),
+            appendonly: self.appendonly,
         }
     }
 }
@@ -165,6 +183,8 @@ impl From<ProstTable> for TableCatalog {
 
         Self {
             id: id.into(),
+            schema_id: tb.schema_id,
+            database_id: tb.database_id,
             associated_source_id: associated_source_id.map(Into::into),
             name,
             order_desc,
@@ -180,6 +200,7 @@ impl From<ProstTable> for TableCatalog {
                 .map(|k| *k as usize)
                 .collect_vec(),
             pks: tb.pk.iter().map(|x| *x as _).collect(),
+            appendonly: tb.appendonly,
         }
     }
 }
@@ -212,8 +233,8 @@ mod tests {
             is_index: false,
             index_on_id: 0,
             id: 0,
-            schema_id: 0,
-            database_id: 0,
+            schema_id: 1,
+            database_id: 2,
             name: "test".to_string(),
             columns: vec![
                 ProstColumnCatalog {
@@ -246,6 +267,7 @@ mod tests {
             orders: vec![OrderType::Ascending.to_prost() as i32],
             dependent_relations: vec![],
             distribution_keys: vec![],
+            appendonly: false,
             optional_associated_source_id: OptionalAssociatedSourceId::AssociatedSourceId(233)
                 .into(),
         }
@@ -256,6 +278,8 @@ mod tests {
             TableCatalog {
                 is_index_on: None,
                 id: TableId::new(0),
+                schema_id: 1,
+                database_id: 2,
                 associated_source_id: Some(TableId::new(233)),
                 name: "test".to_string(),
                 columns: vec![
@@ -294,6 +318,7 @@ mod tests {
                     order: OrderType::Ascending
                 }],
                 distribution_keys: vec![],
+                appendonly: false,
             }
         );
     }
@@ -90,6 +90,7 @@ fn literal_to_protobuf(d: &Datum) -> Option<RexNode> {
         ScalarImpl::Bool(v) => (*v as i8).to_be_bytes().to_vec(),
         ScalarImpl::Decimal(v) => v.to_string().as_bytes().to_vec(),
         ScalarImpl::Interval(v) => v.to_protobuf_owned(),
+        ScalarImpl::Bytea(v) => v.to_vec(),
         ScalarImpl::NaiveDate(_) => todo!(),
         ScalarImpl::NaiveDateTime(_) => todo!(),
         ScalarImpl::NaiveTime(_) => todo!(),
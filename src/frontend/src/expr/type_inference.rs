@@ -41,6 +41,7 @@ enum DataTypeName {
     Timestampz,
     Time,
     Interval,
+    Bytea,
     Struct,
     List,
 }
@@ -60,6 +61,7 @@ fn name_of(ty: &DataType) -> DataTypeName {
         DataType::Timestampz => DataTypeName::Timestampz,
         DataType::Time => DataTypeName::Time,
         DataType::Interval => DataTypeName::Interval,
+        DataType::Bytea => DataTypeName::Bytea,
         DataType::Struct { .. } => DataTypeName::Struct,
         DataType::List { .. } => DataTypeName::List,
     }
@@ -85,6 +87,7 @@ pub fn infer_type(func_type: ExprType, inputs_type: Vec<DataType>) -> Result<Dat
         DataTypeName::Timestampz => DataType::Timestampz,
         DataTypeName::Time => DataType::Time,
         DataTypeName::Interval => DataType::Interval,
+        DataTypeName::Bytea => DataType::Bytea,
         DataTypeName::Struct => DataType::Struct {
             fields: Arc::new([]),
         },
@@ -25,7 +25,6 @@ use risingwave_pb::plan_common::ColumnCatalog;
 use risingwave_sqlparser::ast::{ColumnDef, ObjectName};
 
 use super::create_source::make_prost_source;
-use crate::binder::expr::bind_data_type;
 use crate::catalog::{check_valid_column_name, row_id_column_desc};
 use crate::optimizer::plan_node::{LogicalSource, StreamSource};
 use crate::optimizer::property::{Distribution, Order};
@@ -44,7 +43,9 @@ pub fn bind_sql_columns(columns: Vec<ColumnDef>) -> Result<Vec<ColumnCatalog>> {
         for (i, column) in columns.into_iter().enumerate() {
             check_valid_column_name(&column.name.value)?;
             column_descs.push(ColumnDesc {
-                data_type: bind_data_type(&column.data_type)?,
+                data_type: column
+                    .data_type
+                    .to_data_type_for_column(&column.name.value)?,
                 column_id: ColumnId::new((i + 1) as i32),
                 name: column.name.value,
                 field_descs: vec![],
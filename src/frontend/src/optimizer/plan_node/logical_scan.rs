@@ -17,7 +17,7 @@ use std::fmt;
 use std::rc::Rc;
 
 use itertools::Itertools;
-use risingwave_common::catalog::{ColumnDesc, Schema, TableDesc};
+use risingwave_common::catalog::{ColumnDesc, Schema, SchemaId, TableDesc};
 use risingwave_common::error::Result;
 
 use super::{ColPrunable, PlanBase, PlanRef, StreamTableScan, ToBatch, ToStream};
@@ -119,6 +119,11 @@ impl LogicalScan {
         self.table_desc.as_ref()
     }
 
+    /// Get a reference to the schema of the logical scan's table.
+    pub fn schema_id(&self) -> &SchemaId {
+        &self.table_desc.schema_id
+    }
+
     /// Get a reference to the logical scan's table desc.
     #[must_use]
     pub fn column_descs(&self) -> Vec<ColumnDesc> {
@@ -37,13 +37,13 @@ impl StreamIndexScan {
         let ctx = logical.base.ctx.clone();
 
         let batch_plan_id = ctx.next_plan_node_id();
-        // TODO: derive from input
         let base = PlanBase::new_stream(
             ctx,
             logical.schema().clone(),
             logical.base.pk_indices.clone(),
+            // follows the distribution of the index from its TableCatalog
             Distribution::HashShard(logical.map_distribution_keys()),
-            false, // TODO: determine the `append-only` field of table scan
+            logical.table_desc().appendonly,
         );
         Self {
             base,
@@ -89,7 +89,7 @@ impl StreamIndexScan {
         let batch_plan_node = BatchPlanNode {
             table_ref_id: Some(TableRefId {
                 table_id: self.logical.table_desc().table_id.table_id as i32,
-                schema_ref_id: Default::default(),
+                schema_ref_id: Some(self.logical.schema_id().into()),
             }),
             column_descs: self
                 .schema()
@@ -119,12 +119,26 @@ impl StreamIndexScan {
 
         let pk_indices = self.base.pk_indices.iter().map(|x| *x as u32).collect_vec();
 
+        // The fields from upstream
+        let upstream_fields = self
+            .logical
+            .table_desc()
+            .columns
+            .iter()
+            .map(|x| Field {
+                data_type: Some(x.data_type.to_protobuf()),
+                name: x.name.clone(),
+            })
+            .collect_vec();
+
         ProstStreamPlan {
             fields: self.schema().to_prost(),
             input: vec![
-                // The merge node should be empty
+                // The merge node should be empty, and it receives the whole rows of the upstream
+                // materialize.
                 ProstStreamPlan {
                     node_body: Some(ProstStreamNode::Merge(Default::default())),
+                    fields: upstream_fields.clone(),
                     ..Default::default()
                 },
                 ProstStreamPlan {
@@ -137,7 +151,7 @@ impl StreamIndexScan {
                     identity: if auto_fields { "BatchPlanNode" } else { "" }.into(),
                     pk_indices: pk_indices.clone(),
                     input: vec![],
-                    fields: vec![], // TODO: fill this later
+                    fields: self.schema().to_prost(),
                     append_only: true,
                 },
             ],
@@ -146,19 +160,9 @@ impl StreamIndexScan {
                 disable_rearrange: true,
                 table_ref_id: Some(TableRefId {
                     table_id: self.logical.table_desc().table_id.table_id as i32,
-                    schema_ref_id: None, // TODO: fill schema ref id
+                    schema_ref_id: Some(self.logical.schema_id().into()),
                 }),
-                // The fields from upstream
-                upstream_fields: self
-                    .logical
-                    .table_desc()
-                    .columns
-                    .iter()
-                    .map(|x| Field {
-                        data_type: Some(x.data_type.to_protobuf()),
-                        name: x.name.clone(),
-                    })
-                    .collect(),
+                upstream_fields,
                 // The column idxs need to be forwarded to the downstream
                 column_ids: self
                     .logical
@@ -182,3 +186,231 @@ impl StreamIndexScan {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use risingwave_common::catalog::{
+        ColumnDesc, DatabaseId, OrderedColumnDesc, SchemaId, TableDesc,
+    };
+    use risingwave_common::types::DataType;
+    use risingwave_common::util::sort_util::OrderType;
+    use risingwave_pb::plan_common::{DatabaseRefId, Field, SchemaRefId};
+
+    use super::*;
+    use crate::optimizer::plan_node::ToStream;
+    use crate::session::OptimizerContext;
+
+    #[tokio::test]
+    async fn test_append_only_index() {
+        let ctx = OptimizerContext::mock().await;
+        let index = |appendonly| {
+            Rc::new(TableDesc {
+                table_id: 1.into(),
+                columns: vec![
+                    ColumnDesc::unnamed(0.into(), DataType::Int32),
+                    ColumnDesc::unnamed(1.into(), DataType::Int64),
+                ],
+                distribution_keys: vec![0],
+                pks: vec![1],
+                appendonly,
+                ..Default::default()
+            })
+        };
+
+        for appendonly in [true, false] {
+            let logical = LogicalScan::new(
+                "index".to_string(),
+                vec![0, 1],
+                index(appendonly),
+                vec![],
+                ctx.clone(),
+            );
+            let index_scan = StreamIndexScan::new(logical);
+            assert_eq!(index_scan.append_only(), appendonly);
+            assert_eq!(
+                index_scan.adhoc_to_stream_prost(false).append_only,
+                appendonly
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_schema_ref_id() {
+        let ctx = OptimizerContext::mock().await;
+        let index = Rc::new(TableDesc {
+            table_id: 1.into(),
+            schema_id: SchemaId::new(DatabaseId::new(2), 3),
+            columns: vec![
+                ColumnDesc::unnamed(0.into(), DataType::Int32),
+                ColumnDesc::unnamed(1.into(), DataType::Int64),
+            ],
+            distribution_keys: vec![0],
+            pks: vec![1],
+            ..Default::default()
+        });
+        let logical = LogicalScan::new("index".to_string(), vec![0, 1], index, vec![], ctx);
+        let prost = StreamIndexScan::new(logical).adhoc_to_stream_prost(false);
+
+        let schema_ref_id = Some(SchemaRefId {
+            database_ref_id: Some(DatabaseRefId { database_id: 2 }),
+            schema_id: 3,
+        });
+        match &prost.node_body {
+            Some(ProstStreamNode::Chain(chain)) => assert_eq!(
+                chain.table_ref_id.as_ref().unwrap().schema_ref_id,
+                schema_ref_id
+            ),
+            _ => panic!("expect chain node"),
+        }
+        match &prost.input[1].node_body {
+            Some(ProstStreamNode::BatchPlan(batch_plan)) => assert_eq!(
+                batch_plan.table_ref_id.as_ref().unwrap().schema_ref_id,
+                schema_ref_id
+            ),
+            _ => panic!("expect batch plan node"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fields() {
+        let ctx = OptimizerContext::mock().await;
+        let column = |column_id: i32, data_type, name: &str| ColumnDesc {
+            name: name.to_string(),
+            ..ColumnDesc::unnamed(column_id.into(), data_type)
+        };
+        let index = Rc::new(TableDesc {
+            table_id: 1.into(),
+            columns: vec![
+                column(0, DataType::Int32, "v1"),
+                column(1, DataType::Varchar, "v2"),
+                column(2, DataType::Int64, "_row_id"),
+            ],
+            distribution_keys: vec![0],
+            pks: vec![2],
+            ..Default::default()
+        });
+        let logical = LogicalScan::new("index".to_string(), vec![0, 2], index, vec![], ctx);
+        let index_scan = StreamIndexScan::new(logical);
+        let prost = index_scan.adhoc_to_stream_prost(false);
+
+        let names_and_types = |fields: &[Field]| {
+            fields
+                .iter()
+                .map(|field| {
+                    (
+                        field.name.clone(),
+                        DataType::from(field.data_type.as_ref().unwrap()),
+                    )
+                })
+                .collect_vec()
+        };
+        let expected = vec![
+            ("v1".to_string(), DataType::Int32),
+            ("_row_id".to_string(), DataType::Int64),
+        ];
+        assert_eq!(
+            index_scan
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| (field.name.clone(), field.data_type()))
+                .collect_vec(),
+            expected
+        );
+        assert_eq!(names_and_types(&prost.fields), expected);
+        assert_eq!(names_and_types(&prost.input[1].fields), expected);
+        // The merge node receives the whole rows of the index.
+        assert_eq!(
+            names_and_types(&prost.input[0].fields),
+            vec![
+                ("v1".to_string(), DataType::Int32),
+                ("v2".to_string(), DataType::Varchar),
+                ("_row_id".to_string(), DataType::Int64),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pruned_columns() {
+        let ctx = OptimizerContext::mock().await;
+        let index = Rc::new(TableDesc {
+            table_id: 1.into(),
+            columns: (0..5)
+                .map(|column_id| ColumnDesc::unnamed(column_id.into(), DataType::Int32))
+                .collect(),
+            order_desc: vec![OrderedColumnDesc {
+                column_desc: ColumnDesc::unnamed(4.into(), DataType::Int32),
+                order: OrderType::Ascending,
+            }],
+            distribution_keys: vec![1],
+            pks: vec![4],
+            ..Default::default()
+        });
+        let check_columns = |index_scan: &StreamIndexScan| {
+            assert_eq!(index_scan.pk_indices(), &[2]);
+            let prost = index_scan.adhoc_to_stream_prost(false);
+            match &prost.node_body {
+                Some(ProstStreamNode::Chain(chain)) => assert_eq!(chain.column_ids, vec![1, 3, 4]),
+                _ => panic!("expect chain node"),
+            }
+            match &prost.input[1].node_body {
+                Some(ProstStreamNode::BatchPlan(batch_plan)) => assert_eq!(
+                    batch_plan
+                        .column_descs
+                        .iter()
+                        .map(|column_desc| column_desc.column_id)
+                        .collect_vec(),
+                    vec![1, 3, 4]
+                ),
+                _ => panic!("expect batch plan node"),
+            }
+        };
+
+        // Two of the five columns of the index are required, along with the pk.
+        let logical = LogicalScan::new(
+            "index".to_string(),
+            vec![1, 3, 4],
+            index.clone(),
+            vec![],
+            ctx.clone(),
+        );
+        check_columns(&StreamIndexScan::new(logical));
+
+        // The pk is kept for the stream even if only the two columns are required.
+        let logical = LogicalScan::new("index".to_string(), vec![1, 3], index, vec![], ctx);
+        let (plan, _) = logical.logical_rewrite_for_stream().unwrap();
+        let logical = plan.as_logical_scan().unwrap().clone();
+        check_columns(&StreamIndexScan::new(logical));
+    }
+
+    #[tokio::test]
+    async fn test_distribution() {
+        let ctx = OptimizerContext::mock().await;
+        let index = Rc::new(TableDesc {
+            table_id: 1.into(),
+            columns: (0..5)
+                .map(|column_id| ColumnDesc::unnamed(column_id.into(), DataType::Int32))
+                .collect(),
+            distribution_keys: vec![3, 1],
+            pks: vec![4],
+            ..Default::default()
+        });
+        let logical = LogicalScan::new("index".to_string(), vec![1, 3, 4], index, vec![], ctx);
+        let index_scan = StreamIndexScan::new(logical);
+        // The distribution keys of the index are mapped to the output columns.
+        assert_eq!(
+            index_scan.distribution(),
+            &Distribution::HashShard(vec![1, 0])
+        );
+        let prost = index_scan.adhoc_to_stream_prost(false);
+
+        match &prost.input[1].node_body {
+            Some(ProstStreamNode::BatchPlan(batch_plan)) => {
+                assert_eq!(batch_plan.distribution_keys, vec![1, 0])
+            }
+            _ => panic!("expect batch plan node"),
+        }
+    }
+}
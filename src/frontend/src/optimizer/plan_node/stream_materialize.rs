@@ -169,6 +169,8 @@ impl StreamMaterialize {
 
         let table = TableCatalog {
             id: TableId::placeholder(),
+            schema_id: 0,
+            database_id: 0,
             associated_source_id: None,
             name: mv_name,
             columns,
@@ -176,6 +178,7 @@ impl StreamMaterialize {
             pks: pk_indices.clone(),
             is_index_on,
             distribution_keys: base.dist.dist_column_indices().to_vec(),
+            appendonly: base.append_only,
         };
 
         Ok(Self { base, input, table })
@@ -44,7 +44,7 @@ impl StreamTableScan {
             logical.base.pk_indices.clone(),
             // follows upstream distribution from TableCatalog
             Distribution::HashShard(logical.map_distribution_keys()),
-            false, // TODO: determine the `append-only` field of table scan
+            logical.table_desc().appendonly,
         );
         Self {
             base,
@@ -94,7 +94,7 @@ impl StreamTableScan {
         let batch_plan_node = BatchPlanNode {
             table_ref_id: Some(TableRefId {
                 table_id: self.logical.table_desc().table_id.table_id as i32,
-                schema_ref_id: Default::default(),
+                schema_ref_id: Some(self.logical.schema_id().into()),
             }),
             column_descs: self
                 .schema()
@@ -151,7 +151,7 @@ impl StreamTableScan {
                 disable_rearrange: false,
                 table_ref_id: Some(TableRefId {
                     table_id: self.logical.table_desc().table_id.table_id as i32,
-                    schema_ref_id: None, // TODO: fill schema ref id
+                    schema_ref_id: Some(self.logical.schema_id().into()),
                 }),
                 // The fields from upstream
                 upstream_fields: self
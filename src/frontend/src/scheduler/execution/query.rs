@@ -404,6 +404,7 @@ mod tests {
             vec![0, 1],
             Rc::new(TableDesc {
                 table_id: 0.into(),
+                schema_id: Default::default(),
                 pks: vec![],
                 order_desc: vec![],
                 columns: vec![
@@ -423,6 +424,7 @@ mod tests {
                     },
                 ],
                 distribution_keys: vec![],
+                appendonly: false,
             }),
             vec![],
             ctx,
@@ -14,10 +14,14 @@
 
 use std::sync::Arc;
 
-use risingwave_pb::hummock::Level;
+use bytes::Bytes;
+use risingwave_hummock_sdk::key::{user_key, FullKey};
+use risingwave_hummock_sdk::key_range::KeyRange;
+use risingwave_hummock_sdk::HummockEpoch;
+use risingwave_pb::hummock::{Level, SstableInfo};
 
 use crate::hummock::compaction::overlap_strategy::OverlapStrategy;
-use crate::hummock::compaction::SearchResult;
+use crate::hummock::compaction::{sst_below_epoch, CompactionConfig, SearchResult};
 use crate::hummock::level_handler::LevelHandler;
 
 pub trait CompactionPicker {
@@ -28,6 +32,73 @@ pub trait CompactionPicker {
     ) -> Option<SearchResult>;
 }
 
+/// Splits the key space at up to `parallelism - 1` points inside `key_range`, e.g. the combined key
+/// range of the input SSTs of a task, into contiguous sub-ranges for compactors to compact in
+/// parallel. The split points are interpolated between the user keys of both ends, read as
+/// big-endian numbers after their common prefix, so the same range is always split the same way.
+/// Like the splits generated by pickers, the outermost bounds are empty (unbounded), so no input
+/// key falls outside of the splits. Fewer splits are returned if both ends are too close to be
+/// split further.
+pub fn split_key_range(key_range: &KeyRange, parallelism: usize) -> Vec<KeyRange> {
+    if parallelism <= 1 {
+        return vec![KeyRange::inf()];
+    }
+    let left = if key_range.left.is_empty() {
+        &[][..]
+    } else {
+        user_key(&key_range.left)
+    };
+    let right = if key_range.right.is_empty() {
+        None
+    } else {
+        Some(user_key(&key_range.right))
+    };
+    let prefix_len = match right {
+        Some(right) => left.iter().zip(right).take_while(|(a, b)| a == b).count(),
+        None => 0,
+    };
+    let left_num = key_suffix_to_u128(&left[prefix_len..]);
+    let right_num = match right {
+        Some(right) => key_suffix_to_u128(&right[prefix_len..]),
+        None => u128::MAX,
+    };
+    let width = right_num.saturating_sub(left_num);
+    let n = parallelism as u128;
+
+    let mut splits = Vec::with_capacity(parallelism);
+    let mut split_left = Bytes::new();
+    let mut last_offset = 0;
+    for i in 1..n {
+        let offset = width / n * i + width % n * i / n;
+        // Boundaries grow with the offset, so skip the ones that would make an empty split.
+        if offset <= last_offset {
+            continue;
+        }
+        last_offset = offset;
+        let suffix = (left_num + offset).to_be_bytes();
+        let suffix_len = suffix
+            .iter()
+            .rposition(|b| *b != 0)
+            .map_or(0, |pos| pos + 1);
+        let mut boundary_user_key = left[..prefix_len].to_vec();
+        boundary_user_key.extend_from_slice(&suffix[..suffix_len]);
+        let boundary =
+            Bytes::from(FullKey::from_user_key(boundary_user_key, HummockEpoch::MAX).into_inner());
+        splits.push(KeyRange::new(split_left, boundary.clone()));
+        split_left = boundary;
+    }
+    splits.push(KeyRange::new(split_left, Bytes::new()));
+    splits
+}
+
+/// Reads the first 16 bytes of `suffix` as a big-endian number, padded with zeros if shorter.
+fn key_suffix_to_u128(suffix: &[u8]) -> u128 {
+    let mut buf = [0; 16];
+    let len = suffix.len().min(buf.len());
+    buf[..len].copy_from_slice(&suffix[..len]);
+    u128::from_be_bytes(buf)
+}
+
 pub struct MinOverlappingPicker {
     compact_task_id: u64,
     overlap_strategy: Arc<dyn OverlapStrategy>,
@@ -106,13 +177,184 @@ impl CompactionPicker for MinOverlappingPicker {
     }
 }
 
+/// Picks the longest run of idle SSTs in a non-L0 level to merge them into the same level, which
+/// reduces the number of small files left by previous compactions without pushing data down. The
+/// run is bounded by `max_compaction_bytes`.
+pub struct IntraLevelPicker {
+    compact_task_id: u64,
+    level: usize,
+    config: Arc<CompactionConfig>,
+}
+
+impl IntraLevelPicker {
+    pub fn new(
+        compact_task_id: u64,
+        level: usize,
+        config: Arc<CompactionConfig>,
+    ) -> IntraLevelPicker {
+        IntraLevelPicker {
+            compact_task_id,
+            level,
+            config,
+        }
+    }
+}
+
+impl CompactionPicker for IntraLevelPicker {
+    fn pick_compaction(
+        &self,
+        levels: &[Level],
+        level_handlers: &mut [LevelHandler],
+    ) -> Option<SearchResult> {
+        let mut select_input_ssts = vec![];
+        let mut run = vec![];
+        let mut run_size = 0;
+        for table in &levels[self.level].table_infos {
+            let is_pending = level_handlers[self.level].is_pending_compact(&table.id);
+            // The run is broken by a compacting SST or the SST that would take it over
+            // `max_compaction_bytes`.
+            if is_pending
+                || (!run.is_empty()
+                    && run_size + table.file_size > self.config.max_compaction_bytes)
+            {
+                if run.len() > select_input_ssts.len() {
+                    select_input_ssts = std::mem::take(&mut run);
+                } else {
+                    run.clear();
+                }
+                run_size = 0;
+            }
+            if !is_pending {
+                run_size += table.file_size;
+                run.push(table.clone());
+            }
+        }
+        if run.len() > select_input_ssts.len() {
+            select_input_ssts = run;
+        }
+        if select_input_ssts.len() < 2 {
+            return None;
+        }
+        level_handlers[self.level].add_pending_task(self.compact_task_id, &select_input_ssts);
+        Some(SearchResult {
+            select_level: Level {
+                level_idx: self.level as u32,
+                level_type: levels[self.level].level_type,
+                table_infos: select_input_ssts,
+            },
+            target_level: Level {
+                level_idx: self.level as u32,
+                level_type: levels[self.level].level_type,
+                table_infos: vec![],
+            },
+            split_ranges: vec![KeyRange::inf()],
+        })
+    }
+}
+
+/// Picks the SST whose compaction is estimated to reclaim the most space. Versions below
+/// `safe_epoch` are no longer visible to any reader except the latest one of each key, so deleted
+/// and overwritten data in these SSTs can be dropped physically by compaction.
+pub struct SpaceReclaimPicker {
+    compact_task_id: u64,
+    overlap_strategy: Arc<dyn OverlapStrategy>,
+    level: usize,
+    safe_epoch: HummockEpoch,
+}
+
+impl SpaceReclaimPicker {
+    pub fn new(
+        compact_task_id: u64,
+        level: usize,
+        safe_epoch: HummockEpoch,
+        overlap_strategy: Arc<dyn OverlapStrategy>,
+    ) -> SpaceReclaimPicker {
+        SpaceReclaimPicker {
+            compact_task_id,
+            overlap_strategy,
+            level,
+            safe_epoch,
+        }
+    }
+
+    /// Estimates the reclaimable bytes of an SST by its size if its `max_epoch` is below
+    /// `safe_epoch`, which means no snapshot reads the data in it any more. Otherwise, including
+    /// when its max epoch is unknown, nothing can be reclaimed.
+    fn estimate_reclaimable_bytes(&self, table: &SstableInfo) -> u64 {
+        if sst_below_epoch(table, self.safe_epoch) {
+            table.file_size
+        } else {
+            0
+        }
+    }
+}
+
+impl CompactionPicker for SpaceReclaimPicker {
+    fn pick_compaction(
+        &self,
+        levels: &[Level],
+        level_handlers: &mut [LevelHandler],
+    ) -> Option<SearchResult> {
+        let target_level = self.level + 1;
+        let mut candidates = vec![];
+        for table in &levels[self.level].table_infos {
+            if level_handlers[self.level].is_pending_compact(&table.id) {
+                continue;
+            }
+            let overlap_files = self
+                .overlap_strategy
+                .check_base_level_overlap(&[table.clone()], &levels[target_level].table_infos);
+            if overlap_files
+                .iter()
+                .any(|other| level_handlers[target_level].is_pending_compact(&other.id))
+            {
+                continue;
+            }
+            let reclaimable_bytes = self.estimate_reclaimable_bytes(table)
+                + overlap_files
+                    .iter()
+                    .map(|other| self.estimate_reclaimable_bytes(other))
+                    .sum::<u64>();
+            if reclaimable_bytes > 0 {
+                candidates.push((reclaimable_bytes, table.clone(), overlap_files));
+            }
+        }
+        // Prefer the candidate reclaiming the most bytes, and the earlier one in the level among
+        // candidates reclaiming the same.
+        let (_, table, target_input_ssts) = candidates
+            .into_iter()
+            .rev()
+            .max_by_key(|(reclaimable_bytes, ..)| *reclaimable_bytes)?;
+        let select_input_ssts = vec![table];
+        level_handlers[self.level].add_pending_task(self.compact_task_id, &select_input_ssts);
+        if !target_input_ssts.is_empty() {
+            level_handlers[target_level].add_pending_task(self.compact_task_id, &target_input_ssts);
+        }
+        Some(SearchResult {
+            select_level: Level {
+                level_idx: self.level as u32,
+                level_type: levels[self.level].level_type,
+                table_infos: select_input_ssts,
+            },
+            target_level: Level {
+                level_idx: target_level as u32,
+                level_type: levels[target_level].level_type,
+                table_infos: target_input_ssts,
+            },
+            split_ranges: vec![],
+        })
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
+    use risingwave_hummock_sdk::VersionedComparator;
     use risingwave_pb::hummock::LevelType;
 
     use super::*;
     use crate::hummock::compaction::overlap_strategy::RangeOverlapStrategy;
     use crate::hummock::compaction::tier_compaction_picker::tests::generate_table;
+    use crate::hummock::test_utils::iterator_test_key_of_epoch;
 
     #[test]
     fn test_compact_l1() {
@@ -181,4 +423,217 @@ pub mod tests {
         assert_eq!(ret.target_level.table_infos[0].id, 5);
         assert_eq!(ret.target_level.table_infos[1].id, 6);
     }
+
+    #[test]
+    fn test_intra_level_picker_max_compaction_bytes() {
+        let config = CompactionConfig {
+            max_compaction_bytes: 5,
+            ..Default::default()
+        };
+        let picker = IntraLevelPicker::new(0, 1, Arc::new(config));
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: (0..5)
+                    .map(|sst_id| {
+                        let mut table = generate_table(
+                            sst_id,
+                            1,
+                            sst_id as usize * 10,
+                            sst_id as usize * 10 + 5,
+                            1,
+                        );
+                        table.file_size = 2;
+                        table
+                    })
+                    .collect(),
+            },
+        ];
+        let mut levels_handler = vec![LevelHandler::new(0), LevelHandler::new(1)];
+
+        // A third SST would take the run over `max_compaction_bytes`.
+        let ret = picker
+            .pick_compaction(&levels, &mut levels_handler)
+            .unwrap();
+        assert_eq!(ret.select_level.level_idx, 1);
+        assert_eq!(ret.target_level.level_idx, 1);
+        assert_eq!(
+            ret.select_level
+                .table_infos
+                .iter()
+                .map(|table| table.id)
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+
+        let ret = picker
+            .pick_compaction(&levels, &mut levels_handler)
+            .unwrap();
+        assert_eq!(ret.select_level.table_infos.len(), 2);
+        assert_eq!(ret.select_level.table_infos[0].id, 2);
+
+        // A single SST is not merged.
+        assert!(picker
+            .pick_compaction(&levels, &mut levels_handler)
+            .is_none());
+    }
+
+    #[test]
+    fn test_space_reclaim_picker() {
+        let picker = SpaceReclaimPicker::new(0, 1, 5, Arc::new(RangeOverlapStrategy::default()));
+        let mut obsolete_table = generate_table(0, 1, 0, 100, 1);
+        obsolete_table.file_size = 10;
+        let mut live_table = generate_table(1, 1, 101, 200, 10);
+        live_table.file_size = 100;
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![obsolete_table, live_table],
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![
+                    generate_table(2, 1, 0, 100, 1),
+                    generate_table(3, 1, 101, 200, 1),
+                ],
+            },
+        ];
+        let mut levels_handler = vec![
+            LevelHandler::new(0),
+            LevelHandler::new(1),
+            LevelHandler::new(2),
+        ];
+
+        // The obsolete table is picked though the live one is larger.
+        let ret = picker
+            .pick_compaction(&levels, &mut levels_handler)
+            .unwrap();
+        assert_eq!(ret.select_level.table_infos.len(), 1);
+        assert_eq!(ret.select_level.table_infos[0].id, 0);
+        assert_eq!(ret.target_level.table_infos.len(), 1);
+        assert_eq!(ret.target_level.table_infos[0].id, 2);
+
+        // The live table still reclaims the obsolete data in the lower level.
+        let ret = picker
+            .pick_compaction(&levels, &mut levels_handler)
+            .unwrap();
+        assert_eq!(ret.select_level.table_infos[0].id, 1);
+        assert_eq!(ret.target_level.table_infos[0].id, 3);
+
+        assert!(picker
+            .pick_compaction(&levels, &mut levels_handler)
+            .is_none());
+
+        // An SST holding a newer version of a key between its bounds, or one whose max epoch is
+        // unknown, is not obsolete.
+        let mut newer_table = generate_table(4, 1, 0, 100, 1);
+        newer_table.max_epoch = 8;
+        let mut unknown_table = generate_table(5, 1, 101, 200, 1);
+        unknown_table.max_epoch = 0;
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![newer_table, unknown_table],
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![],
+            },
+        ];
+        let mut levels_handler = vec![
+            LevelHandler::new(0),
+            LevelHandler::new(1),
+            LevelHandler::new(2),
+        ];
+        assert!(picker
+            .pick_compaction(&levels, &mut levels_handler)
+            .is_none());
+    }
+
+    fn assert_contiguous_splits(splits: &[KeyRange], parallelism: usize) {
+        assert!(!splits.is_empty() && splits.len() <= parallelism);
+        assert!(splits.first().unwrap().left.is_empty());
+        assert!(splits.last().unwrap().right.is_empty());
+        for pair in splits.windows(2) {
+            assert_eq!(pair[0].right, pair[1].left);
+        }
+        for split in &splits[1..] {
+            assert!(!split.left.is_empty());
+        }
+        for pair in splits[1..].windows(2) {
+            assert_eq!(
+                VersionedComparator::compare_key(&pair[0].left, &pair[1].left),
+                std::cmp::Ordering::Less
+            );
+        }
+    }
+
+    #[test]
+    fn test_split_key_range() {
+        let left = Bytes::from(iterator_test_key_of_epoch(1, 100, 1));
+        let right = Bytes::from(iterator_test_key_of_epoch(1, 400, 1));
+        let key_range = KeyRange::new(left.clone(), right.clone());
+        for parallelism in [2, 3, 4, 7] {
+            let splits = split_key_range(&key_range, parallelism);
+            assert_eq!(splits.len(), parallelism);
+            assert_contiguous_splits(&splits, parallelism);
+            // Split points lie strictly inside the range.
+            for split in &splits[1..] {
+                assert_eq!(
+                    VersionedComparator::compare_key(&left, &split.left),
+                    std::cmp::Ordering::Less
+                );
+                assert_ne!(
+                    VersionedComparator::compare_key(&split.left, &right),
+                    std::cmp::Ordering::Greater
+                );
+            }
+            // The same range is always split the same way.
+            assert_eq!(splits, split_key_range(&key_range, parallelism));
+        }
+        assert_eq!(split_key_range(&key_range, 1), vec![KeyRange::inf()]);
+
+        // Unbounded ends.
+        let splits = split_key_range(&KeyRange::inf(), 4);
+        assert_eq!(splits.len(), 4);
+        assert_contiguous_splits(&splits, 4);
+
+        // Ends too close to be split.
+        let key = Bytes::from(iterator_test_key_of_epoch(1, 100, 1));
+        let splits = split_key_range(&KeyRange::new(key.clone(), key), 3);
+        assert_eq!(splits.len(), 1);
+        assert_contiguous_splits(&splits, 3);
+        let mut right_user_key = b"a".to_vec();
+        right_user_key.extend_from_slice(&[0; 15]);
+        right_user_key.push(3);
+        let splits = split_key_range(
+            &KeyRange::new(
+                Bytes::from(FullKey::from_user_key(b"a".to_vec(), 1).into_inner()),
+                Bytes::from(FullKey::from_user_key(right_user_key, 1).into_inner()),
+            ),
+            7,
+        );
+        assert_eq!(splits.len(), 3);
+        assert_contiguous_splits(&splits, 7);
+    }
 }
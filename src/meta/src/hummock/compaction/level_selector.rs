@@ -21,10 +21,14 @@ use std::sync::Arc;
 
 use risingwave_pb::hummock::Level;
 
-use crate::hummock::compaction::compaction_picker::{CompactionPicker, MinOverlappingPicker};
-use crate::hummock::compaction::overlap_strategy::{OverlapStrategy, RangeOverlapStrategy};
+use crate::hummock::compaction::compaction_picker::{
+    CompactionPicker, IntraLevelPicker, MinOverlappingPicker,
+};
+use crate::hummock::compaction::overlap_strategy::{
+    create_overlap_strategy, OverlapStrategy, RangeOverlapStrategy,
+};
 use crate::hummock::compaction::tier_compaction_picker::TierCompactionPicker;
-use crate::hummock::compaction::{CompactionConfig, SearchResult};
+use crate::hummock::compaction::{CompactionConfig, LevelSelectionPolicy, SearchResult};
 use crate::hummock::level_handler::LevelHandler;
 
 const SCORE_BASE: u64 = 100;
@@ -40,6 +44,22 @@ pub trait LevelSelector: Sync + Send {
     ) -> Option<SearchResult>;
 
     fn name(&self) -> &'static str;
+
+    /// Clones the selector along with its settings, so that a cloned `CompactStatus` keeps picking
+    /// tasks the same way.
+    fn clone_box(&self) -> Box<dyn LevelSelector>;
+
+    /// Sets the provider of table statistics to consult when picking tasks, or removes it with
+    /// `None`. Selectors not using the statistics ignore it.
+    fn set_table_stats_provider(&mut self, _provider: Option<Arc<dyn TableStatsProvider>>) {}
+}
+
+/// Provides the statistics of tables collected outside of compaction, e.g. by the query engine,
+/// so that the access pattern can be taken into account when picking tasks.
+pub trait TableStatsProvider: Send + Sync {
+    /// Returns how frequently the data in level `level_idx` is read. Any unit works as long as
+    /// it's consistent among levels.
+    fn level_read_frequency(&self, level_idx: usize) -> u64;
 }
 
 #[derive(Default)]
@@ -55,9 +75,11 @@ pub struct SelectContext {
 }
 
 // TODO: Set these configurations by meta rpc
+#[derive(Clone)]
 pub struct DynamicLevelSelector {
     config: Arc<CompactionConfig>,
     overlap_strategy: Arc<dyn OverlapStrategy>,
+    table_stats_provider: Option<Arc<dyn TableStatsProvider>>,
 }
 
 impl Default for DynamicLevelSelector {
@@ -74,6 +96,7 @@ impl DynamicLevelSelector {
         DynamicLevelSelector {
             config,
             overlap_strategy,
+            table_stats_provider: None,
         }
     }
 
@@ -88,17 +111,31 @@ impl DynamicLevelSelector {
                 task_id,
                 base_level,
                 self.config.clone(),
-                self.overlap_strategy.clone(),
+                self.overlap_strategy(level),
             ))
         } else {
             Box::new(MinOverlappingPicker::new(
                 task_id,
                 level,
-                self.overlap_strategy.clone(),
+                self.overlap_strategy(level),
             ))
         }
     }
 
+    /// Returns the overlap strategy configured for `level`, or the one of the selector.
+    fn overlap_strategy(&self, level: usize) -> Arc<dyn OverlapStrategy> {
+        match self.config.level_overlap_strategies.get(level) {
+            Some(strategy_type) => create_overlap_strategy(*strategy_type),
+            None => self.overlap_strategy.clone(),
+        }
+    }
+
+    /// Returns the target size of each level for the current shape of `levels`. L0 has no
+    /// target size, which is `u64::MAX`.
+    pub fn level_max_bytes(&self, levels: &[Level]) -> Vec<u64> {
+        self.calculate_level_base_size(levels).level_max_bytes
+    }
+
     // TODO: calculate this scores in apply compact result.
     /// `calculate_level_base_size` calculate base level and the base size of LSM tree build for
     /// current dataset. In other words,  `level_max_bytes` is our compaction goal which shall
@@ -127,18 +164,32 @@ impl DynamicLevelSelector {
         ctx.level_max_bytes
             .resize(self.config.max_level as usize + 1, u64::MAX);
 
+        if self.config.base_level_size_bytes > 0 {
+            // The targets are anchored at the configured size of the bottommost level. The base
+            // level is the first one whose target reaches `max_bytes_for_level_base`, and the
+            // levels above it stay empty.
+            let level_size_targets = self.config.level_size_targets();
+            ctx.base_level = (1..=self.config.max_level)
+                .find(|&i| level_size_targets[i] >= self.config.max_bytes_for_level_base)
+                .unwrap_or(self.config.max_level);
+            let base_level = ctx.base_level;
+            ctx.level_max_bytes[base_level..].copy_from_slice(&level_size_targets[base_level..]);
+            return ctx;
+        }
+
         if max_level_size == 0 {
             // Use the bottommost level.
             ctx.base_level = self.config.max_level;
             return ctx;
         }
 
+        let level_multiplier = self.config.level_multiplier();
         let base_bytes_max = std::cmp::max(self.config.max_bytes_for_level_base, l0_size);
-        let base_bytes_min = base_bytes_max / self.config.max_bytes_for_level_multiplier;
+        let base_bytes_min = base_bytes_max / level_multiplier;
 
         let mut cur_level_size = max_level_size;
         for _ in first_non_empty_level..self.config.max_level {
-            cur_level_size /= self.config.max_bytes_for_level_multiplier;
+            cur_level_size /= level_multiplier;
         }
 
         let base_level_size = if cur_level_size <= base_bytes_min {
@@ -151,12 +202,11 @@ impl DynamicLevelSelector {
             ctx.base_level = first_non_empty_level;
             while ctx.base_level > 1 && cur_level_size > base_bytes_max {
                 ctx.base_level -= 1;
-                cur_level_size /= self.config.max_bytes_for_level_multiplier;
+                cur_level_size /= level_multiplier;
             }
             std::cmp::min(base_bytes_max, cur_level_size)
         };
 
-        let level_multiplier = self.config.max_bytes_for_level_multiplier as f64;
         let mut level_size = base_level_size;
         for i in ctx.base_level..=self.config.max_level {
             // Don't set any level below base_bytes_max. Otherwise, the LSM can
@@ -164,32 +214,40 @@ impl DynamicLevelSelector {
             // causes compaction scoring, which depends on level sizes, to favor L1+
             // at the expense of L0, which may fill up and stall.
             ctx.level_max_bytes[i] = std::cmp::max(level_size, base_bytes_max);
-            level_size = (level_size as f64 * level_multiplier) as u64;
+            level_size = (level_size as f64 * level_multiplier as f64) as u64;
         }
         ctx
     }
 
-    fn get_priority_levels(
-        &self,
-        levels: &[Level],
-        handlers: &mut [LevelHandler],
-    ) -> SelectContext {
+    fn get_priority_levels(&self, levels: &[Level], handlers: &[LevelHandler]) -> SelectContext {
         let mut ctx = self.calculate_level_base_size(levels);
+        // Idle bytes and the smallest idle SST id of each level, to order levels by the policy.
+        let mut idle_sizes = vec![0; self.config.max_level + 1];
+        let mut oldest_sst_ids = vec![u64::MAX; self.config.max_level + 1];
 
         // The bottommost level can not be input level.
         for level in &levels[..self.config.max_level] {
             let level_idx = level.level_idx as usize;
+            if self.config.tier_only && level_idx > 0 {
+                break;
+            }
+            let handler = match handlers.get(level_idx) {
+                Some(handler) => handler,
+                None => continue,
+            };
             let mut total_size = 0;
             let mut idle_file_count = 0;
             for table in &level.table_infos {
-                if !handlers[level_idx].is_pending_compact(&table.id) {
+                if !handler.is_pending_compact(&table.id) {
                     total_size += table.file_size;
                     idle_file_count += 1;
+                    oldest_sst_ids[level_idx] = oldest_sst_ids[level_idx].min(table.id);
                 }
             }
             if total_size == 0 {
                 continue;
             }
+            idle_sizes[level_idx] = total_size;
             if level_idx == 0 {
                 // The number of files in L0 can grow quickly due to frequent checkpoint. So, if we
                 // set level0_trigger_number too small, the manager will always
@@ -214,10 +272,105 @@ impl DynamicLevelSelector {
             }
         }
 
-        // sort reverse to pick the largest one.
-        ctx.score_levels.sort_by(|a, b| b.0.cmp(&a.0));
+        // Levels hot for reads are compacted sooner because reads on them are amplified more. The
+        // score of a level is doubled at most, when all reads go to it.
+        if let Some(provider) = self.table_stats_provider.as_ref() {
+            let read_frequencies: Vec<u64> = ctx
+                .score_levels
+                .iter()
+                .map(|(_, level_idx)| provider.level_read_frequency(*level_idx))
+                .collect();
+            let total_read_frequency = read_frequencies
+                .iter()
+                .fold(0u64, |total, read_frequency| {
+                    total.saturating_add(*read_frequency)
+                })
+                .max(1);
+            for ((score, _), read_frequency) in ctx.score_levels.iter_mut().zip(read_frequencies) {
+                // Multiplied in 128 bits not to overflow. The bonus is at most the score itself.
+                let bonus =
+                    (*score as u128 * read_frequency as u128 / total_read_frequency as u128) as u64;
+                *score = score.saturating_add(bonus);
+            }
+        }
+
+        match self.config.level_selection_policy {
+            // sort reverse to pick the largest one.
+            LevelSelectionPolicy::LargestFirst => ctx.score_levels.sort_by(|a, b| b.0.cmp(&a.0)),
+            // The levels over their targets still come first, as `pick_compaction` stops at the
+            // first level which is not.
+            LevelSelectionPolicy::SmallestFirst => ctx
+                .score_levels
+                .sort_by_key(|(score, level_idx)| (*score <= SCORE_BASE, idle_sizes[*level_idx])),
+            LevelSelectionPolicy::OldestFirst => {
+                ctx.score_levels.sort_by_key(|(score, level_idx)| {
+                    (*score <= SCORE_BASE, oldest_sst_ids[*level_idx])
+                })
+            }
+        }
         ctx
     }
+
+    /// Returns the levels holding idle SSTs paired with their scores relative to their targets,
+    /// e.g. 1.5 for a level 50% over its target, with the most urgent one first. L0 is scored by
+    /// its number of files as well.
+    pub fn levels_by_urgency(
+        &self,
+        levels: &[Level],
+        handlers: &[LevelHandler],
+    ) -> Vec<(u32, f64)> {
+        let mut score_levels = self.get_priority_levels(levels, handlers).score_levels;
+        score_levels.sort_by(|a, b| b.0.cmp(&a.0));
+        score_levels
+            .into_iter()
+            .map(|(score, level_idx)| (level_idx as u32, score as f64 / SCORE_BASE as f64))
+            .collect()
+    }
+
+    /// Returns the non-L0 levels under their target size but with at least
+    /// `intra_level_trigger_number` idle files or more than `max_files_per_level` files, which are
+    /// worth merging into themselves to reduce the number of files. The ones with more idle files
+    /// come first.
+    fn get_intra_level_candidates(
+        &self,
+        levels: &[Level],
+        handlers: &[LevelHandler],
+        ctx: &SelectContext,
+    ) -> Vec<usize> {
+        if (self.config.intra_level_trigger_number == 0 && self.config.max_files_per_level == 0)
+            || self.config.tier_only
+        {
+            return vec![];
+        }
+        let mut candidates = vec![];
+        for level in levels.iter().take(self.config.max_level + 1).skip(1) {
+            let level_idx = level.level_idx as usize;
+            let handler = match handlers.get(level_idx) {
+                Some(handler) => handler,
+                None => continue,
+            };
+            let mut total_size = 0;
+            let mut idle_file_count = 0;
+            for table in &level.table_infos {
+                total_size += table.file_size;
+                if !handler.is_pending_compact(&table.id) {
+                    idle_file_count += 1;
+                }
+            }
+            let reach_trigger = self.config.intra_level_trigger_number > 0
+                && idle_file_count >= self.config.intra_level_trigger_number;
+            let too_many_files = self.config.max_files_per_level > 0
+                && level.table_infos.len() > self.config.max_files_per_level;
+            if total_size <= ctx.level_max_bytes[level_idx] && (reach_trigger || too_many_files) {
+                candidates.push((idle_file_count, level_idx));
+            }
+        }
+        candidates.sort_by(|a, b| b.0.cmp(&a.0));
+        candidates
+            .into_iter()
+            .map(|(_, level_idx)| level_idx)
+            .collect()
+    }
 }
 
 impl LevelSelector for DynamicLevelSelector {
@@ -227,6 +380,9 @@ impl LevelSelector for DynamicLevelSelector {
             .first()
             .map(|(score, _)| *score > SCORE_BASE)
             .unwrap_or(false)
+            || !self
+                .get_intra_level_candidates(levels, level_handlers, &ctx)
+                .is_empty()
     }
 
     fn pick_compaction(
@@ -236,11 +392,18 @@ impl LevelSelector for DynamicLevelSelector {
         level_handlers: &mut [LevelHandler],
     ) -> Option<SearchResult> {
         let ctx = self.get_priority_levels(levels, level_handlers);
-        for (score, level_idx) in ctx.score_levels {
-            if score <= SCORE_BASE {
-                return None;
+        for (score, level_idx) in &ctx.score_levels {
+            if *score <= SCORE_BASE {
+                break;
             }
-            let picker = self.create_compaction_picker(level_idx, ctx.base_level, task_id);
+            let picker = self.create_compaction_picker(*level_idx, ctx.base_level, task_id);
+            if let Some(ret) = picker.pick_compaction(levels, level_handlers) {
+                return Some(ret);
+            }
+        }
+        // No level needs to be pushed down, so try to reduce the files of the small levels.
+        for level_idx in self.get_intra_level_candidates(levels, level_handlers, &ctx) {
+            let picker = IntraLevelPicker::new(task_id, level_idx, self.config.clone());
             if let Some(ret) = picker.pick_compaction(levels, level_handlers) {
                 return Some(ret);
             }
@@ -251,6 +414,14 @@ impl LevelSelector for DynamicLevelSelector {
     fn name(&self) -> &'static str {
         "DynamicLevelSelector"
     }
+
+    fn clone_box(&self) -> Box<dyn LevelSelector> {
+        Box::new(self.clone())
+    }
+
+    fn set_table_stats_provider(&mut self, provider: Option<Arc<dyn TableStatsProvider>>) {
+        self.table_stats_provider = provider;
+    }
 }
 
 #[cfg(test)]
@@ -258,10 +429,11 @@ pub mod tests {
     use std::ops::Range;
 
     use itertools::Itertools;
-    use risingwave_pb::hummock::{LevelType, SstableInfo};
+    use risingwave_hummock_sdk::key_range::KeyRange;
+    use risingwave_pb::hummock::{LevelType, SstableInfo, VNodeBitmap};
 
     use super::*;
-    use crate::hummock::compaction::overlap_strategy::RangeOverlapStrategy;
+    use crate::hummock::compaction::overlap_strategy::{OverlapStrategyType, RangeOverlapStrategy};
     use crate::hummock::compaction::tier_compaction_picker::tests::generate_table;
 
     pub fn generate_tables(
@@ -291,6 +463,22 @@ pub mod tests {
             max_compaction_bytes: 0,
             level0_max_file_number: 0,
             level0_trigger_number: 2,
+            max_output_writers: 0,
+            level_stuck_timeout_sec: 0,
+            intra_level_trigger_number: 0,
+            tier_only: false,
+            min_l0_sublevels_to_compact: 0,
+            level_selection_policy: LevelSelectionPolicy::LargestFirst,
+            max_files_per_level: 0,
+            level_overlap_strategies: vec![],
+            target_write_amp: None,
+            parallelism: 0,
+            min_compaction_bytes: 0,
+            key_range_splits: 0,
+            level0_stop_writes_file_number: 0,
+            base_level_size_bytes: 0,
+            cold_level: 0,
+            space_reclaim_first: false,
         };
         let selector =
             DynamicLevelSelector::new(Arc::new(config), Arc::new(RangeOverlapStrategy::default()));
@@ -367,6 +555,22 @@ pub mod tests {
             max_compaction_bytes: 10000,
             level0_max_file_number: 0,
             level0_trigger_number: 2,
+            max_output_writers: 0,
+            level_stuck_timeout_sec: 0,
+            intra_level_trigger_number: 0,
+            tier_only: false,
+            min_l0_sublevels_to_compact: 0,
+            level_selection_policy: LevelSelectionPolicy::LargestFirst,
+            max_files_per_level: 0,
+            level_overlap_strategies: vec![],
+            target_write_amp: None,
+            parallelism: 0,
+            min_compaction_bytes: 0,
+            key_range_splits: 0,
+            level0_stop_writes_file_number: 0,
+            base_level_size_bytes: 0,
+            cold_level: 0,
+            space_reclaim_first: false,
         };
         let selector =
             DynamicLevelSelector::new(Arc::new(config), Arc::new(RangeOverlapStrategy::default()));
@@ -421,5 +625,464 @@ pub mod tests {
         // to score.
         let compaction = selector.pick_compaction(2, &levels, &mut levels_handlers);
         assert!(compaction.is_none());
+
+        // Levels without a handler are not scored.
+        let mut levels_handlers = (0..3).into_iter().map(LevelHandler::new).collect_vec();
+        assert!(selector
+            .get_priority_levels(&levels, &levels_handlers)
+            .score_levels
+            .iter()
+            .all(|(_, level_idx)| *level_idx < 3));
+        selector.need_compaction(&levels, &mut levels_handlers);
+    }
+
+    #[test]
+    fn test_fall_back_to_leveled_compaction() {
+        let config = CompactionConfig {
+            max_bytes_for_level_base: 100,
+            max_level: 3,
+            max_bytes_for_level_multiplier: 5,
+            max_compaction_bytes: 10000,
+            level0_trigger_number: 2,
+            ..Default::default()
+        };
+        let selector =
+            DynamicLevelSelector::new(Arc::new(config), Arc::new(RangeOverlapStrategy::default()));
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![generate_table(20, 1, 0, 100, 4)],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(0..4, 0..1000, 3, 100),
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(4..12, 0..1000, 2, 100),
+            },
+            Level {
+                level_idx: 3,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(12..16, 0..1000, 1, 1000),
+            },
+        ];
+
+        // The single file of L0 doesn't reach the trigger of the tier picker, so the leveled
+        // picker pushes a file of L1 down to the overlapping files of L2 instead.
+        let mut levels_handlers = (0..4).into_iter().map(LevelHandler::new).collect_vec();
+        let compaction = selector
+            .pick_compaction(1, &levels, &mut levels_handlers)
+            .unwrap();
+        assert_eq!(compaction.select_level.level_idx, 1);
+        assert_eq!(compaction.target_level.level_idx, 2);
+        assert_eq!(compaction.select_level.table_infos.len(), 1);
+        let select_range = KeyRange::from(
+            compaction.select_level.table_infos[0]
+                .key_range
+                .as_ref()
+                .unwrap(),
+        );
+        assert_eq!(compaction.target_level.table_infos.len(), 2);
+        for table in &compaction.target_level.table_infos {
+            assert!(
+                select_range.full_key_overlap(&KeyRange::from(table.key_range.as_ref().unwrap()))
+            );
+        }
+    }
+
+    struct FixedTableStats(Vec<u64>);
+
+    impl TableStatsProvider for FixedTableStats {
+        fn level_read_frequency(&self, level_idx: usize) -> u64 {
+            self.0[level_idx]
+        }
+    }
+
+    #[test]
+    fn test_pick_compaction_with_table_stats() {
+        let config = CompactionConfig {
+            max_bytes_for_level_base: 100,
+            max_level: 4,
+            max_bytes_for_level_multiplier: 5,
+            max_compaction_bytes: 10000,
+            ..Default::default()
+        };
+        let mut selector =
+            DynamicLevelSelector::new(Arc::new(config), Arc::new(RangeOverlapStrategy::default()));
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![],
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(0..10, 0..1000, 3, 25),
+            },
+            Level {
+                level_idx: 3,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(10..15, 0..1000, 2, 300),
+            },
+            Level {
+                level_idx: 4,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(15..20, 0..1000, 1, 1000),
+            },
+        ];
+
+        // L3 exceeds its target more than L2.
+        let mut levels_handlers = (0..5).into_iter().map(LevelHandler::new).collect_vec();
+        let compaction = selector
+            .pick_compaction(1, &levels, &mut levels_handlers)
+            .unwrap();
+        assert_eq!(compaction.select_level.level_idx, 3);
+
+        // But L2 is preferred when most reads go to it.
+        selector.set_table_stats_provider(Some(Arc::new(FixedTableStats(vec![0, 0, 9, 1, 0]))));
+        let mut levels_handlers = (0..5).into_iter().map(LevelHandler::new).collect_vec();
+        let compaction = selector
+            .pick_compaction(1, &levels, &mut levels_handlers)
+            .unwrap();
+        assert_eq!(compaction.select_level.level_idx, 2);
+        assert_eq!(compaction.target_level.level_idx, 3);
+
+        selector.set_table_stats_provider(None);
+        let mut levels_handlers = (0..5).into_iter().map(LevelHandler::new).collect_vec();
+        let compaction = selector
+            .pick_compaction(1, &levels, &mut levels_handlers)
+            .unwrap();
+        assert_eq!(compaction.select_level.level_idx, 3);
+
+        // Huge frequencies don't overflow the weighting.
+        selector.set_table_stats_provider(Some(Arc::new(FixedTableStats(vec![u64::MAX; 5]))));
+        let mut levels_handlers = (0..5).into_iter().map(LevelHandler::new).collect_vec();
+        let compaction = selector
+            .pick_compaction(1, &levels, &mut levels_handlers)
+            .unwrap();
+        assert_eq!(compaction.select_level.level_idx, 3);
+    }
+
+    #[test]
+    fn test_level_overlap_strategies() {
+        let config = CompactionConfig {
+            max_level: 2,
+            level0_trigger_number: 1,
+            level_overlap_strategies: vec![
+                OverlapStrategyType::Conservative,
+                OverlapStrategyType::Range,
+            ],
+            ..Default::default()
+        };
+        let selector =
+            DynamicLevelSelector::new(Arc::new(config), Arc::new(RangeOverlapStrategy::default()));
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![generate_table(1, 1, 0, 50, 2)],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(2..6, 0..400, 1, 1),
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(6..10, 0..400, 1, 1),
+            },
+        ];
+        let target_ids = |ret: SearchResult| {
+            ret.target_level
+                .table_infos
+                .iter()
+                .map(|table| table.id)
+                .collect_vec()
+        };
+
+        // L0 takes all files of L1 as overlapping with its file.
+        let mut levels_handlers = (0..3).into_iter().map(LevelHandler::new).collect_vec();
+        let ret = selector
+            .create_compaction_picker(0, 1, 1)
+            .pick_compaction(&levels, &mut levels_handlers)
+            .unwrap();
+        assert_eq!(target_ids(ret), vec![2, 3, 4, 5]);
+
+        // L1 only takes the file of L2 whose key range overlaps.
+        let mut levels_handlers = (0..3).into_iter().map(LevelHandler::new).collect_vec();
+        let ret = selector
+            .create_compaction_picker(1, 1, 1)
+            .pick_compaction(&levels, &mut levels_handlers)
+            .unwrap();
+        assert_eq!(target_ids(ret).len(), 1);
+    }
+
+    #[test]
+    fn test_hash_overlap_strategy() {
+        // Each SST holds a single virtual node of table 1.
+        let with_vnode = |mut table: SstableInfo, vnode: u8| {
+            table.vnode_bitmaps = vec![VNodeBitmap {
+                table_id: 1,
+                maplen: 1,
+                bitmap: vec![1 << vnode],
+            }];
+            table
+        };
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![with_vnode(generate_table(1, 1, 0, 400, 2), 0)],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(2..6, 0..400, 1, 1)
+                    .into_iter()
+                    .map(|table| {
+                        let vnode = (table.id % 2) as u8;
+                        with_vnode(table, vnode)
+                    })
+                    .collect_vec(),
+            },
+        ];
+        let target_ids = |strategy_type| {
+            let config = CompactionConfig {
+                max_level: 1,
+                level0_trigger_number: 1,
+                level_overlap_strategies: vec![strategy_type],
+                ..Default::default()
+            };
+            let selector = DynamicLevelSelector::new(
+                Arc::new(config),
+                Arc::new(RangeOverlapStrategy::default()),
+            );
+            let mut levels_handlers = (0..2).into_iter().map(LevelHandler::new).collect_vec();
+            selector
+                .create_compaction_picker(0, 1, 1)
+                .pick_compaction(&levels, &mut levels_handlers)
+                .unwrap()
+                .target_level
+                .table_infos
+                .iter()
+                .map(|table| table.id)
+                .collect_vec()
+        };
+
+        // All SSTs of L1 overlap with the SST of L0 by key range, but only those holding the same
+        // virtual node overlap by hash. The SSTs in between are taken as well to keep L1
+        // non-overlapping after the compaction.
+        assert_eq!(target_ids(OverlapStrategyType::Range), vec![2, 3, 4, 5]);
+        assert_eq!(target_ids(OverlapStrategyType::Hash), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_level_selection_policy() {
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![],
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(10..20, 0..1000, 3, 25),
+            },
+            Level {
+                level_idx: 3,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(0..5, 0..1000, 2, 300),
+            },
+            Level {
+                level_idx: 4,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(20..25, 0..1000, 1, 1000),
+            },
+        ];
+        let pick_level = |level_selection_policy| {
+            let config = CompactionConfig {
+                max_bytes_for_level_base: 100,
+                max_level: 4,
+                max_bytes_for_level_multiplier: 5,
+                max_compaction_bytes: 10000,
+                level_selection_policy,
+                ..Default::default()
+            };
+            let selector = DynamicLevelSelector::new(
+                Arc::new(config),
+                Arc::new(RangeOverlapStrategy::default()),
+            );
+            let mut levels_handlers = (0..5).into_iter().map(LevelHandler::new).collect_vec();
+            selector
+                .pick_compaction(1, &levels, &mut levels_handlers)
+                .unwrap()
+                .select_level
+                .level_idx
+        };
+
+        // L2 and L3 are both over their targets. L3 exceeds its target more, but L2 is smaller.
+        assert_eq!(pick_level(LevelSelectionPolicy::LargestFirst), 3);
+        assert_eq!(pick_level(LevelSelectionPolicy::SmallestFirst), 2);
+        // L3 holds the SST with the smallest id.
+        assert_eq!(pick_level(LevelSelectionPolicy::OldestFirst), 3);
+    }
+
+    /// Golden decisions of `test_golden_pick_decisions`.
+    const GOLDEN_PICK_DECISIONS: &str = include_str!("picker_decisions.golden");
+
+    /// Recorded LSM shapes to guard the decisions of the selector against silent changes. Each
+    /// shape starts with empty levels of `max_level` 4.
+    fn recorded_shapes() -> Vec<(&'static str, Vec<Level>)> {
+        let shape = |tables: Vec<Vec<SstableInfo>>| {
+            tables
+                .into_iter()
+                .enumerate()
+                .map(|(level_idx, table_infos)| Level {
+                    level_idx: level_idx as u32,
+                    level_type: if level_idx == 0 {
+                        LevelType::Overlapping as i32
+                    } else {
+                        LevelType::Nonoverlapping as i32
+                    },
+                    table_infos,
+                })
+                .collect_vec()
+        };
+        vec![
+            (
+                "idle",
+                shape(vec![
+                    vec![],
+                    vec![],
+                    vec![],
+                    vec![],
+                    generate_tables(0..3, 0..1000, 1, 20),
+                ]),
+            ),
+            (
+                "l0_overlapping",
+                shape(vec![
+                    generate_tables(0..3, 0..300, 3, 30),
+                    vec![],
+                    vec![],
+                    vec![],
+                    generate_tables(3..6, 0..1000, 1, 200),
+                ]),
+            ),
+            (
+                "l0_into_base",
+                shape(vec![
+                    vec![
+                        generate_table(0, 1, 100, 400, 3),
+                        generate_table(1, 1, 350, 600, 4),
+                    ],
+                    vec![],
+                    vec![],
+                    generate_tables(2..6, 0..1000, 2, 40),
+                    generate_tables(6..9, 0..1000, 1, 200),
+                ]),
+            ),
+            (
+                "base_over_target",
+                shape(vec![
+                    vec![],
+                    vec![],
+                    generate_tables(0..5, 0..1000, 3, 30),
+                    generate_tables(5..10, 0..1000, 2, 50),
+                    generate_tables(10..15, 0..1000, 1, 200),
+                ]),
+            ),
+            (
+                "intra_level",
+                shape(vec![
+                    vec![],
+                    generate_tables(0..6, 0..600, 2, 1),
+                    vec![],
+                    vec![],
+                    generate_tables(6..9, 0..1000, 1, 200),
+                ]),
+            ),
+        ]
+    }
+
+    fn format_ids(level: &Level) -> String {
+        format!(
+            "L{} [{}]",
+            level.level_idx,
+            level.table_infos.iter().map(|table| table.id).join(", ")
+        )
+    }
+
+    /// Runs the selector over the recorded shapes, and compares the input SSTs picked, until there
+    /// is nothing to pick or at most 3 times per shape, against the golden decisions. Run with
+    /// `UPDATE_GOLDEN=1` to update the golden file after an intended change of the decisions.
+    #[test]
+    fn test_golden_pick_decisions() {
+        let config = CompactionConfig {
+            max_bytes_for_level_base: 100,
+            max_level: 4,
+            max_bytes_for_level_multiplier: 5,
+            max_compaction_bytes: 10000,
+            level0_trigger_number: 2,
+            intra_level_trigger_number: 4,
+            ..Default::default()
+        };
+        let selector =
+            DynamicLevelSelector::new(Arc::new(config), Arc::new(RangeOverlapStrategy::default()));
+        let mut decisions = String::new();
+        for (name, levels) in recorded_shapes() {
+            let mut levels_handlers = (0..5).into_iter().map(LevelHandler::new).collect_vec();
+            for task_id in 1..=3 {
+                match selector.pick_compaction(task_id, &levels, &mut levels_handlers) {
+                    Some(ret) => {
+                        decisions += &format!(
+                            "{} #{}: {} -> {}\n",
+                            name,
+                            task_id,
+                            format_ids(&ret.select_level),
+                            format_ids(&ret.target_level)
+                        );
+                    }
+                    None => {
+                        decisions += &format!("{} #{}: none\n", name, task_id);
+                        break;
+                    }
+                }
+            }
+        }
+
+        if std::env::var("UPDATE_GOLDEN").is_ok() {
+            std::fs::write(
+                concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/src/hummock/compaction/picker_decisions.golden"
+                ),
+                &decisions,
+            )
+            .unwrap();
+            return;
+        }
+        assert_eq!(
+            decisions, GOLDEN_PICK_DECISIONS,
+            "the decisions of the selector changed, update the golden file with `UPDATE_GOLDEN=1` \
+             if it's intended"
+        );
     }
 }
@@ -17,23 +17,37 @@ mod level_selector;
 mod overlap_strategy;
 mod tier_compaction_picker;
 
-use std::collections::HashSet;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashSet};
 use std::fmt::{Debug, Formatter};
 use std::io::Cursor;
 use std::sync::Arc;
 
 use itertools::Itertools;
 use prost::Message;
-use risingwave_common::error::Result;
+use risingwave_common::error::{ErrorCode, Result};
+use risingwave_common::hash::VIRTUAL_NODE_COUNT;
+use risingwave_common::util::epoch::{Epoch, INVALID_EPOCH};
+use risingwave_hummock_sdk::key::user_key;
 use risingwave_hummock_sdk::key_range::KeyRange;
-use risingwave_hummock_sdk::HummockEpoch;
+use risingwave_hummock_sdk::{HummockEpoch, HummockSSTableId, VersionedComparator};
+use risingwave_pb::hummock::compact_task::OutputTier;
+use risingwave_pb::hummock::compaction_group::PrefixPair;
 use risingwave_pb::hummock::{
-    CompactMetrics, CompactTask, HummockVersion, Level, TableSetStatistics,
+    CompactMetrics, CompactTask, HummockVersion, Level, LevelType, SstableInfo, TableSetStatistics,
+    UncommittedEpoch, VNodeBitmap,
 };
 
-use crate::hummock::compaction::level_selector::{DynamicLevelSelector, LevelSelector};
-use crate::hummock::compaction::overlap_strategy::RangeOverlapStrategy;
+use crate::hummock::compaction::compaction_picker::{
+    split_key_range, CompactionPicker, SpaceReclaimPicker,
+};
+use crate::hummock::compaction::level_selector::{
+    DynamicLevelSelector, LevelSelector, TableStatsProvider,
+};
+pub use crate::hummock::compaction::overlap_strategy::OverlapStrategyType;
+use crate::hummock::compaction::overlap_strategy::{OverlapStrategy, RangeOverlapStrategy};
 use crate::hummock::level_handler::LevelHandler;
+use crate::hummock::model::sstable_id_info::get_timestamp_now;
 use crate::hummock::model::HUMMOCK_DEFAULT_CF_NAME;
 use crate::model::Transactional;
 use crate::storage;
@@ -45,16 +59,39 @@ pub(crate) const HUMMOCK_COMPACT_STATUS_KEY: &str = "compact_status";
 const DEFAULT_MAX_COMPACTION_BYTES: u64 = 2 * 1024 * 1024 * 1024; // 2GB
 const DEFAULT_MAX_BYTES_FOR_LEVEL_BASE: u64 = 1024 * 1024 * 1024;
 const DEFAULT_LEVEL0_MAX_FILE_NUMBER: usize = 32;
+// 0 means the splits of a task are all compacted in parallel.
+const DEFAULT_MAX_OUTPUT_WRITERS: usize = 0;
+const DEFAULT_LEVEL_STUCK_TIMEOUT_SEC: u64 = 30 * 60;
+// Merging a level into itself rewrites data without pushing it down, so it's off by default.
+const DEFAULT_INTRA_LEVEL_TRIGGER_NUMBER: usize = 0;
 
 // decrease this configure when the generation of checkpoint barrier is not frequent.
 const DEFAULT_LEVEL0_TRIGGER_NUMBER: usize = 16;
 
 const MAX_LEVEL: usize = 6;
 
+const BYTES_PER_GB: u64 = 1024 * 1024 * 1024;
+
+// The recommended flush size grows with the congestion of L0, up to this multiple of the default.
+const MAX_FLUSH_SIZE_MULTIPLIER: u64 = 4;
+
 pub struct CompactStatus {
     pub(crate) level_handlers: Vec<LevelHandler>,
     pub(crate) next_compact_task_id: u64,
+    /// Total number of tasks issued so far, regardless of the base of `next_compact_task_id`.
+    tasks_issued: u64,
+    /// Cumulative metrics of all finished tasks.
+    total_metrics: CompactMetrics,
+    /// Epoch when a task was last reported as finished successfully, or `INVALID_EPOCH` if none
+    /// has finished yet.
+    last_compacted_epoch: HummockEpoch,
+    /// The last time in seconds when a compaction of each level finished, or when the status was
+    /// created if none has finished yet. It's not persisted.
+    last_compacted_timestamps: Vec<u64>,
+    config: Arc<CompactionConfig>,
     compaction_selector: Box<dyn LevelSelector>,
+    /// Statistics of tables to consult when picking tasks. It's not persisted.
+    table_stats_provider: Option<Arc<dyn TableStatsProvider>>,
 }
 
 impl Debug for CompactStatus {
@@ -62,6 +99,10 @@ impl Debug for CompactStatus {
         f.debug_struct("CompactStatus")
             .field("level_handlers", &self.level_handlers)
             .field("next_compact_task_id", &self.next_compact_task_id)
+            .field("tasks_issued", &self.tasks_issued)
+            .field("total_metrics", &self.total_metrics)
+            .field("last_compacted_epoch", &self.last_compacted_epoch)
+            .field("config", &self.config)
             .field("compaction_selector", &self.compaction_selector.name())
             .finish()
     }
@@ -71,6 +112,9 @@ impl PartialEq for CompactStatus {
     fn eq(&self, other: &Self) -> bool {
         self.level_handlers.eq(&other.level_handlers)
             && self.next_compact_task_id == other.next_compact_task_id
+            && self.tasks_issued == other.tasks_issued
+            && self.total_metrics == other.total_metrics
+            && self.last_compacted_epoch == other.last_compacted_epoch
             && self.compaction_selector.name() == other.compaction_selector.name()
     }
 }
@@ -79,8 +123,14 @@ impl Clone for CompactStatus {
     fn clone(&self) -> Self {
         Self {
             level_handlers: self.level_handlers.clone(),
-            compaction_selector: Box::new(DynamicLevelSelector::default()),
+            compaction_selector: self.compaction_selector.clone_box(),
+            table_stats_provider: self.table_stats_provider.clone(),
             next_compact_task_id: self.next_compact_task_id,
+            tasks_issued: self.tasks_issued,
+            total_metrics: self.total_metrics.clone(),
+            last_compacted_epoch: self.last_compacted_epoch,
+            last_compacted_timestamps: self.last_compacted_timestamps.clone(),
+            config: self.config.clone(),
         }
     }
 }
@@ -91,6 +141,19 @@ pub struct SearchResult {
     split_ranges: Vec<KeyRange>,
 }
 
+/// The two compaction groups a group is split into by `CompactStatus::split_group`.
+#[derive(Debug, PartialEq)]
+pub struct GroupSplit {
+    /// The key prefixes staying in the group.
+    pub left: Vec<PrefixPair>,
+    /// The SSTs of the group below the split key.
+    pub left_version: HummockVersion,
+    /// The key prefixes moved to the new group.
+    pub right: Vec<PrefixPair>,
+    /// The SSTs of the group at or above the split key.
+    pub right_version: HummockVersion,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct CompactionConfig {
     pub max_bytes_for_level_base: u64,
@@ -99,6 +162,109 @@ pub struct CompactionConfig {
     pub max_compaction_bytes: u64,
     pub level0_max_file_number: usize,
     pub level0_trigger_number: usize,
+    pub max_output_writers: usize,
+    /// A level over its target for this long without any compaction finished is seen as stuck.
+    pub level_stuck_timeout_sec: u64,
+    /// A non-L0 level under its target size is merged into itself when it has at least this many
+    /// files. 0 means never.
+    pub intra_level_trigger_number: usize,
+    /// Only L0 is merged into itself when set, and data is never pushed down to lower levels.
+    pub tier_only: bool,
+    /// L0 is not compacted until it has at least this many sub-levels, so that more work can be
+    /// batched in one task. Every SST in L0 is a sub-level since they may overlap with each other.
+    pub min_l0_sublevels_to_compact: usize,
+    /// Which level to compact first when multiple levels are over their targets.
+    pub level_selection_policy: LevelSelectionPolicy,
+    /// A non-L0 level under its target size is merged into itself when it has more files than
+    /// this, to bound the metadata and open files of a level. 0 means no limit.
+    pub max_files_per_level: usize,
+    /// Overlap strategy of the pickers of each level by index. The levels without one use the
+    /// strategy of the selector, which is the range strategy by default.
+    pub level_overlap_strategies: Vec<OverlapStrategyType>,
+    /// Target total write amplification of the non-L0 levels. When set, the size multiplier of
+    /// the levels is derived from it instead of `max_bytes_for_level_multiplier`.
+    pub target_write_amp: Option<f64>,
+    /// Number of sub-tasks to split each task into by disjoint ranges of virtual nodes, for
+    /// compactors to execute in parallel. 0 or 1 means no split.
+    pub parallelism: usize,
+    /// L0 is not compacted until the L0 SSTs picked for a task sum up to more than this many
+    /// bytes, to avoid tiny tasks under light write load. 0 means no threshold.
+    pub min_compaction_bytes: u64,
+    /// Number of key ranges of roughly equal width to split the input of each task into, instead
+    /// of the splits chosen by the pickers, for compactors to compact in parallel. 0 or 1 keeps
+    /// the splits of the pickers.
+    pub key_range_splits: usize,
+    /// Writes are stopped once L0 has at least this many files, until compaction catches up, to
+    /// avoid running out of memory on too many SSTs to read. 0 means never.
+    pub level0_stop_writes_file_number: usize,
+    /// Target size of the bottommost level. When set, the targets of the levels above are derived
+    /// from it by dividing by the level multiplier, instead of from the size of the bottommost
+    /// level, so that the shape of the tree doesn't change with the data. 0 means dynamic.
+    pub base_level_size_bytes: u64,
+    /// Tasks with a target level of at least this level write their output to the cold tier of
+    /// object storage, e.g. `max_level` for only the bottommost level, which is rarely rewritten.
+    /// 0 means all tasks write to the hot tier.
+    pub cold_level: usize,
+    /// Tasks reclaiming the space of data below the safe epoch are picked before those of the
+    /// selector, for delete-heavy workloads. Such data is pushed down until the bottommost level.
+    pub space_reclaim_first: bool,
+}
+
+impl CompactionConfig {
+    /// Returns the size multiplier between adjacent levels, derived from `target_write_amp` if set.
+    pub fn level_multiplier(&self) -> u64 {
+        match self.target_write_amp {
+            Some(target_write_amp) => {
+                level_multiplier_for_write_amp(self.max_level, target_write_amp)
+            }
+            None => self.max_bytes_for_level_multiplier,
+        }
+    }
+
+    /// Returns the target size of each level anchored at `base_level_size_bytes` for the
+    /// bottommost level, each level above being `level_multiplier` times smaller. L0 has no target
+    /// size, which is `u64::MAX`.
+    pub fn level_size_targets(&self) -> Vec<u64> {
+        let level_multiplier = self.level_multiplier();
+        let mut targets = vec![u64::MAX; self.max_level + 1];
+        let mut level_size = self.base_level_size_bytes;
+        for level_idx in (1..=self.max_level).rev() {
+            targets[level_idx] = level_size;
+            level_size /= level_multiplier;
+        }
+        targets
+    }
+}
+
+/// Derives the size multiplier between adjacent levels for `num_levels` non-L0 levels to reach
+/// the total write amplification `target_write_amp`. Each byte is rewritten about `multiplier`
+/// times when it's pushed down a level, so the total is about `num_levels * multiplier`. The
+/// multiplier is rounded down to stay within the target, but it's at least 2 for the levels to
+/// grow.
+pub fn level_multiplier_for_write_amp(num_levels: usize, target_write_amp: f64) -> u64 {
+    const MIN_LEVEL_MULTIPLIER: u64 = 2;
+    if num_levels == 0 {
+        return MIN_LEVEL_MULTIPLIER;
+    }
+    let multiplier = (target_write_amp / num_levels as f64).floor();
+    if multiplier > MIN_LEVEL_MULTIPLIER as f64 {
+        multiplier as u64
+    } else {
+        MIN_LEVEL_MULTIPLIER
+    }
+}
+
+/// Order to compact the levels over their targets in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(clippy::enum_variant_names)]
+pub enum LevelSelectionPolicy {
+    /// The level most over its target first, which reduces the imbalance of the tree the most.
+    LargestFirst,
+    /// The level with the fewest bytes first, whose task finishes and frees resources the soonest.
+    SmallestFirst,
+    /// The level holding the oldest SST first. SST ids are allocated in increasing order, so the
+    /// oldest SST is the one with the smallest id.
+    OldestFirst,
 }
 
 impl Default for CompactionConfig {
@@ -110,73 +276,435 @@ impl Default for CompactionConfig {
             max_compaction_bytes: DEFAULT_MAX_COMPACTION_BYTES,
             level0_max_file_number: DEFAULT_LEVEL0_MAX_FILE_NUMBER,
             level0_trigger_number: DEFAULT_LEVEL0_TRIGGER_NUMBER,
+            max_output_writers: DEFAULT_MAX_OUTPUT_WRITERS,
+            level_stuck_timeout_sec: DEFAULT_LEVEL_STUCK_TIMEOUT_SEC,
+            intra_level_trigger_number: DEFAULT_INTRA_LEVEL_TRIGGER_NUMBER,
+            tier_only: false,
+            min_l0_sublevels_to_compact: 0,
+            level_selection_policy: LevelSelectionPolicy::LargestFirst,
+            max_files_per_level: 0,
+            level_overlap_strategies: vec![],
+            target_write_amp: None,
+            parallelism: 0,
+            min_compaction_bytes: 0,
+            key_range_splits: 0,
+            level0_stop_writes_file_number: 0,
+            base_level_size_bytes: 0,
+            cold_level: 0,
+            space_reclaim_first: false,
         }
     }
 }
 
 impl CompactStatus {
     pub fn new(config: Arc<CompactionConfig>) -> CompactStatus {
+        // TODO: create selector and overlap strategy by configure.
+        let compaction_selector = Box::new(DynamicLevelSelector::new(
+            config.clone(),
+            Arc::new(RangeOverlapStrategy::default()),
+        ));
+        Self::with_selector(config, compaction_selector)
+    }
+
+    /// Creates a `CompactStatus` which picks tasks with `compaction_selector` instead of the
+    /// default `DynamicLevelSelector`, so that other picking algorithms can be plugged in.
+    pub fn with_selector(
+        config: Arc<CompactionConfig>,
+        compaction_selector: Box<dyn LevelSelector>,
+    ) -> CompactStatus {
         let mut level_handlers = vec![];
         for level in 0..=config.max_level {
             level_handlers.push(LevelHandler::new(level as u32));
         }
+        let last_compacted_timestamps = vec![get_timestamp_now(); level_handlers.len()];
         CompactStatus {
             level_handlers,
             next_compact_task_id: 1,
-            // TODO: create selector and overlap strategy by configure.
-            compaction_selector: Box::new(DynamicLevelSelector::new(
-                config,
-                Arc::new(RangeOverlapStrategy::default()),
-            )),
+            tasks_issued: 0,
+            total_metrics: CompactMetrics::default(),
+            last_compacted_epoch: INVALID_EPOCH,
+            last_compacted_timestamps,
+            config,
+            compaction_selector,
+            table_stats_provider: None,
         }
     }
 
-    fn cf_name() -> &'static str {
+    /// Takes over the progress of `stored`, e.g. the status loaded by `get` after a restart, while
+    /// keeping the config and the selector of `self`, which aren't persisted.
+    pub fn restore(&mut self, stored: CompactStatus) {
+        self.level_handlers = stored.level_handlers;
+        self.next_compact_task_id = stored.next_compact_task_id;
+        self.tasks_issued = stored.tasks_issued;
+        self.total_metrics = stored.total_metrics;
+        self.last_compacted_epoch = stored.last_compacted_epoch;
+        self.last_compacted_timestamps = stored.last_compacted_timestamps;
+    }
+
+    /// Sets the provider of table statistics, e.g. the read frequency of each level, for the
+    /// selector to weight its picking with, or removes it with `None`.
+    pub fn set_table_stats_provider(&mut self, provider: Option<Arc<dyn TableStatsProvider>>) {
+        self.compaction_selector
+            .set_table_stats_provider(provider.clone());
+        self.table_stats_provider = provider;
+    }
+
+    pub(crate) fn cf_name() -> &'static str {
         HUMMOCK_DEFAULT_CF_NAME
     }
 
-    fn key() -> &'static str {
+    pub(crate) fn key() -> &'static str {
         HUMMOCK_COMPACT_STATUS_KEY
     }
 
+    /// Loads the persisted status, or returns `None` if there's none. A value that can't be
+    /// decoded, e.g. corrupted or partially written, is an error, so that the caller can decide to
+    /// reinitialize the status or bail.
     pub async fn get<S: MetaStore>(meta_store: &S) -> Result<Option<CompactStatus>> {
-        match meta_store
+        let value = match meta_store
             .get_cf(CompactStatus::cf_name(), CompactStatus::key().as_bytes())
             .await
-            .map(|v| risingwave_pb::hummock::CompactStatus::decode(&mut Cursor::new(v)).unwrap())
-            .map(|s| (&s).into())
         {
-            Ok(compact_status) => Ok(Some(compact_status)),
+            Ok(value) => value,
             Err(err) => {
                 if !matches!(err, storage::Error::ItemNotFound(_)) {
                     return Err(err.into());
                 }
-                Ok(None)
+                return Ok(None);
             }
-        }
+        };
+        let compact_status = risingwave_pb::hummock::CompactStatus::decode(&mut Cursor::new(value))
+            .map_err(|e| {
+                ErrorCode::InternalError(format!("failed to decode compact status: {}", e))
+            })?;
+        Ok(Some((&compact_status).into()))
     }
 
-    pub fn get_compact_task(&mut self, levels: &[Level]) -> Option<CompactTask> {
+    /// Picks a task over `levels`. The task never drops the versions of keys visible to the
+    /// snapshots at or above `safe_epoch`, i.e. the minimum epoch pinned by readers, which is set
+    /// as its watermark.
+    pub fn get_compact_task(
+        &mut self,
+        levels: &[Level],
+        safe_epoch: HummockEpoch,
+    ) -> Option<CompactTask> {
         // When we compact the files, we must make the result of compaction meet the following
         // conditions, for any user key, the epoch of it in the file existing in the lower
         // layer must be larger.
 
-        let ret = match self.pick_compaction(levels) {
-            Some(ret) => ret,
-            None => return None,
-        };
+        if !self.has_next_task_id() {
+            return None;
+        }
+        let ret = self
+            .pick_space_reclaim(levels, safe_epoch)
+            .or_else(|| self.pick_compaction(levels))?;
 
         let select_level_id = ret.select_level.level_idx;
         let target_level_id = ret.target_level.level_idx;
+        // A selector may pick levels out of the range of the handlers by mistake, and the flag of
+        // ultimate level derived from them would be wrong. Drop such a task.
+        if select_level_id as usize >= self.level_handlers.len()
+            || target_level_id as usize >= self.level_handlers.len()
+        {
+            tracing::warn!(
+                "drop compact task {} with invalid levels: select level {}, target level {}",
+                self.next_compact_task_id,
+                select_level_id,
+                target_level_id
+            );
+            for level_handler in &mut self.level_handlers {
+                level_handler.remove_task(self.next_compact_task_id);
+            }
+            return None;
+        }
+
+        Some(self.build_compact_task(levels, ret, safe_epoch))
+    }
+
+    /// Picks a task like `get_compact_task`, and splits it into `parallelism` sub-tasks by
+    /// disjoint ranges of virtual nodes of the same size if configured. Each sub-task compacts the
+    /// keys of its own virtual nodes of all the input, so the sub-tasks share the id and input of
+    /// the task. They must be merged by `merge_sub_tasks` once all of them are done, and reported
+    /// and applied as a whole.
+    pub fn get_compact_tasks(
+        &mut self,
+        levels: &[Level],
+        safe_epoch: HummockEpoch,
+    ) -> Vec<CompactTask> {
+        match self.get_compact_task(levels, safe_epoch) {
+            Some(compact_task) => {
+                split_compact_task_by_vnode(compact_task, self.config.parallelism)
+            }
+            None => vec![],
+        }
+    }
+
+    /// Picks a task like `get_compact_task`, but only among the SSTs of the compaction group with
+    /// the key prefixes `prefix_pairs`, so that the groups are compacted independently and a write
+    /// burst to one group doesn't stall the others. The prefixes are carried in the task. An SST
+    /// straddling the boundary of the group is picked as well, since it holds keys of the group.
+    pub fn get_group_compact_task(
+        &mut self,
+        levels: &[Level],
+        prefix_pairs: &[PrefixPair],
+        safe_epoch: HummockEpoch,
+    ) -> Option<CompactTask> {
+        let group_levels = levels
+            .iter()
+            .map(|level| Level {
+                level_idx: level.level_idx,
+                level_type: level.level_type,
+                table_infos: level
+                    .table_infos
+                    .iter()
+                    .filter(|sst| sst_overlaps_group(prefix_pairs, sst))
+                    .cloned()
+                    .collect_vec(),
+            })
+            .collect_vec();
+        let mut compact_task = self.get_compact_task(&group_levels, safe_epoch)?;
+        compact_task.prefix_pairs = prefix_pairs.to_vec();
+        Some(compact_task)
+    }
+
+    /// Picks a one-off task merging the SSTs of two compaction groups, which are compacted in
+    /// isolation otherwise, e.g. when the tables of the groups are consolidated. So it must be
+    /// requested explicitly rather than by the scheduler. `group_a` and `group_b` are the key
+    /// prefixes of the groups. The SSTs of both groups in the bottommost non-L0 level holding idle
+    /// SSTs of both are merged into the same level, and all the prefixes belong to `group_a` in
+    /// the result. Returns `None` if the groups are invalid or there is nothing to merge.
+    pub fn get_cross_group_compact_task(
+        &mut self,
+        levels: &[Level],
+        group_a: &[PrefixPair],
+        group_b: &[PrefixPair],
+        safe_epoch: HummockEpoch,
+    ) -> Option<CompactTask> {
+        if !self.has_next_task_id() {
+            return None;
+        }
+        let (group_a_id, group_b_id) = match (group_id(group_a), group_id(group_b)) {
+            (Some(group_a_id), Some(group_b_id)) if group_a_id != group_b_id => {
+                (group_a_id, group_b_id)
+            }
+            _ => {
+                tracing::warn!("cannot merge invalid compaction groups");
+                return None;
+            }
+        };
+        for level in levels.iter().skip(1).rev() {
+            // The version may have more levels than the status tracks, which can't be compacted.
+            let level_handler = match self.level_handlers.get(level.level_idx as usize) {
+                Some(level_handler) => level_handler,
+                None => continue,
+            };
+            let select_tables = level
+                .table_infos
+                .iter()
+                .filter(|sst| sst_in_group(group_a, sst) || sst_in_group(group_b, sst))
+                .cloned()
+                .collect_vec();
+            if !select_tables.iter().any(|sst| sst_in_group(group_a, sst))
+                || !select_tables.iter().any(|sst| sst_in_group(group_b, sst))
+                || select_tables
+                    .iter()
+                    .any(|sst| level_handler.is_pending_compact(&sst.id))
+            {
+                continue;
+            }
+            self.level_handlers[level.level_idx as usize]
+                .add_pending_task(self.next_compact_task_id, &select_tables);
+            let ret = SearchResult {
+                select_level: Level {
+                    level_idx: level.level_idx,
+                    level_type: level.level_type,
+                    table_infos: select_tables,
+                },
+                target_level: Level {
+                    level_idx: level.level_idx,
+                    level_type: level.level_type,
+                    table_infos: vec![],
+                },
+                split_ranges: vec![KeyRange::inf()],
+            };
+            let mut compact_task = self.build_compact_task(levels, ret, safe_epoch);
+            compact_task.prefix_pairs = group_a
+                .iter()
+                .chain(group_b)
+                .map(|prefix_pair| PrefixPair {
+                    group_id: group_a_id,
+                    prefix: prefix_pair.prefix.clone(),
+                })
+                .collect_vec();
+            tracing::info!(
+                "merge compaction group {} into {} with task {}",
+                group_b_id,
+                group_a_id,
+                compact_task.task_id
+            );
+            return Some(compact_task);
+        }
+        None
+    }
+
+    /// Splits the compaction group with the key prefixes `group` into two at `split_key`, e.g. to
+    /// isolate the tables of a tenant. The prefixes before `split_key` stay in the group, and the
+    /// others are moved to a new group `new_group_id`. The SSTs of the group in `version` are
+    /// partitioned by their key ranges, so that each result holds the SSTs entirely on its side of
+    /// `split_key` in a version otherwise the same as `version`. No SST is rewritten, so it fails
+    /// if an SST straddles `split_key`, which must be compacted with `split_key` as a split
+    /// boundary first. It also fails if `split_key` falls inside the key space of a prefix, if
+    /// either group would be empty, or if any SST of the group is being compacted, since the
+    /// task carries the prefixes of the group.
+    pub fn split_group(
+        &self,
+        version: &HummockVersion,
+        group: &[PrefixPair],
+        new_group_id: u64,
+        split_key: &[u8],
+    ) -> Result<GroupSplit> {
+        let group_id = match group_id(group) {
+            Some(group_id) if group_id != new_group_id => group_id,
+            _ => {
+                return Err(ErrorCode::InternalError(format!(
+                    "cannot split invalid compaction group into group {}",
+                    new_group_id
+                ))
+                .into())
+            }
+        };
+        if let Some(prefix_pair) = group.iter().find(|prefix_pair| {
+            split_key.starts_with(&prefix_pair.prefix) && split_key.len() > prefix_pair.prefix.len()
+        }) {
+            return Err(ErrorCode::InternalError(format!(
+                "split key {:?} falls inside prefix {:?} of compaction group {}",
+                split_key, prefix_pair.prefix, group_id
+            ))
+            .into());
+        }
+        let (left, right): (Vec<_>, Vec<_>) = group
+            .iter()
+            .cloned()
+            .partition(|prefix_pair| prefix_pair.prefix.as_slice() < split_key);
+        if left.is_empty() || right.is_empty() {
+            return Err(ErrorCode::InternalError(format!(
+                "split key {:?} doesn't split compaction group {}",
+                split_key, group_id
+            ))
+            .into());
+        }
+        let mut left_version = HummockVersion {
+            levels: vec![],
+            uncommitted_epochs: vec![],
+            ..version.clone()
+        };
+        let mut right_version = left_version.clone();
+        for level in &version.levels {
+            let level_handler = self
+                .level_handlers
+                .get(level.level_idx as usize)
+                .ok_or_else(|| {
+                    ErrorCode::InternalError(format!(
+                        "level {} of compaction group {} is not tracked",
+                        level.level_idx, group_id
+                    ))
+                })?;
+            if level.table_infos.iter().any(|sst| {
+                sst_overlaps_group(group, sst) && level_handler.is_pending_compact(&sst.id)
+            }) {
+                return Err(ErrorCode::InternalError(format!(
+                    "cannot split compaction group {} while it's being compacted",
+                    group_id
+                ))
+                .into());
+            }
+            let (left_ssts, right_ssts) = split_ssts(group, &level.table_infos, split_key)?;
+            left_version.levels.push(Level {
+                table_infos: left_ssts,
+                ..level.clone()
+            });
+            right_version.levels.push(Level {
+                table_infos: right_ssts,
+                ..level.clone()
+            });
+        }
+        for uncommitted_epoch in &version.uncommitted_epochs {
+            let (left_ssts, right_ssts) = split_ssts(group, &uncommitted_epoch.tables, split_key)?;
+            left_version.uncommitted_epochs.push(UncommittedEpoch {
+                epoch: uncommitted_epoch.epoch,
+                tables: left_ssts,
+            });
+            right_version.uncommitted_epochs.push(UncommittedEpoch {
+                epoch: uncommitted_epoch.epoch,
+                tables: right_ssts,
+            });
+        }
+        let right = right
+            .into_iter()
+            .map(|prefix_pair| PrefixPair {
+                group_id: new_group_id,
+                prefix: prefix_pair.prefix,
+            })
+            .collect_vec();
+        tracing::info!(
+            "split compaction group {} into {} at {:?}",
+            group_id,
+            new_group_id,
+            split_key
+        );
+        Ok(GroupSplit {
+            left,
+            left_version,
+            right,
+            right_version,
+        })
+    }
+
+    /// Returns whether `next_compact_task_id` can be issued without overflowing the id space. An id
+    /// wrapped around could collide with a task in flight and make `report_compact_task` remove the
+    /// wrong task, so no more tasks are issued once the ids are exhausted.
+    fn has_next_task_id(&self) -> bool {
+        if self.next_compact_task_id.checked_add(1).is_none() {
+            tracing::error!(
+                "compact task ids are exhausted at {}",
+                self.next_compact_task_id
+            );
+            return false;
+        }
+        true
+    }
 
+    fn build_compact_task(
+        &mut self,
+        levels: &[Level],
+        ret: SearchResult,
+        safe_epoch: HummockEpoch,
+    ) -> CompactTask {
+        let select_level_id = ret.select_level.level_idx;
+        let target_level_id = ret.target_level.level_idx;
+        let split_ranges = if self.config.key_range_splits > 1 {
+            split_key_range(&input_key_range(&ret), self.config.key_range_splits)
+        } else {
+            ret.split_ranges
+        };
+        let read_level_n = level_statistics(&ret.select_level);
+        let read_level_nplus1 = level_statistics(&ret.target_level);
+        // Compaction never writes more than it reads, so the input is an upper bound of the output.
+        let write = TableSetStatistics {
+            level_idx: target_level_id,
+            size_gb: read_level_n.size_gb + read_level_nplus1.size_gb,
+            cnt: read_level_n.cnt + read_level_nplus1.cnt,
+        };
+        let output_tier =
+            if self.config.cold_level > 0 && target_level_id as usize >= self.config.cold_level {
+                OutputTier::Cold
+            } else {
+                OutputTier::Hot
+            };
         let compact_task = CompactTask {
             input_ssts: vec![ret.select_level, ret.target_level],
-            splits: ret
-                .split_ranges
-                .iter()
-                .map(|v| v.clone().into())
-                .collect_vec(),
-            watermark: HummockEpoch::MAX,
+            splits: split_ranges.into_iter().map(|v| v.into()).collect_vec(),
+            watermark: safe_epoch,
             sorted_output_ssts: vec![],
             task_id: self.next_compact_task_id,
             target_level: target_level_id,
@@ -184,29 +712,248 @@ impl CompactStatus {
                 == self.level_handlers.len() - 1
                 && select_level_id > 0,
             metrics: Some(CompactMetrics {
-                read_level_n: Some(TableSetStatistics {
-                    level_idx: select_level_id,
-                    size_gb: 0f64,
-                    cnt: 0,
-                }),
-                read_level_nplus1: Some(TableSetStatistics {
-                    level_idx: target_level_id,
-                    size_gb: 0f64,
-                    cnt: 0,
-                }),
-                write: Some(TableSetStatistics {
-                    level_idx: target_level_id,
-                    size_gb: 0f64,
-                    cnt: 0,
-                }),
+                read_level_n: Some(read_level_n),
+                read_level_nplus1: Some(read_level_nplus1),
+                write: Some(write),
             }),
             task_status: false,
-            // TODO: fill with compaction group info
+            // Filled by the callers picking for compaction groups.
             prefix_pairs: vec![],
             vnode_mappings: vec![],
+            max_output_writers: self.config.max_output_writers as u32,
+            priority: self.compact_task_priority(levels, select_level_id),
+            vnode_bitmaps: vec![],
+            output_tier: output_tier as i32,
         };
-        self.next_compact_task_id += 1;
-        Some(compact_task)
+        // Checked by `has_next_task_id` before picking.
+        self.next_compact_task_id = self
+            .next_compact_task_id
+            .checked_add(1)
+            .expect("compact task id overflow");
+        self.tasks_issued += 1;
+        compact_task
+    }
+
+    /// Returns the priority of a task compacting `select_level_idx`. Tasks of L0 rank above all the
+    /// others, since L0 files overlap with each other and slow down reads the most. Tasks of the
+    /// same kind are ranked by how many bytes the level is over its target, in KB saturated to 31
+    /// bits. Every byte in L0 is over target.
+    fn compact_task_priority(&self, levels: &[Level], select_level_idx: u32) -> u32 {
+        const L0_PRIORITY: u32 = 1 << 31;
+        let level_size = levels
+            .iter()
+            .find(|level| level.level_idx == select_level_idx)
+            .map_or(0, |level| {
+                level
+                    .table_infos
+                    .iter()
+                    .map(|table| table.file_size)
+                    .sum::<u64>()
+            });
+        if select_level_idx == 0 {
+            return L0_PRIORITY | (level_size >> 10).min(L0_PRIORITY as u64 - 1) as u32;
+        }
+        let level_max_bytes = self.level_max_bytes(levels);
+        let over_target_bytes = level_size.saturating_sub(
+            level_max_bytes
+                .get(select_level_idx as usize)
+                .copied()
+                .unwrap_or(u64::MAX),
+        );
+        (over_target_bytes >> 10).min(L0_PRIORITY as u64 - 1) as u32
+    }
+
+    fn level_max_bytes(&self, levels: &[Level]) -> Vec<u64> {
+        DynamicLevelSelector::new(
+            self.config.clone(),
+            Arc::new(RangeOverlapStrategy::default()),
+        )
+        .level_max_bytes(levels)
+    }
+
+    /// Returns the levels to compact paired with their urgency, sorted with the most urgent first,
+    /// for a scheduler to process levels in order. The urgency is the score used to pick tasks
+    /// relative to the target of the level, so a level over its target has an urgency over 1.
+    /// SSTs being compacted are not counted.
+    pub fn levels_by_urgency(&self, levels: &[Level]) -> Vec<(u32, f64)> {
+        DynamicLevelSelector::new(
+            self.config.clone(),
+            Arc::new(RangeOverlapStrategy::default()),
+        )
+        .levels_by_urgency(levels, &self.level_handlers)
+    }
+
+    pub fn config(&self) -> &CompactionConfig {
+        &self.config
+    }
+
+    /// Returns the total number of tasks issued so far.
+    pub fn tasks_issued(&self) -> u64 {
+        self.tasks_issued
+    }
+
+    /// Returns the number of tasks in flight, i.e. issued but not reported yet, e.g. to hold back
+    /// new tasks when compaction falls behind. A task across levels is counted once.
+    pub fn pending_task_count(&self) -> usize {
+        self.level_handlers
+            .iter()
+            .flat_map(|level_handler| level_handler.pending_tasks_ids())
+            .collect::<HashSet<u64>>()
+            .len()
+    }
+
+    /// Returns whether any task in flight compacts SSTs of the level `level_idx`.
+    pub fn is_level_overlapping_task(&self, level_idx: usize) -> bool {
+        match self.level_handlers.get(level_idx) {
+            Some(level_handler) => !level_handler.pending_tasks_ids().is_empty(),
+            None => false,
+        }
+    }
+
+    /// Recommends the size of SSTs to flush according to the congestion of L0. Each L0 file
+    /// overlapping others adds a layer to merge for reads and compaction, so once there are more
+    /// of them than `level0_trigger_number`, flushing larger SSTs helps to reduce the number of new
+    /// layers. The recommendation grows proportionally with the overlapping files beyond that,
+    /// and stays `default_size` otherwise, including when there are no levels at all.
+    pub fn recommend_flush_size(&self, levels: &[Level], default_size: u64) -> u64 {
+        let overlap_strategy = RangeOverlapStrategy::default();
+        let level0 = match levels.first() {
+            Some(level0) => &level0.table_infos,
+            None => return default_size,
+        };
+        let overlapping_file_count = level0
+            .iter()
+            .filter(|table| {
+                level0.iter().any(|other| {
+                    other.id != table.id && overlap_strategy.check_overlap(table, other)
+                })
+            })
+            .count();
+        let level0_trigger_number = self.config.level0_trigger_number.max(1);
+        if overlapping_file_count <= level0_trigger_number {
+            return default_size;
+        }
+        let recommended_size = (default_size as u128 * overlapping_file_count as u128
+            / level0_trigger_number as u128)
+            .min(default_size as u128 * MAX_FLUSH_SIZE_MULTIPLIER as u128);
+        recommended_size as u64
+    }
+
+    /// Returns whether writes should be stopped since L0 has reached
+    /// `level0_stop_writes_file_number` files. `HummockManager::add_tables` polls it to hold back
+    /// new SSTs until compaction catches up.
+    pub fn should_stop_writes(&self, levels: &[Level]) -> bool {
+        match levels.first() {
+            Some(level0) => {
+                self.config.level0_stop_writes_file_number > 0
+                    && level0.table_infos.len() >= self.config.level0_stop_writes_file_number
+            }
+            None => false,
+        }
+    }
+
+    /// Estimates the IO of the task `get_compact_task` would pick next over `levels` with
+    /// `safe_epoch`, i.e. the bytes of its input SSTs to read plus the bytes to write, which is
+    /// bounded by the input as well. It picks in the same order as `get_compact_task` on a copy of
+    /// the status, so nothing is issued or locked. Returns `None` if no task would be picked.
+    pub fn preview_next_io_cost(&self, levels: &[Level], safe_epoch: HummockEpoch) -> Option<u64> {
+        if !self.has_next_task_id() {
+            return None;
+        }
+        let mut compact_status = self.clone();
+        let ret = compact_status
+            .pick_space_reclaim(levels, safe_epoch)
+            .or_else(|| compact_status.pick_compaction(levels))?;
+        let read_bytes: u64 = ret
+            .select_level
+            .table_infos
+            .iter()
+            .chain(ret.target_level.table_infos.iter())
+            .map(|table| table.file_size)
+            .sum();
+        Some(read_bytes * 2)
+    }
+
+    /// Returns a human-readable summary of the compaction state over `levels`, including the
+    /// size of each level, the tasks in flight and the config.
+    pub fn report(&self, levels: &[Level]) -> String {
+        let mut report = format!(
+            "next compact task id: {}\ntasks issued: {}\nin-flight tasks: {}\n",
+            self.next_compact_task_id,
+            self.tasks_issued,
+            self.pending_task_count()
+        );
+        for level in levels {
+            let pending_file_count = self
+                .level_handlers
+                .get(level.level_idx as usize)
+                .map_or(0, |level_handler| level_handler.get_pending_file_count());
+            report += &format!(
+                "L{}: {} files, {} bytes, {} files compacting\n",
+                level.level_idx,
+                level.table_infos.len(),
+                level
+                    .table_infos
+                    .iter()
+                    .map(|table| table.file_size)
+                    .sum::<u64>(),
+                pending_file_count
+            );
+        }
+        report += &format!("config: {:?}\n", self.config);
+        report
+    }
+
+    /// Renders the LSM tree over `levels` as a Graphviz DOT graph. Each level is a cluster of SST
+    /// nodes labeled with their user key range and size, and each in-flight task is a node with
+    /// edges from its input SSTs.
+    pub fn to_dot(&self, levels: &[Level]) -> String {
+        let format_key = |key: &[u8]| {
+            if key.is_empty() {
+                String::new()
+            } else {
+                hex::encode(user_key(key))
+            }
+        };
+        let mut dot = "digraph lsm {\n    node [shape=box];\n".to_string();
+        let mut in_flight_tasks = BTreeSet::new();
+        let mut edges = String::new();
+        for level in levels {
+            let level_handler = self.level_handlers.get(level.level_idx as usize);
+            dot += &format!(
+                "    subgraph cluster_L{} {{\n        label=\"L{}\";\n",
+                level.level_idx, level.level_idx
+            );
+            for table in &level.table_infos {
+                let key_range = table.key_range.as_ref().map_or(String::new(), |key_range| {
+                    format!(
+                        "[{}, {}]",
+                        format_key(&key_range.left),
+                        format_key(&key_range.right)
+                    )
+                });
+                dot += &format!(
+                    "        sst_{} [label=\"SST {}\\n{}\\n{} bytes\"];\n",
+                    table.id, table.id, key_range, table.file_size
+                );
+                if let Some(task_id) = level_handler
+                    .and_then(|level_handler| level_handler.compacting_task_id(&table.id))
+                {
+                    in_flight_tasks.insert(task_id);
+                    edges += &format!("    sst_{} -> task_{};\n", table.id, task_id);
+                }
+            }
+            dot += "    }\n";
+        }
+        for task_id in in_flight_tasks {
+            dot += &format!(
+                "    task_{} [label=\"task {}\", shape=ellipse];\n",
+                task_id, task_id
+            );
+        }
+        dot += &edges;
+        dot += "}\n";
+        dot
     }
 
     fn pick_compaction(&mut self, levels: &[Level]) -> Option<SearchResult> {
@@ -217,13 +964,316 @@ impl CompactStatus {
         )
     }
 
-    /// Declares a task is either finished or canceled.
+    /// Picks the task reclaiming the most space below `safe_epoch` from the topmost non-L0 level
+    /// having one, if `space_reclaim_first` is set. L0 is skipped since its SSTs overlap each
+    /// other and can't be pushed down one by one, and so is the bottommost level.
+    fn pick_space_reclaim(
+        &mut self,
+        levels: &[Level],
+        safe_epoch: HummockEpoch,
+    ) -> Option<SearchResult> {
+        if !self.config.space_reclaim_first || self.config.tier_only {
+            return None;
+        }
+        let level_count = levels.len().min(self.level_handlers.len());
+        for level_idx in 1..level_count.saturating_sub(1) {
+            let picker = SpaceReclaimPicker::new(
+                self.next_compact_task_id,
+                level_idx,
+                safe_epoch,
+                Arc::new(RangeOverlapStrategy::default()),
+            );
+            if let Some(ret) = picker.pick_compaction(levels, &mut self.level_handlers) {
+                return Some(ret);
+            }
+        }
+        None
+    }
+
+    /// Declares a task is either finished or canceled according to its `task_status`.
     pub fn report_compact_task(&mut self, compact_task: &CompactTask) {
+        if compact_task.task_status {
+            self.finish_compact_task(compact_task);
+        } else {
+            self.cancel_compact_task(compact_task);
+        }
+    }
+
+    /// Declares a task is canceled, which releases its input SSTs as if it had never been issued.
+    /// Nothing of it is recorded, e.g. `last_compacted_epoch` and the total metrics stay the same.
+    pub fn cancel_compact_task(&mut self, compact_task: &CompactTask) {
+        self.release_compact_task(compact_task);
+    }
+
+    /// Declares a task is finished, which releases its input SSTs and records the compaction of
+    /// them. Its result is to be applied by `apply_compact_result`.
+    pub fn finish_compact_task(&mut self, compact_task: &CompactTask) {
+        self.release_compact_task(compact_task);
+        let now = get_timestamp_now();
+        for level in &compact_task.input_ssts {
+            if let Some(timestamp) = self
+                .last_compacted_timestamps
+                .get_mut(level.level_idx as usize)
+            {
+                *timestamp = now;
+            }
+        }
+        self.last_compacted_epoch = Epoch::now().0;
+        if let Some(metrics) = compact_task.metrics.as_ref() {
+            accumulate_statistics(
+                &mut self.total_metrics.read_level_n,
+                metrics.read_level_n.as_ref(),
+            );
+            accumulate_statistics(
+                &mut self.total_metrics.read_level_nplus1,
+                metrics.read_level_nplus1.as_ref(),
+            );
+            accumulate_statistics(&mut self.total_metrics.write, metrics.write.as_ref());
+        }
+    }
+
+    fn release_compact_task(&mut self, compact_task: &CompactTask) {
         for level in &compact_task.input_ssts {
             self.level_handlers[level.level_idx as usize].remove_task(compact_task.task_id);
         }
     }
 
+    /// Returns the metrics accumulated from all finished tasks, e.g. the total size read from the
+    /// upper levels in `read_level_n`.
+    pub fn total_metrics(&self) -> &CompactMetrics {
+        &self.total_metrics
+    }
+
+    /// Returns the epoch when a task was last reported as finished successfully, or
+    /// `INVALID_EPOCH` if none has finished yet.
+    pub fn last_compacted_epoch(&self) -> HummockEpoch {
+        self.last_compacted_epoch
+    }
+
+    /// Collapses the levels of `version` into `new_count` levels after the configured number of
+    /// levels decreases, which moves all SSTs of the removed levels into the new bottom level and
+    /// trims the level handlers accordingly. The config of `self` is expected to be updated with
+    /// the new number of levels by the caller.
+    ///
+    /// The new bottom level stays non-overlapping if the moved SSTs don't overlap with each other
+    /// or with the SSTs already in it. Otherwise it becomes overlapping, and its SSTs are ordered
+    /// from the oldest to the newest, i.e. the ones from the lower levels come first, so that
+    /// readers find the latest version of a key first.
+    pub fn collapse_levels(
+        &mut self,
+        new_count: usize,
+        version: HummockVersion,
+    ) -> Result<HummockVersion> {
+        if new_count < 2 || new_count > self.level_handlers.len() {
+            return Err(ErrorCode::InternalError(format!(
+                "cannot collapse {} levels into {}",
+                self.level_handlers.len(),
+                new_count
+            ))
+            .into());
+        }
+        let bottom_level_idx = new_count - 1;
+        if self.level_handlers[bottom_level_idx..]
+            .iter()
+            .any(|level_handler| level_handler.get_pending_file_count() > 0)
+        {
+            return Err(ErrorCode::InternalError(format!(
+                "cannot collapse levels while L{} or lower levels are being compacted",
+                bottom_level_idx
+            ))
+            .into());
+        }
+
+        let mut new_version = version;
+        let removed_levels = new_version.levels.split_off(new_count);
+        let bottom_level = &mut new_version.levels[bottom_level_idx];
+        let mut table_infos = removed_levels
+            .into_iter()
+            .rev()
+            .flat_map(|level| level.table_infos)
+            .collect_vec();
+        table_infos.append(&mut bottom_level.table_infos);
+
+        let mut sorted_table_infos = table_infos.clone();
+        sorted_table_infos.sort_by(|sst1, sst2| {
+            let a = KeyRange::from(sst1.key_range.as_ref().unwrap());
+            let b = KeyRange::from(sst2.key_range.as_ref().unwrap());
+            a.cmp(&b)
+        });
+        let overlapping = sorted_table_infos.windows(2).any(|pair| {
+            user_key(&pair[0].key_range.as_ref().unwrap().right)
+                >= user_key(&pair[1].key_range.as_ref().unwrap().left)
+        });
+        if overlapping {
+            bottom_level.level_type = LevelType::Overlapping as i32;
+            bottom_level.table_infos = table_infos;
+        } else {
+            bottom_level.table_infos = sorted_table_infos;
+        }
+
+        self.level_handlers.truncate(new_count);
+        self.last_compacted_timestamps.truncate(new_count);
+        Ok(new_version)
+    }
+
+    /// Estimates the bytes a manual compaction over `key_range` would reclaim, i.e. the size of the
+    /// SSTs overlapping it with all data below `safe_epoch`, which are garbage as those in the gc
+    /// hints of `apply_compact_result_with_gc_hints`. SSTs without a key range or a known max epoch
+    /// are not counted.
+    pub fn estimate_manual_reclaim(
+        &self,
+        levels: &[Level],
+        key_range: &KeyRange,
+        safe_epoch: HummockEpoch,
+    ) -> u64 {
+        levels
+            .iter()
+            .flat_map(|level| &level.table_infos)
+            .filter(|sst| match sst.key_range.as_ref() {
+                Some(sst_key_range) => {
+                    KeyRange::from(sst_key_range).full_key_overlap(key_range)
+                        && sst_below_epoch(sst, safe_epoch)
+                }
+                None => false,
+            })
+            .map(|sst| sst.file_size)
+            .sum()
+    }
+
+    /// Returns the levels which have been over their target for longer than
+    /// `level_stuck_timeout_sec` at `now`, i.e. no compaction of them has finished since then.
+    /// L0 is over its target when it has more files than `level0_trigger_number`.
+    pub fn stuck_levels(&self, levels: &[Level], now: u64) -> Vec<u32> {
+        let level_max_bytes = self.level_max_bytes(levels);
+        levels
+            .iter()
+            .filter(|level| {
+                let level_idx = level.level_idx as usize;
+                let last_compacted_timestamp = match self.last_compacted_timestamps.get(level_idx) {
+                    Some(timestamp) => *timestamp,
+                    None => return false,
+                };
+                self.is_over_target(level, &level_max_bytes)
+                    && now.saturating_sub(last_compacted_timestamp)
+                        > self.config.level_stuck_timeout_sec
+            })
+            .map(|level| level.level_idx)
+            .collect_vec()
+    }
+
+    /// Returns whether each of `levels` is eligible to be compacted right now, i.e. it's over its
+    /// target, and it has idle SSTs which are not being compacted, and compacting it is enabled
+    /// by the config. The bottommost level is never eligible, and neither are the non-L0 levels
+    /// when `tier_only` is set. L0 must also have at least `min_l0_sublevels_to_compact` files.
+    pub fn level_eligibility(&self, levels: &[Level]) -> Vec<bool> {
+        let level_max_bytes = self.level_max_bytes(levels);
+        levels
+            .iter()
+            .map(|level| {
+                let level_idx = level.level_idx as usize;
+                let enabled = level_idx < self.config.max_level
+                    && !(self.config.tier_only && level_idx > 0)
+                    && (level_idx > 0
+                        || level.table_infos.len() >= self.config.min_l0_sublevels_to_compact);
+                let has_idle_sst = self
+                    .level_handlers
+                    .get(level_idx)
+                    .map(|handler| {
+                        level
+                            .table_infos
+                            .iter()
+                            .any(|table| !handler.is_pending_compact(&table.id))
+                    })
+                    .unwrap_or(false);
+                enabled && has_idle_sst && self.is_over_target(level, &level_max_bytes)
+            })
+            .collect_vec()
+    }
+
+    /// L0 is over its target when it has more files than `level0_trigger_number`, and the other
+    /// levels when they hold more bytes than `level_max_bytes`. Levels without a target, i.e.
+    /// those beyond `max_level`, are never over it.
+    fn is_over_target(&self, level: &Level, level_max_bytes: &[u64]) -> bool {
+        let level_idx = level.level_idx as usize;
+        if level_idx == 0 {
+            return level.table_infos.len() > self.config.level0_trigger_number;
+        }
+        match level_max_bytes.get(level_idx) {
+            Some(max_bytes) => {
+                level
+                    .table_infos
+                    .iter()
+                    .map(|table| table.file_size)
+                    .sum::<u64>()
+                    > *max_bytes
+            }
+            None => false,
+        }
+    }
+
+    /// Declares that `completed_splits` of `compact_task` are finished while the others are still
+    /// running. The finished part is moved out of `compact_task` into the returned task, which
+    /// holds the completed splits, the output SSTs within them and the input SSTs fully covered by
+    /// them, and can be applied by `apply_compact_result`. These input SSTs are unlocked, and the
+    /// rest stay locked until the remaining `compact_task` is reported.
+    pub fn report_partial_compact_task(
+        &mut self,
+        compact_task: &mut CompactTask,
+        completed_splits: &[KeyRange],
+    ) -> CompactTask {
+        let completed_splits = completed_splits
+            .iter()
+            .map(|split| risingwave_pb::hummock::KeyRange::from(split.clone()))
+            .collect_vec();
+        let (finished_splits, running_splits): (Vec<_>, Vec<_>) = compact_task
+            .splits
+            .drain(..)
+            .partition(|split| completed_splits.contains(split));
+        compact_task.splits = running_splits;
+        let is_finished = |sst: &SstableInfo| {
+            finished_splits
+                .iter()
+                .any(|split| split_contains(split, sst))
+        };
+
+        let mut finished_input_ssts = Vec::with_capacity(compact_task.input_ssts.len());
+        for level in &mut compact_task.input_ssts {
+            let (finished_tables, running_tables): (Vec<_>, Vec<_>) = level
+                .table_infos
+                .drain(..)
+                .partition(|sst| is_finished(sst));
+            level.table_infos = running_tables;
+            self.level_handlers[level.level_idx as usize].remove_ssts_of_task(
+                compact_task.task_id,
+                &finished_tables.iter().map(|sst| sst.id).collect_vec(),
+            );
+            finished_input_ssts.push(Level {
+                level_idx: level.level_idx,
+                level_type: level.level_type,
+                table_infos: finished_tables,
+            });
+        }
+        let (finished_output_ssts, running_output_ssts): (Vec<_>, Vec<_>) = compact_task
+            .sorted_output_ssts
+            .drain(..)
+            .partition(|sst| is_finished(sst));
+        compact_task.sorted_output_ssts = running_output_ssts;
+
+        CompactTask {
+            input_ssts: finished_input_ssts,
+            splits: finished_splits,
+            sorted_output_ssts: finished_output_ssts,
+            task_status: true,
+            ..compact_task.clone()
+        }
+    }
+
+    /// Unlocks the SSTs of the tasks not in `live_task_ids`, e.g. the tasks lost in a restart of
+    /// compactors, so that they can be picked again.
+    pub fn reconcile_live_tasks(&mut self, live_task_ids: &HashSet<u64>) {
+        self.cancel_compaction_tasks_if(|task_id| !live_task_ids.contains(&task_id));
+    }
+
     pub fn cancel_compaction_tasks_if<F: Fn(u64) -> bool>(&mut self, should_cancel: F) -> u32 {
         let mut count: u32 = 0;
         for level in &mut self.level_handlers {
@@ -237,19 +1287,132 @@ impl CompactStatus {
         count
     }
 
-    /// Applies the compact task result and get a new hummock version.
+    /// Merges the results of the sub-tasks split by `get_compact_tasks` into the result of their
+    /// task, whose output is the output of all the sub-tasks. The task fails if any of the
+    /// sub-tasks failed. It fails if there are no sub-tasks, or they are of different tasks.
+    pub fn merge_sub_tasks(sub_tasks: Vec<CompactTask>) -> Result<CompactTask> {
+        let mut sub_tasks = sub_tasks.into_iter();
+        let mut compact_task = sub_tasks.next().ok_or_else(|| {
+            ErrorCode::InternalError("no sub-tasks of compact task to merge".to_string())
+        })?;
+        compact_task.vnode_bitmaps.clear();
+        for sub_task in sub_tasks {
+            if sub_task.task_id != compact_task.task_id {
+                return Err(ErrorCode::InternalError(format!(
+                    "sub-task of compact task {} can't be merged into compact task {}",
+                    sub_task.task_id, compact_task.task_id
+                ))
+                .into());
+            }
+            compact_task.task_status &= sub_task.task_status;
+            compact_task
+                .sorted_output_ssts
+                .extend(sub_task.sorted_output_ssts);
+        }
+        Ok(compact_task)
+    }
+
+    /// Applies the compact task result and get a new hummock version. It fails if the ids of the
+    /// output SSTs are duplicated, or collide with the ids of the SSTs in the target level, or if
+    /// any input SST doesn't exist in its level of the base version.
     pub fn apply_compact_result(
         compact_task: &CompactTask,
         based_hummock_version: HummockVersion,
-    ) -> HummockVersion {
+    ) -> Result<HummockVersion> {
+        Ok(Self::apply_compact_result_with_gc_hints(compact_task, based_hummock_version)?.0)
+    }
+
+    /// Applies the compact task result like `apply_compact_result`, and also returns the ids of
+    /// the removed SSTs, each with a hint of whether it can be deleted from the object store
+    /// immediately. An SST can if all its data is below the safe epoch of the new version,
+    /// otherwise it must linger for the readers below the safe epoch. The safe epoch is capped by
+    /// `max_committed_epoch` of the version, so that recently written data is never judged as
+    /// garbage by a watermark beyond what has been committed.
+    pub fn apply_compact_result_with_gc_hints(
+        compact_task: &CompactTask,
+        based_hummock_version: HummockVersion,
+    ) -> Result<(HummockVersion, Vec<(HummockSSTableId, bool)>)> {
+        let mut output_ids = HashSet::with_capacity(compact_task.sorted_output_ssts.len());
+        for sst in &compact_task.sorted_output_ssts {
+            if !output_ids.insert(sst.id) {
+                return Err(ErrorCode::InternalError(format!(
+                    "duplicate output SST {} of compact task {}",
+                    sst.id, compact_task.task_id
+                ))
+                .into());
+            }
+        }
+        if let Some(sst) = based_hummock_version.levels[compact_task.target_level as usize]
+            .table_infos
+            .iter()
+            .find(|sst| output_ids.contains(&sst.id))
+        {
+            return Err(ErrorCode::InternalError(format!(
+                "output SST {} of compact task {} already exists in L{}",
+                sst.id, compact_task.task_id, compact_task.target_level
+            ))
+            .into());
+        }
+        // An input SST missing from the base version has been removed by another operation, and
+        // applying the result would make the version diverge from the object store.
+        let mut missing_ids = vec![];
+        for input_level in &compact_task.input_ssts {
+            let level_ids: HashSet<u64> = based_hummock_version
+                .levels
+                .get(input_level.level_idx as usize)
+                .map(|level| level.table_infos.iter().map(|sst| sst.id).collect())
+                .unwrap_or_default();
+            missing_ids.extend(
+                input_level
+                    .table_infos
+                    .iter()
+                    .map(|sst| sst.id)
+                    .filter(|id| !level_ids.contains(id)),
+            );
+        }
+        if !missing_ids.is_empty() {
+            return Err(ErrorCode::InternalError(format!(
+                "input SSTs {:?} of compact task {} don't exist",
+                missing_ids, compact_task.task_id
+            ))
+            .into());
+        }
+
         let mut new_version = based_hummock_version;
         new_version.safe_epoch = std::cmp::max(new_version.safe_epoch, compact_task.watermark);
+        let gc_epoch = std::cmp::min(new_version.safe_epoch, new_version.max_committed_epoch);
         let mut removed_table: HashSet<u64> = HashSet::default();
+        let mut gc_hints = vec![];
         for input_level in &compact_task.input_ssts {
             for table in &input_level.table_infos {
                 removed_table.insert(table.id);
+                gc_hints.push((table.id, sst_below_epoch(table, gc_epoch)));
             }
         }
+        // Compaction never writes data of a newer epoch than its input, whose bound is unknown if
+        // the bound of any input SST is.
+        let input_ssts = || {
+            compact_task
+                .input_ssts
+                .iter()
+                .flat_map(|level| &level.table_infos)
+        };
+        let max_epoch = if input_ssts().any(|sst| sst.max_epoch == INVALID_EPOCH) {
+            INVALID_EPOCH
+        } else {
+            input_ssts()
+                .map(|sst| sst.max_epoch)
+                .max()
+                .unwrap_or(INVALID_EPOCH)
+        };
+        let output_ssts = compact_task
+            .sorted_output_ssts
+            .iter()
+            .map(|sst| SstableInfo {
+                max_epoch,
+                ..sst.clone()
+            })
+            .collect_vec();
         if compact_task.target_level == 0 {
             assert_eq!(compact_task.input_ssts[0].level_idx, 0);
             let mut new_table_infos = vec![];
@@ -258,7 +1421,7 @@ impl CompactStatus {
                 if !removed_table.contains(&table.id) {
                     new_table_infos.push(new_version.levels[0].table_infos[idx].clone());
                 } else if !find_remove_position {
-                    new_table_infos.extend(compact_task.sorted_output_ssts.clone());
+                    new_table_infos.extend(output_ssts.clone());
                     find_remove_position = true;
                 }
             }
@@ -271,7 +1434,7 @@ impl CompactStatus {
             }
             new_version.levels[compact_task.target_level as usize]
                 .table_infos
-                .extend(compact_task.sorted_output_ssts.clone());
+                .extend(output_ssts);
             new_version.levels[compact_task.target_level as usize]
                 .table_infos
                 .sort_by(|sst1, sst2| {
@@ -280,8 +1443,203 @@ impl CompactStatus {
                     a.cmp(&b)
                 });
         }
-        new_version
+        Ok((new_version, gc_hints))
+    }
+
+    /// Advances the safe epoch of `version` to `new_epoch` without compacting anything, e.g. after
+    /// the retention of old data is shortened, so that the following tasks can drop the versions of
+    /// keys below it. It fails if `new_epoch` is below the current safe epoch, which readers may
+    /// still rely on, or above the max committed epoch.
+    pub fn advance_safe_epoch(
+        version: HummockVersion,
+        new_epoch: HummockEpoch,
+    ) -> Result<HummockVersion> {
+        if new_epoch < version.safe_epoch {
+            return Err(ErrorCode::InternalError(format!(
+                "safe epoch can't regress from {} to {}",
+                version.safe_epoch, new_epoch
+            ))
+            .into());
+        }
+        if new_epoch > version.max_committed_epoch {
+            return Err(ErrorCode::InternalError(format!(
+                "safe epoch {} can't exceed the max committed epoch {}",
+                new_epoch, version.max_committed_epoch
+            ))
+            .into());
+        }
+        let mut new_version = version;
+        new_version.safe_epoch = new_epoch;
+        Ok(new_version)
+    }
+}
+
+/// Returns the number and total size of the SSTs in `level`.
+fn level_statistics(level: &Level) -> TableSetStatistics {
+    TableSetStatistics {
+        level_idx: level.level_idx,
+        size_gb: level
+            .table_infos
+            .iter()
+            .map(|table| table.file_size)
+            .sum::<u64>() as f64
+            / BYTES_PER_GB as f64,
+        cnt: level.table_infos.len() as u64,
+    }
+}
+
+fn accumulate_statistics(
+    total: &mut Option<TableSetStatistics>,
+    statistics: Option<&TableSetStatistics>,
+) {
+    if let Some(statistics) = statistics {
+        let total = total.get_or_insert_with(TableSetStatistics::default);
+        total.size_gb += statistics.size_gb;
+        total.cnt += statistics.cnt;
+    }
+}
+
+/// Splits `compact_task` into `parallelism` sub-tasks, each covering a range of virtual nodes of
+/// all the tables in its input. A task whose input holds no tables is not split. Neither is a task
+/// whose target level is not L0, as the outputs of the sub-tasks overlap in key range, which only
+/// an overlapping level allows.
+fn split_compact_task_by_vnode(compact_task: CompactTask, parallelism: usize) -> Vec<CompactTask> {
+    let table_ids: BTreeSet<u32> = compact_task
+        .input_ssts
+        .iter()
+        .flat_map(|level| &level.table_infos)
+        .flat_map(|sst| sst.vnode_bitmaps.iter().map(|bitmap| bitmap.table_id))
+        .collect();
+    let parallelism = parallelism.min(VIRTUAL_NODE_COUNT);
+    if parallelism <= 1 || table_ids.is_empty() || compact_task.target_level != 0 {
+        return vec![compact_task];
+    }
+    let bitmap_len = VIRTUAL_NODE_COUNT / 8;
+    (0..parallelism)
+        .map(|i| {
+            let mut bitmap = vec![0u8; bitmap_len];
+            for vnode in
+                i * VIRTUAL_NODE_COUNT / parallelism..(i + 1) * VIRTUAL_NODE_COUNT / parallelism
+            {
+                bitmap[vnode >> 3] |= 1 << (vnode & 0b111);
+            }
+            let mut sub_task = compact_task.clone();
+            sub_task.vnode_bitmaps = table_ids
+                .iter()
+                .map(|table_id| VNodeBitmap {
+                    table_id: *table_id,
+                    maplen: bitmap_len as u32,
+                    bitmap: bitmap.clone(),
+                })
+                .collect_vec();
+            sub_task
+        })
+        .collect_vec()
+}
+
+/// Returns the id of the compaction group with the key prefixes `group`, or `None` if there are no
+/// prefixes or they belong to different groups.
+fn group_id(group: &[PrefixPair]) -> Option<u64> {
+    let group_id = group.first()?.group_id;
+    if group
+        .iter()
+        .any(|prefix_pair| prefix_pair.group_id != group_id)
+    {
+        return None;
+    }
+    Some(group_id)
+}
+
+/// Whether all keys of `sst` belong to the compaction group with the key prefixes `group`.
+fn sst_in_group(group: &[PrefixPair], sst: &SstableInfo) -> bool {
+    match &sst.key_range {
+        Some(key_range) => group.iter().any(|prefix_pair| {
+            user_key(&key_range.left).starts_with(&prefix_pair.prefix)
+                && user_key(&key_range.right).starts_with(&prefix_pair.prefix)
+        }),
+        None => false,
+    }
+}
+
+/// Partitions the SSTs of the compaction group with the key prefixes `group` among `ssts` into the
+/// ones entirely below `split_key` and the ones entirely at or above it. It fails if any of them
+/// straddles `split_key`.
+fn split_ssts(
+    group: &[PrefixPair],
+    ssts: &[SstableInfo],
+    split_key: &[u8],
+) -> Result<(Vec<SstableInfo>, Vec<SstableInfo>)> {
+    let mut left = vec![];
+    let mut right = vec![];
+    for sst in ssts.iter().filter(|sst| sst_overlaps_group(group, sst)) {
+        let key_range = match &sst.key_range {
+            Some(key_range) => key_range,
+            None => continue,
+        };
+        if user_key(&key_range.right) < split_key {
+            left.push(sst.clone());
+        } else if user_key(&key_range.left) >= split_key {
+            right.push(sst.clone());
+        } else {
+            return Err(ErrorCode::InternalError(format!(
+                "SST {} straddles split key {:?}",
+                sst.id, split_key
+            ))
+            .into());
+        }
+    }
+    Ok((left, right))
+}
+
+/// Whether any key of `sst` may belong to the compaction group with the key prefixes `group`, i.e.
+/// its key range intersects the key space of a prefix. Unlike `sst_in_group`, it also holds for
+/// an SST straddling the boundary of the group.
+fn sst_overlaps_group(group: &[PrefixPair], sst: &SstableInfo) -> bool {
+    match &sst.key_range {
+        Some(key_range) => {
+            let left = user_key(&key_range.left);
+            let right = user_key(&key_range.right);
+            group.iter().any(|prefix_pair| {
+                let prefix = prefix_pair.prefix.as_slice();
+                (left < prefix || left.starts_with(prefix)) && right >= prefix
+            })
+        }
+        None => false,
+    }
+}
+
+/// Returns whether all data in `sst` is below `epoch`, judged by its `max_epoch`. An SST without a
+/// known bound never is.
+fn sst_below_epoch(sst: &SstableInfo, epoch: HummockEpoch) -> bool {
+    sst.max_epoch != INVALID_EPOCH && sst.max_epoch < epoch
+}
+
+/// Returns the combined key range of the input SSTs of `ret`.
+fn input_key_range(ret: &SearchResult) -> KeyRange {
+    let mut tables = ret
+        .select_level
+        .table_infos
+        .iter()
+        .chain(ret.target_level.table_infos.iter())
+        .map(|sst| KeyRange::from(sst.key_range.as_ref().unwrap()));
+    let mut key_range = tables.next().unwrap_or_else(KeyRange::inf);
+    for other in tables {
+        key_range.full_key_extend(&other);
+    }
+    key_range
+}
+
+/// Returns whether the key range of `sst` lies in `split`. An empty bound of `split` means it's
+/// unbounded on that side, which is how the splits generated by pickers look like.
+fn split_contains(split: &risingwave_pb::hummock::KeyRange, sst: &SstableInfo) -> bool {
+    if split.inf {
+        return true;
     }
+    let key_range = sst.key_range.as_ref().unwrap();
+    (split.left.is_empty()
+        || VersionedComparator::compare_key(&split.left, &key_range.left) != Ordering::Greater)
+        && (split.right.is_empty()
+            || VersionedComparator::compare_key(&key_range.right, &split.right) == Ordering::Less)
 }
 
 impl Transactional for CompactStatus {
@@ -314,35 +1672,2509 @@ impl From<&CompactStatus> for risingwave_pb::hummock::CompactStatus {
         risingwave_pb::hummock::CompactStatus {
             level_handlers: status.level_handlers.iter().map_into().collect(),
             next_compact_task_id: status.next_compact_task_id,
+            tasks_issued: status.tasks_issued,
+            total_metrics: Some(status.total_metrics.clone()),
+            last_compacted_epoch: status.last_compacted_epoch,
         }
     }
 }
 
+/// The config and the selector aren't persisted, so the status converted back uses the defaults.
+/// Use `CompactStatus::restore` to load it into a status created with the configured ones.
 impl From<&risingwave_pb::hummock::CompactStatus> for CompactStatus {
     fn from(status: &risingwave_pb::hummock::CompactStatus) -> Self {
         CompactStatus {
             level_handlers: status.level_handlers.iter().map_into().collect(),
             next_compact_task_id: status.next_compact_task_id,
+            tasks_issued: status.tasks_issued,
+            total_metrics: status.total_metrics.clone().unwrap_or_default(),
+            last_compacted_epoch: status.last_compacted_epoch,
+            last_compacted_timestamps: vec![get_timestamp_now(); status.level_handlers.len()],
+            config: Arc::new(CompactionConfig::default()),
             compaction_selector: Box::new(DynamicLevelSelector::default()),
+            table_stats_provider: None,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use bytes::Bytes;
+    use risingwave_hummock_sdk::key::FullKey;
+
     use super::*;
+    use crate::hummock::compaction::level_selector::tests::generate_tables;
+    use crate::hummock::compaction::tier_compaction_picker::tests::generate_table;
+    use crate::hummock::test_utils::iterator_test_key_of_epoch;
+    use crate::storage::MemStore;
 
-    #[tokio::test]
-    async fn test_serde() -> Result<()> {
-        let origin = CompactStatus {
-            next_compact_task_id: 4,
-            ..Default::default()
-        };
-        let ser = risingwave_pb::hummock::CompactStatus::from(&origin).encode_to_vec();
-        let de = risingwave_pb::hummock::CompactStatus::decode(&mut Cursor::new(ser));
-        let de = (&de.unwrap()).into();
+    /// Workload of a compaction group driven by `simulate_scheduling`.
+    pub struct GroupWorkload {
+        /// Prefix of the keys written to the group, which must differ among groups.
+        pub table_prefix: u64,
+        /// Number of SSTs flushed into L0 of the group in each iteration.
+        pub flush_per_iteration: usize,
+        pub levels: Vec<Level>,
+    }
+
+    impl GroupWorkload {
+        pub fn new(table_prefix: u64, flush_per_iteration: usize, max_level: usize) -> Self {
+            let levels = (0..=max_level)
+                .map(|level_idx| Level {
+                    level_idx: level_idx as u32,
+                    level_type: if level_idx == 0 {
+                        LevelType::Overlapping as i32
+                    } else {
+                        LevelType::Nonoverlapping as i32
+                    },
+                    table_infos: vec![],
+                })
+                .collect_vec();
+            Self {
+                table_prefix,
+                flush_per_iteration,
+                levels,
+            }
+        }
+    }
+
+    /// Drives the compaction groups in `workloads` through `status` for `iterations` rounds and
+    /// returns the number of tasks each group gets. In each round, every group flushes its SSTs
+    /// into L0 first. Then the groups are asked for tasks in turn until none of them can be
+    /// compacted any more, and all these tasks finish at the end of the round, each of which
+    /// merges its input SSTs into a single SST in the target level.
+    pub fn simulate_scheduling(
+        status: &mut CompactStatus,
+        workloads: &mut [GroupWorkload],
+        iterations: usize,
+    ) -> Vec<usize> {
+        let mut task_counts = vec![0; workloads.len()];
+        let mut next_sst_id = workloads
+            .iter()
+            .flat_map(|workload| &workload.levels)
+            .flat_map(|level| &level.table_infos)
+            .map(|sst| sst.id + 1)
+            .max()
+            .unwrap_or(1);
+        for epoch in 1..=iterations as u64 {
+            for workload in workloads.iter_mut() {
+                for i in 0..workload.flush_per_iteration {
+                    workload.levels[0].table_infos.push(generate_table(
+                        next_sst_id,
+                        workload.table_prefix,
+                        i * 100,
+                        i * 100 + 99,
+                        epoch,
+                    ));
+                    next_sst_id += 1;
+                }
+            }
+
+            let mut running_tasks = vec![];
+            loop {
+                let mut picked = false;
+                for (group, workload) in workloads.iter().enumerate() {
+                    if let Some(compact_task) =
+                        status.get_compact_task(&workload.levels, HummockEpoch::MAX)
+                    {
+                        running_tasks.push((group, compact_task));
+                        task_counts[group] += 1;
+                        picked = true;
+                    }
+                }
+                if !picked {
+                    break;
+                }
+            }
+
+            for (group, mut compact_task) in running_tasks {
+                let mut key_range: Option<KeyRange> = None;
+                let mut file_size = 0;
+                for sst in compact_task
+                    .input_ssts
+                    .iter()
+                    .flat_map(|level| &level.table_infos)
+                {
+                    let sst_key_range = KeyRange::from(sst.key_range.as_ref().unwrap());
+                    match key_range.as_mut() {
+                        Some(key_range) => key_range.full_key_extend(&sst_key_range),
+                        None => key_range = Some(sst_key_range),
+                    }
+                    file_size += sst.file_size;
+                }
+                compact_task.sorted_output_ssts = vec![SstableInfo {
+                    id: next_sst_id,
+                    key_range: key_range.map(Into::into),
+                    file_size,
+                    ..Default::default()
+                }];
+                next_sst_id += 1;
+                status.report_compact_task(&compact_task);
+                let version = HummockVersion {
+                    levels: std::mem::take(&mut workloads[group].levels),
+                    ..Default::default()
+                };
+                workloads[group].levels =
+                    CompactStatus::apply_compact_result(&compact_task, version)
+                        .unwrap()
+                        .levels;
+            }
+        }
+        task_counts
+    }
+
+    /// A trivial selector which always compacts all idle files in L0 into L0.
+    #[derive(Clone)]
+    struct L0Selector;
+
+    impl LevelSelector for L0Selector {
+        fn need_compaction(&self, levels: &[Level], level_handlers: &mut [LevelHandler]) -> bool {
+            levels[0]
+                .table_infos
+                .iter()
+                .any(|table| !level_handlers[0].is_pending_compact(&table.id))
+        }
+
+        fn pick_compaction(
+            &self,
+            task_id: u64,
+            levels: &[Level],
+            level_handlers: &mut [LevelHandler],
+        ) -> Option<SearchResult> {
+            let select_tables = levels[0]
+                .table_infos
+                .iter()
+                .filter(|table| !level_handlers[0].is_pending_compact(&table.id))
+                .cloned()
+                .collect_vec();
+            if select_tables.is_empty() {
+                return None;
+            }
+            level_handlers[0].add_pending_task(task_id, &select_tables);
+            Some(SearchResult {
+                select_level: Level {
+                    level_idx: 0,
+                    level_type: LevelType::Overlapping as i32,
+                    table_infos: select_tables,
+                },
+                target_level: Level {
+                    level_idx: 0,
+                    level_type: LevelType::Overlapping as i32,
+                    table_infos: vec![],
+                },
+                split_ranges: vec![KeyRange::inf()],
+            })
+        }
+
+        fn name(&self) -> &'static str {
+            "L0Selector"
+        }
+
+        fn clone_box(&self) -> Box<dyn LevelSelector> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serde() -> Result<()> {
+        let origin = CompactStatus {
+            next_compact_task_id: 4,
+            ..Default::default()
+        };
+        let ser = risingwave_pb::hummock::CompactStatus::from(&origin).encode_to_vec();
+        let de = risingwave_pb::hummock::CompactStatus::decode(&mut Cursor::new(ser));
+        let de = (&de.unwrap()).into();
         assert_eq!(origin, de);
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_get_corrupted() -> Result<()> {
+        let meta_store = MemStore::default();
+        assert!(CompactStatus::get(&meta_store).await?.is_none());
+
+        meta_store
+            .put_cf(
+                CompactStatus::cf_name(),
+                CompactStatus::key().as_bytes().to_vec(),
+                vec![0xff; 8],
+            )
+            .await?;
+        let err = CompactStatus::get(&meta_store).await.unwrap_err();
+        assert!(matches!(err.inner(), ErrorCode::InternalError(_)));
+
+        let origin = CompactStatus {
+            next_compact_task_id: 4,
+            ..Default::default()
+        };
+        meta_store
+            .put_cf(
+                CompactStatus::cf_name(),
+                CompactStatus::key().as_bytes().to_vec(),
+                risingwave_pb::hummock::CompactStatus::from(&origin).encode_to_vec(),
+            )
+            .await?;
+        assert_eq!(CompactStatus::get(&meta_store).await?, Some(origin));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_selector() {
+        let mut compact_status = CompactStatus::with_selector(
+            Arc::new(CompactionConfig::default()),
+            Box::new(L0Selector),
+        );
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![
+                    generate_table(1, 1, 100, 200, 2),
+                    generate_table(2, 1, 150, 250, 2),
+                ],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![generate_table(3, 1, 0, 300, 1)],
+            },
+        ];
+        let task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(task.input_ssts[0].level_idx, 0);
+        assert_eq!(task.input_ssts[0].table_infos.len(), 2);
+        assert_eq!(task.target_level, 0);
+        assert!(compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .is_none());
+
+        compact_status.report_compact_task(&task);
+        let task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(task.task_id, 2);
+        assert_eq!(task.input_ssts[0].table_infos.len(), 2);
+        compact_status.report_compact_task(&task);
+
+        // A cloned status keeps picking with the custom selector.
+        let mut cloned_status = compact_status.clone();
+        assert_eq!(cloned_status.compaction_selector.name(), "L0Selector");
+        let task = cloned_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(task.task_id, 3);
+        assert_eq!(task.target_level, 0);
+
+        // So does a status restored from the persisted one, which alone uses the default selector.
+        let compact_status_pb = risingwave_pb::hummock::CompactStatus::from(&compact_status);
+        let stored = CompactStatus::from(&compact_status_pb);
+        assert_eq!(stored.compaction_selector.name(), "DynamicLevelSelector");
+        let mut restored_status = CompactStatus::with_selector(
+            Arc::new(CompactionConfig::default()),
+            Box::new(L0Selector),
+        );
+        restored_status.restore(stored);
+        assert_eq!(restored_status, compact_status);
+        let task = restored_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(task.task_id, 3);
+        assert_eq!(task.target_level, 0);
+    }
+
+    /// A selector which always picks L0 into a level out of range.
+    #[derive(Clone)]
+    struct OutOfRangeSelector;
+
+    impl LevelSelector for OutOfRangeSelector {
+        fn need_compaction(&self, _levels: &[Level], _level_handlers: &mut [LevelHandler]) -> bool {
+            true
+        }
+
+        fn pick_compaction(
+            &self,
+            task_id: u64,
+            levels: &[Level],
+            level_handlers: &mut [LevelHandler],
+        ) -> Option<SearchResult> {
+            level_handlers[0].add_pending_task(task_id, &levels[0].table_infos);
+            Some(SearchResult {
+                select_level: levels[0].clone(),
+                target_level: Level {
+                    level_idx: level_handlers.len() as u32,
+                    level_type: LevelType::Nonoverlapping as i32,
+                    table_infos: vec![],
+                },
+                split_ranges: vec![KeyRange::inf()],
+            })
+        }
+
+        fn name(&self) -> &'static str {
+            "OutOfRangeSelector"
+        }
+
+        fn clone_box(&self) -> Box<dyn LevelSelector> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[test]
+    fn test_out_of_range_target_level() {
+        let mut compact_status = CompactStatus::with_selector(
+            Arc::new(CompactionConfig::default()),
+            Box::new(OutOfRangeSelector),
+        );
+        let levels = vec![Level {
+            level_idx: 0,
+            level_type: LevelType::Overlapping as i32,
+            table_infos: vec![generate_table(1, 1, 100, 200, 1)],
+        }];
+        assert!(compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .is_none());
+        // The files locked by the dropped task are released.
+        assert!(!compact_status.level_handlers[0].is_pending_compact(&1));
+        assert_eq!(compact_status.next_compact_task_id, 1);
+        assert_eq!(compact_status.tasks_issued(), 0);
+    }
+
+    #[test]
+    fn test_max_output_writers() {
+        let config = CompactionConfig {
+            max_output_writers: 4,
+            ..Default::default()
+        };
+        let mut compact_status =
+            CompactStatus::with_selector(Arc::new(config), Box::new(L0Selector));
+        let levels = vec![Level {
+            level_idx: 0,
+            level_type: LevelType::Overlapping as i32,
+            table_infos: vec![generate_table(1, 1, 100, 200, 1)],
+        }];
+        let compact_task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(compact_task.max_output_writers, 4);
+    }
+
+    #[test]
+    fn test_stuck_levels() {
+        let config = CompactionConfig {
+            max_bytes_for_level_base: 100,
+            max_level: 2,
+            level0_trigger_number: 2,
+            level_stuck_timeout_sec: 60,
+            ..Default::default()
+        };
+        let mut compact_status =
+            CompactStatus::with_selector(Arc::new(config), Box::new(L0Selector));
+        let mut levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: (1..=3)
+                    .map(|sst_id| generate_table(sst_id, 1, 100, 200, sst_id))
+                    .collect_vec(),
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![],
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![generate_table(4, 1, 0, 300, 1)],
+            },
+        ];
+        let now = get_timestamp_now();
+        assert!(compact_status.stuck_levels(&levels, now).is_empty());
+        assert_eq!(compact_status.stuck_levels(&levels, now + 61), vec![0]);
+
+        // A finished compaction makes progress on L0.
+        let mut compact_task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        compact_task.task_status = true;
+        compact_status.report_compact_task(&compact_task);
+        let now = get_timestamp_now();
+        assert!(compact_status.stuck_levels(&levels, now + 30).is_empty());
+        assert_eq!(compact_status.stuck_levels(&levels, now + 61), vec![0]);
+
+        // L0 is no longer over its target.
+        levels[0].table_infos.truncate(1);
+        assert!(compact_status.stuck_levels(&levels, now + 61).is_empty());
+    }
+
+    #[test]
+    fn test_cross_group_compact_task() {
+        let mut compact_status = CompactStatus::new(Arc::new(CompactionConfig {
+            max_level: 2,
+            ..Default::default()
+        }));
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![
+                    generate_table(1, 1, 100, 200, 1),
+                    generate_table(2, 2, 100, 200, 1),
+                    generate_table(3, 3, 100, 200, 1),
+                ],
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![],
+            },
+        ];
+        let group = |group_id, prefix: &str| {
+            vec![PrefixPair {
+                group_id,
+                prefix: prefix.as_bytes().to_vec(),
+            }]
+        };
+        let group_a = group(1, "001_");
+        let group_b = group(2, "002_");
+
+        // Only an explicit request merges groups, and a group can't be merged with itself.
+        assert!(compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .is_none());
+        assert!(compact_status
+            .get_cross_group_compact_task(&levels, &group_a, &group(1, "002_"), HummockEpoch::MAX)
+            .is_none());
+        assert!(compact_status
+            .get_cross_group_compact_task(&levels, &group_a, &[], HummockEpoch::MAX)
+            .is_none());
+
+        let compact_task = compact_status
+            .get_cross_group_compact_task(&levels, &group_a, &group_b, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(compact_task.target_level, 1);
+        assert_eq!(
+            compact_task.input_ssts[0]
+                .table_infos
+                .iter()
+                .map(|sst| sst.id)
+                .collect_vec(),
+            vec![1, 2]
+        );
+        assert!(compact_task.input_ssts[1].table_infos.is_empty());
+        assert_eq!(compact_task.prefix_pairs.len(), 2);
+        assert!(compact_task
+            .prefix_pairs
+            .iter()
+            .all(|prefix_pair| prefix_pair.group_id == 1));
+
+        // The SSTs of the groups are locked by the task.
+        assert!(compact_status
+            .get_cross_group_compact_task(&levels, &group_a, &group_b, HummockEpoch::MAX)
+            .is_none());
+
+        // Levels not tracked by the status are skipped.
+        let mut compact_status = CompactStatus::new(Arc::new(CompactionConfig {
+            max_level: 0,
+            ..Default::default()
+        }));
+        assert!(compact_status
+            .get_cross_group_compact_task(&levels, &group_a, &group_b, HummockEpoch::MAX)
+            .is_none());
+    }
+
+    #[test]
+    fn test_compact_tasks_split_by_vnode() {
+        let config = CompactionConfig {
+            parallelism: 4,
+            ..Default::default()
+        };
+        let mut compact_status =
+            CompactStatus::with_selector(Arc::new(config), Box::new(L0Selector));
+        let with_tables = |mut sst: SstableInfo, table_ids: &[u32]| {
+            sst.vnode_bitmaps = table_ids
+                .iter()
+                .map(|table_id| VNodeBitmap {
+                    table_id: *table_id,
+                    maplen: 0,
+                    bitmap: vec![],
+                })
+                .collect_vec();
+            sst
+        };
+        let levels = vec![Level {
+            level_idx: 0,
+            level_type: LevelType::Overlapping as i32,
+            table_infos: vec![
+                with_tables(generate_table(1, 1, 100, 200, 1), &[1]),
+                with_tables(generate_table(2, 1, 150, 250, 2), &[1, 2]),
+            ],
+        }];
+
+        let sub_tasks = compact_status.get_compact_tasks(&levels, HummockEpoch::MAX);
+        assert_eq!(sub_tasks.len(), 4);
+        assert!(sub_tasks.iter().all(
+            |sub_task| sub_task.task_id == 1 && sub_task.input_ssts == sub_tasks[0].input_ssts
+        ));
+        for table_id in [1, 2] {
+            let bitmaps = sub_tasks
+                .iter()
+                .map(|sub_task| {
+                    &sub_task
+                        .vnode_bitmaps
+                        .iter()
+                        .find(|bitmap| bitmap.table_id == table_id)
+                        .unwrap()
+                        .bitmap
+                })
+                .collect_vec();
+            // The sub-tasks cover each virtual node exactly once.
+            for vnode in 0..VIRTUAL_NODE_COUNT {
+                let covered = bitmaps
+                    .iter()
+                    .filter(|bitmap| bitmap[vnode >> 3] & (1 << (vnode & 0b111)) != 0)
+                    .count();
+                assert_eq!(covered, 1);
+            }
+        }
+
+        // The SSTs are locked by the sub-tasks as a whole.
+        assert!(compact_status
+            .get_compact_tasks(&levels, HummockEpoch::MAX)
+            .is_empty());
+
+        // The sub-tasks are merged into their task, which fails if any of them failed.
+        let done_sub_tasks = sub_tasks
+            .iter()
+            .enumerate()
+            .map(|(i, sub_task)| {
+                let mut sub_task = sub_task.clone();
+                sub_task.task_status = true;
+                sub_task.sorted_output_ssts = vec![generate_table(10 + i as u64, 1, 100, 250, 3)];
+                sub_task
+            })
+            .collect_vec();
+        let compact_task = CompactStatus::merge_sub_tasks(done_sub_tasks.clone()).unwrap();
+        assert!(compact_task.task_status);
+        assert!(compact_task.vnode_bitmaps.is_empty());
+        assert_eq!(
+            compact_task
+                .sorted_output_ssts
+                .iter()
+                .map(|sst| sst.id)
+                .collect_vec(),
+            vec![10, 11, 12, 13]
+        );
+        let mut failed_sub_tasks = done_sub_tasks.clone();
+        failed_sub_tasks[2].task_status = false;
+        assert!(
+            !CompactStatus::merge_sub_tasks(failed_sub_tasks)
+                .unwrap()
+                .task_status
+        );
+        let mut other_sub_tasks = done_sub_tasks;
+        other_sub_tasks[1].task_id = 2;
+        assert!(CompactStatus::merge_sub_tasks(other_sub_tasks).is_err());
+        assert!(CompactStatus::merge_sub_tasks(vec![]).is_err());
+        compact_status.report_compact_task(&compact_task);
+
+        // A task of SSTs without tables is not split.
+        let levels = vec![Level {
+            level_idx: 0,
+            level_type: LevelType::Overlapping as i32,
+            table_infos: vec![generate_table(3, 1, 100, 200, 3)],
+        }];
+        let sub_tasks = compact_status.get_compact_tasks(&levels, HummockEpoch::MAX);
+        assert_eq!(sub_tasks.len(), 1);
+        assert!(sub_tasks[0].vnode_bitmaps.is_empty());
+
+        // Neither is a task to a non-overlapping level.
+        let compact_task = CompactTask {
+            input_ssts: vec![Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![with_tables(generate_table(4, 1, 100, 200, 4), &[1])],
+            }],
+            target_level: 1,
+            ..Default::default()
+        };
+        let sub_tasks = split_compact_task_by_vnode(compact_task, 4);
+        assert_eq!(sub_tasks.len(), 1);
+        assert!(sub_tasks[0].vnode_bitmaps.is_empty());
+    }
+
+    #[test]
+    fn test_group_compact_task() {
+        let mut compact_status = CompactStatus::with_selector(
+            Arc::new(CompactionConfig::default()),
+            Box::new(L0Selector),
+        );
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![
+                    generate_table(1, 1, 100, 200, 1),
+                    generate_table(2, 2, 100, 200, 1),
+                    generate_table(3, 1, 150, 250, 2),
+                    generate_table(4, 2, 150, 250, 2),
+                ],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![],
+            },
+        ];
+        let mut straddling_sst = generate_table(5, 2, 100, 300, 3);
+        straddling_sst.key_range.as_mut().unwrap().left = iterator_test_key_of_epoch(1, 300, 3);
+        let mut levels_with_straddling_sst = levels.clone();
+        levels_with_straddling_sst[0]
+            .table_infos
+            .push(straddling_sst);
+        let group = |group_id, prefix: &str| {
+            vec![PrefixPair {
+                group_id,
+                prefix: prefix.as_bytes().to_vec(),
+            }]
+        };
+
+        let compact_task = compact_status
+            .get_group_compact_task(&levels, &group(2, "002_"), HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(
+            compact_task.input_ssts[0]
+                .table_infos
+                .iter()
+                .map(|sst| sst.id)
+                .collect_vec(),
+            vec![2, 4]
+        );
+        assert_eq!(compact_task.prefix_pairs, group(2, "002_"));
+
+        // The other group is picked independently while the first one is compacting.
+        let compact_task = compact_status
+            .get_group_compact_task(&levels, &group(1, "001_"), HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(
+            compact_task.input_ssts[0]
+                .table_infos
+                .iter()
+                .map(|sst| sst.id)
+                .collect_vec(),
+            vec![1, 3]
+        );
+        assert_eq!(compact_task.prefix_pairs, group(1, "001_"));
+
+        assert!(compact_status
+            .get_group_compact_task(&levels, &group(3, "003_"), HummockEpoch::MAX)
+            .is_none());
+
+        // An SST straddling two groups holds keys of both of them.
+        for (prefix, expected_ssts) in [("001_", vec![1, 3, 5]), ("002_", vec![2, 4, 5])] {
+            let mut compact_status = CompactStatus::with_selector(
+                Arc::new(CompactionConfig::default()),
+                Box::new(L0Selector),
+            );
+            let compact_task = compact_status
+                .get_group_compact_task(
+                    &levels_with_straddling_sst,
+                    &group(1, prefix),
+                    HummockEpoch::MAX,
+                )
+                .unwrap();
+            assert_eq!(
+                compact_task.input_ssts[0]
+                    .table_infos
+                    .iter()
+                    .map(|sst| sst.id)
+                    .collect_vec(),
+                expected_ssts
+            );
+        }
+    }
+
+    #[test]
+    fn test_split_group() {
+        let mut compact_status = CompactStatus::with_selector(
+            Arc::new(CompactionConfig::default()),
+            Box::new(L0Selector),
+        );
+        let version = HummockVersion {
+            id: 1,
+            levels: vec![
+                Level {
+                    level_idx: 0,
+                    level_type: LevelType::Overlapping as i32,
+                    table_infos: vec![
+                        generate_table(1, 1, 100, 200, 2),
+                        generate_table(2, 2, 100, 200, 2),
+                        generate_table(3, 3, 100, 200, 2),
+                    ],
+                },
+                Level {
+                    level_idx: 1,
+                    level_type: LevelType::Nonoverlapping as i32,
+                    table_infos: vec![
+                        generate_table(4, 1, 0, 1000, 1),
+                        generate_table(5, 2, 0, 1000, 1),
+                        generate_table(6, 3, 0, 1000, 1),
+                        generate_table(7, 4, 0, 1000, 1),
+                    ],
+                },
+            ],
+            uncommitted_epochs: vec![UncommittedEpoch {
+                epoch: 3,
+                tables: vec![generate_table(8, 3, 0, 100, 3)],
+            }],
+            max_committed_epoch: 2,
+            safe_epoch: 1,
+        };
+        let prefix_pair = |group_id, prefix: &str| PrefixPair {
+            group_id,
+            prefix: prefix.as_bytes().to_vec(),
+        };
+        let group = vec![
+            prefix_pair(1, "001_"),
+            prefix_pair(1, "002_"),
+            prefix_pair(1, "003_"),
+        ];
+        let level_ssts = |version: &HummockVersion| {
+            version
+                .levels
+                .iter()
+                .map(|level| level.table_infos.iter().map(|sst| sst.id).collect_vec())
+                .collect_vec()
+        };
+
+        let split = compact_status
+            .split_group(&version, &group, 2, b"002_")
+            .unwrap();
+        assert_eq!(split.left, vec![prefix_pair(1, "001_")]);
+        assert_eq!(
+            split.right,
+            vec![prefix_pair(2, "002_"), prefix_pair(2, "003_")]
+        );
+        // The SSTs of the group are partitioned, and the one out of the group is in neither.
+        assert_eq!(level_ssts(&split.left_version), vec![vec![1], vec![4]]);
+        assert_eq!(
+            level_ssts(&split.right_version),
+            vec![vec![2, 3], vec![5, 6]]
+        );
+        assert!(split.left_version.uncommitted_epochs[0].tables.is_empty());
+        assert_eq!(split.right_version.uncommitted_epochs[0].tables[0].id, 8);
+        for split_version in [&split.left_version, &split.right_version] {
+            assert_eq!(split_version.id, version.id);
+            assert_eq!(
+                split_version.max_committed_epoch,
+                version.max_committed_epoch
+            );
+            assert_eq!(split_version.safe_epoch, version.safe_epoch);
+            assert_eq!(
+                split_version.levels[1].level_type,
+                LevelType::Nonoverlapping as i32
+            );
+        }
+
+        // The split key must fall between the prefixes and leave both groups non-empty.
+        assert!(compact_status
+            .split_group(&version, &group, 2, b"002_0")
+            .is_err());
+        assert!(compact_status
+            .split_group(&version, &group, 2, b"000_")
+            .is_err());
+        assert!(compact_status
+            .split_group(&version, &group, 2, b"004_")
+            .is_err());
+        assert!(compact_status
+            .split_group(&version, &group, 1, b"002_")
+            .is_err());
+
+        // An SST straddling the split key can't be assigned to either group.
+        let mut straddling_version = version.clone();
+        let mut straddling_sst = generate_table(9, 1, 500, 600, 2);
+        straddling_sst.key_range.as_mut().unwrap().right = iterator_test_key_of_epoch(2, 50, 2);
+        straddling_version.levels[0]
+            .table_infos
+            .push(straddling_sst);
+        assert!(compact_status
+            .split_group(&straddling_version, &group, 2, b"002_")
+            .is_err());
+        // It doesn't matter if it straddles another key.
+        let split = compact_status
+            .split_group(&straddling_version, &group, 2, b"003_")
+            .unwrap();
+        assert_eq!(level_ssts(&split.left_version)[0], vec![1, 2, 9]);
+
+        // The status must track every level of the version.
+        let mut untracked_version = version.clone();
+        untracked_version.levels.push(Level {
+            level_idx: CompactionConfig::default().max_level as u32 + 1,
+            level_type: LevelType::Nonoverlapping as i32,
+            table_infos: vec![],
+        });
+        assert!(compact_status
+            .split_group(&untracked_version, &group, 2, b"002_")
+            .is_err());
+
+        // A group can't be split while it's being compacted.
+        compact_status
+            .get_group_compact_task(&version.levels, &group, HummockEpoch::MAX)
+            .unwrap();
+        assert!(compact_status
+            .split_group(&version, &group, 2, b"002_")
+            .is_err());
+    }
+
+    #[test]
+    fn test_reconcile_live_tasks() {
+        let mut compact_status = CompactStatus::with_selector(
+            Arc::new(CompactionConfig::default()),
+            Box::new(L0Selector),
+        );
+        let mut levels = vec![Level {
+            level_idx: 0,
+            level_type: LevelType::Overlapping as i32,
+            table_infos: vec![],
+        }];
+        let mut task_ids = vec![];
+        for sst_id in 1..=3 {
+            levels[0]
+                .table_infos
+                .push(generate_table(sst_id, 1, 100, 200, sst_id));
+            task_ids.push(
+                compact_status
+                    .get_compact_task(&levels, HummockEpoch::MAX)
+                    .unwrap()
+                    .task_id,
+            );
+        }
+
+        compact_status.reconcile_live_tasks(&HashSet::from([task_ids[1]]));
+        let level_handler = &compact_status.level_handlers[0];
+        assert!(!level_handler.is_pending_compact(&1));
+        assert!(level_handler.is_pending_compact(&2));
+        assert!(!level_handler.is_pending_compact(&3));
+        assert_eq!(level_handler.pending_tasks_ids(), vec![task_ids[1]]);
+
+        // The unlocked SSTs can be picked again.
+        let compact_task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        let ids = compact_task.input_ssts[0]
+            .table_infos
+            .iter()
+            .map(|sst| sst.id)
+            .collect_vec();
+        assert_eq!(ids, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let config = CompactionConfig {
+            max_level: 1,
+            level0_trigger_number: 2,
+            ..Default::default()
+        };
+        let mut compact_status =
+            CompactStatus::with_selector(Arc::new(config), Box::new(L0Selector));
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: (1..=3)
+                    .map(|sst_id| generate_table(sst_id, 1, 100, 200, sst_id))
+                    .collect_vec(),
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![generate_table(4, 1, 0, 300, 1)],
+            },
+        ];
+        let dot = compact_status.to_dot(&levels);
+        assert!(dot.starts_with("digraph lsm {"));
+        assert!(dot.contains("subgraph cluster_L0 {"));
+        assert!(dot.contains("subgraph cluster_L1 {"));
+        for sst_id in 1..=4 {
+            assert!(dot.contains(&format!("sst_{} [label=\"SST {}\\n[", sst_id, sst_id)));
+        }
+        assert!(!dot.contains("->"));
+
+        let compact_task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        let task_id = compact_task.task_id;
+        let dot = compact_status.to_dot(&levels);
+        assert!(dot.contains(&format!("task_{} [label=", task_id)));
+        for table in &compact_task.input_ssts[0].table_infos {
+            assert!(dot.contains(&format!("sst_{} -> task_{};", table.id, task_id)));
+        }
+    }
+
+    #[test]
+    fn test_intra_level_compaction() {
+        let config = CompactionConfig {
+            max_bytes_for_level_base: 100,
+            max_level: 3,
+            max_bytes_for_level_multiplier: 10,
+            max_compaction_bytes: 6,
+            intra_level_trigger_number: 4,
+            ..Default::default()
+        };
+        let mut compact_status = CompactStatus::new(Arc::new(config));
+        let mut bottom_table = generate_table(100, 1, 0, 1000, 1);
+        bottom_table.file_size = 1000;
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![],
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: (1..=10)
+                    .map(|sst_id| {
+                        generate_table(sst_id, 1, sst_id as usize * 10, sst_id as usize * 10 + 5, 1)
+                    })
+                    .collect_vec(),
+            },
+            Level {
+                level_idx: 3,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![bottom_table],
+            },
+        ];
+
+        // L2 is far below its target size, so it's merged into itself instead of pushed down.
+        let compact_task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(compact_task.input_ssts[0].level_idx, 2);
+        assert_eq!(compact_task.target_level, 2);
+        assert!(!compact_task.is_target_ultimate_and_leveling);
+        assert_eq!(
+            compact_task.input_ssts[0]
+                .table_infos
+                .iter()
+                .map(|table| table.id)
+                .collect_vec(),
+            vec![1, 2, 3, 4, 5, 6]
+        );
+        assert!(compact_task.input_ssts[1].table_infos.is_empty());
+
+        // The rest of L2 still reach the trigger, and then all files of L2 are compacting.
+        let compact_task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(compact_task.target_level, 2);
+        assert_eq!(compact_task.input_ssts[0].table_infos.len(), 4);
+        assert!(compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .is_none());
+
+        let mut compact_status = CompactStatus::new(Arc::new(CompactionConfig {
+            intra_level_trigger_number: 0,
+            ..(*compact_status.config).clone()
+        }));
+        assert!(compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .is_none());
+    }
+
+    #[test]
+    fn test_max_files_per_level() {
+        let config = CompactionConfig {
+            max_bytes_for_level_base: 100,
+            max_level: 3,
+            max_bytes_for_level_multiplier: 10,
+            intra_level_trigger_number: 0,
+            max_files_per_level: 8,
+            ..Default::default()
+        };
+        let mut bottom_table = generate_table(100, 1, 0, 1000, 1);
+        bottom_table.file_size = 1000;
+        let mut levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![],
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: (1..=8)
+                    .map(|sst_id| {
+                        generate_table(sst_id, 1, sst_id as usize * 10, sst_id as usize * 10 + 5, 1)
+                    })
+                    .collect_vec(),
+            },
+            Level {
+                level_idx: 3,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![bottom_table],
+            },
+        ];
+
+        // L2 is below its target size and at the file count limit.
+        let mut compact_status = CompactStatus::new(Arc::new(config));
+        assert!(compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .is_none());
+
+        // One more file exceeds the limit, and L2 is merged into itself.
+        levels[2].table_infos.push(generate_table(9, 1, 90, 95, 1));
+        let compact_task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(compact_task.input_ssts[0].level_idx, 2);
+        assert_eq!(compact_task.target_level, 2);
+        assert_eq!(compact_task.input_ssts[0].table_infos.len(), 9);
+    }
+
+    #[test]
+    fn test_tier_only() {
+        let config = CompactionConfig {
+            max_bytes_for_level_base: 100,
+            max_level: 4,
+            max_bytes_for_level_multiplier: 5,
+            max_compaction_bytes: 10,
+            level0_max_file_number: 4,
+            level0_trigger_number: 2,
+            tier_only: true,
+            ..Default::default()
+        };
+        let mut compact_status = CompactStatus::new(Arc::new(config.clone()));
+        let mut workloads = vec![GroupWorkload::new(1, 4, 4)];
+        let task_counts = simulate_scheduling(&mut compact_status, &mut workloads, 10);
+        assert!(task_counts[0] > 0);
+        // Any task targeting a lower level would have left SSTs there.
+        assert!(workloads[0].levels[1..]
+            .iter()
+            .all(|level| level.table_infos.is_empty()));
+
+        let mut compact_status = CompactStatus::new(Arc::new(CompactionConfig {
+            tier_only: false,
+            ..config
+        }));
+        let mut workloads = vec![GroupWorkload::new(1, 4, 4)];
+        simulate_scheduling(&mut compact_status, &mut workloads, 10);
+        assert!(workloads[0].levels[1..]
+            .iter()
+            .any(|level| !level.table_infos.is_empty()));
+    }
+
+    #[test]
+    fn test_tasks_issued() {
+        let mut compact_status = CompactStatus::with_selector(
+            Arc::new(CompactionConfig::default()),
+            Box::new(L0Selector),
+        );
+        compact_status.next_compact_task_id = 100;
+        let mut levels = vec![Level {
+            level_idx: 0,
+            level_type: LevelType::Overlapping as i32,
+            table_infos: vec![],
+        }];
+        for sst_id in 1..=3 {
+            levels[0]
+                .table_infos
+                .push(generate_table(sst_id, 1, 100, 200, sst_id));
+            compact_status
+                .get_compact_task(&levels, HummockEpoch::MAX)
+                .unwrap();
+        }
+        assert_eq!(compact_status.next_compact_task_id, 103);
+        assert_eq!(compact_status.tasks_issued(), 3);
+
+        let compact_status_pb = risingwave_pb::hummock::CompactStatus::from(&compact_status);
+        assert_eq!(compact_status_pb.tasks_issued, 3);
+        assert_eq!(CompactStatus::from(&compact_status_pb).tasks_issued(), 3);
+    }
+
+    #[test]
+    fn test_collapse_levels() {
+        let config = CompactionConfig {
+            max_level: 3,
+            ..Default::default()
+        };
+        let mut compact_status = CompactStatus::new(Arc::new(config));
+        let level = |level_idx: u32, table_infos| Level {
+            level_idx,
+            level_type: if level_idx == 0 {
+                LevelType::Overlapping as i32
+            } else {
+                LevelType::Nonoverlapping as i32
+            },
+            table_infos,
+        };
+        let version = HummockVersion {
+            levels: vec![
+                level(0, vec![generate_table(1, 1, 0, 100, 4)]),
+                level(1, vec![generate_table(2, 1, 200, 300, 3)]),
+                level(
+                    2,
+                    vec![
+                        generate_table(3, 1, 0, 100, 2),
+                        generate_table(4, 1, 400, 500, 2),
+                    ],
+                ),
+                level(3, vec![generate_table(5, 1, 600, 700, 1)]),
+            ],
+            ..Default::default()
+        };
+        let table_ids = |level: &Level| level.table_infos.iter().map(|sst| sst.id).collect_vec();
+
+        assert!(compact_status.collapse_levels(1, version.clone()).is_err());
+        assert!(compact_status.collapse_levels(5, version.clone()).is_err());
+
+        let new_version = compact_status
+            .clone()
+            .collapse_levels(2, version.clone())
+            .unwrap();
+        assert_eq!(new_version.levels.len(), 2);
+        assert_eq!(table_ids(&new_version.levels[0]), vec![1]);
+        assert_eq!(table_ids(&new_version.levels[1]), vec![3, 2, 4, 5]);
+        assert_eq!(
+            new_version.levels[1].level_type,
+            LevelType::Nonoverlapping as i32
+        );
+
+        // SSTs of L1 and L2 overlap, so L1 becomes overlapping with the older SSTs first.
+        let mut overlapping_version = version.clone();
+        overlapping_version.levels[1].table_infos = vec![generate_table(2, 1, 50, 300, 3)];
+        let new_version = compact_status
+            .clone()
+            .collapse_levels(2, overlapping_version)
+            .unwrap();
+        assert_eq!(table_ids(&new_version.levels[1]), vec![5, 3, 4, 2]);
+        assert_eq!(
+            new_version.levels[1].level_type,
+            LevelType::Overlapping as i32
+        );
+
+        // The levels to collapse must not be compacting.
+        compact_status.level_handlers[2].add_pending_task(1, &version.levels[2].table_infos);
+        assert!(compact_status.collapse_levels(2, version.clone()).is_err());
+        compact_status.level_handlers[2].remove_task(1);
+        compact_status.collapse_levels(2, version).unwrap();
+        assert_eq!(compact_status.level_handlers.len(), 2);
+    }
+
+    #[test]
+    fn test_target_write_amp() {
+        // A write amplification of 10 over 4 levels allows each level to be 2.5 times the size of
+        // the upper one, which is rounded down.
+        assert_eq!(level_multiplier_for_write_amp(4, 10.0), 2);
+        assert_eq!(level_multiplier_for_write_amp(4, 40.0), 10);
+        assert_eq!(level_multiplier_for_write_amp(6, 5.0), 2);
+
+        let mut levels = (0..=4)
+            .map(|level_idx| Level {
+                level_idx,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![],
+            })
+            .collect_vec();
+        levels[4].table_infos = generate_tables(1..11, 0..1000, 1, 1 << 20);
+        let config = CompactionConfig {
+            max_bytes_for_level_base: 1 << 20,
+            max_level: 4,
+            max_bytes_for_level_multiplier: 5,
+            ..Default::default()
+        };
+        let target_write_amp_config = CompactionConfig {
+            target_write_amp: Some(40.0),
+            ..config.clone()
+        };
+        assert_eq!(config.level_multiplier(), 5);
+        assert_eq!(target_write_amp_config.level_multiplier(), 10);
+        assert_eq!(
+            CompactStatus::new(Arc::new(target_write_amp_config)).level_max_bytes(&levels),
+            CompactStatus::new(Arc::new(CompactionConfig {
+                max_bytes_for_level_multiplier: 10,
+                ..config
+            }))
+            .level_max_bytes(&levels)
+        );
+    }
+
+    #[test]
+    fn test_base_level_size_bytes() {
+        let config = CompactionConfig {
+            max_bytes_for_level_base: 10,
+            max_level: 4,
+            max_bytes_for_level_multiplier: 10,
+            base_level_size_bytes: 1000,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.level_size_targets(),
+            vec![u64::MAX, 1, 10, 100, 1000]
+        );
+
+        let mut levels = (0..=4)
+            .map(|level_idx| Level {
+                level_idx,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![],
+            })
+            .collect_vec();
+        let compact_status = CompactStatus::new(Arc::new(config));
+        // L1 is under `max_bytes_for_level_base`, so data is pushed from L0 to L2 directly.
+        let expected = vec![u64::MAX, u64::MAX, 10, 100, 1000];
+        assert_eq!(compact_status.level_max_bytes(&levels), expected);
+        // The targets don't change with the size of the bottommost level.
+        levels[4].table_infos = generate_tables(1..11, 0..1000, 1, 1 << 20);
+        assert_eq!(compact_status.level_max_bytes(&levels), expected);
+    }
+
+    #[test]
+    fn test_compact_task_priority() {
+        let config = Arc::new(CompactionConfig {
+            max_bytes_for_level_base: 1 << 20,
+            max_level: 2,
+            max_bytes_for_level_multiplier: 10,
+            level0_trigger_number: 2,
+            ..Default::default()
+        });
+        let mb = 1 << 20;
+
+        let mut l0_compact_status = CompactStatus::new(config.clone());
+        let l0_levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: generate_tables(1..4, 0..300, 1, mb),
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![],
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(4..14, 0..1000, 1, mb),
+            },
+        ];
+        let l0_task = l0_compact_status
+            .get_compact_task(&l0_levels, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(l0_task.input_ssts[0].level_idx, 0);
+
+        // L1 holds more bytes than L0 above, and is over its target.
+        let mut l1_compact_status = CompactStatus::new(config);
+        let l1_levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(14..18, 0..400, 1, mb),
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(4..14, 0..1000, 1, mb),
+            },
+        ];
+        let l1_task = l1_compact_status
+            .get_compact_task(&l1_levels, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(l1_task.input_ssts[0].level_idx, 1);
+
+        assert!(l1_task.priority > 0);
+        assert!(l0_task.priority > l1_task.priority);
+    }
+
+    #[test]
+    fn test_output_tier() {
+        let config = CompactionConfig {
+            max_bytes_for_level_base: 1 << 20,
+            max_level: 2,
+            max_bytes_for_level_multiplier: 10,
+            level0_trigger_number: 2,
+            cold_level: 2,
+            ..Default::default()
+        };
+        let mb = 1 << 20;
+        let mut levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: generate_tables(1..4, 0..300, 1, mb),
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![],
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(4..14, 0..1000, 1, mb),
+            },
+        ];
+        let l0_task = CompactStatus::new(Arc::new(config.clone()))
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(l0_task.target_level, 1);
+        assert_eq!(l0_task.output_tier, OutputTier::Hot as i32);
+
+        levels[0].table_infos.clear();
+        levels[1].table_infos = generate_tables(14..18, 0..400, 1, mb);
+        let l1_task = CompactStatus::new(Arc::new(config.clone()))
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(l1_task.target_level, 2);
+        assert_eq!(l1_task.output_tier, OutputTier::Cold as i32);
+
+        // All tasks write to the hot tier by default.
+        let l1_task = CompactStatus::new(Arc::new(CompactionConfig {
+            cold_level: 0,
+            ..config
+        }))
+        .get_compact_task(&levels, HummockEpoch::MAX)
+        .unwrap();
+        assert_eq!(l1_task.output_tier, OutputTier::Hot as i32);
+    }
+
+    #[test]
+    fn test_levels_by_urgency() {
+        let config = Arc::new(CompactionConfig {
+            max_bytes_for_level_base: 100,
+            max_level: 3,
+            max_bytes_for_level_multiplier: 10,
+            level0_trigger_number: 4,
+            ..Default::default()
+        });
+        let mut levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(1..3, 0..100, 1, 50),
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(3..7, 0..400, 1, 1000),
+            },
+            Level {
+                level_idx: 3,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(7..17, 0..1000, 1, 1000),
+            },
+        ];
+        let compact_status = CompactStatus::new(config);
+        let level_max_bytes = compact_status.level_max_bytes(&levels);
+        assert_eq!(level_max_bytes[1..], [100, 1000, 10000]);
+
+        // L2 is 4 times its target, L1 at its target, and L0 is empty. The bottommost level is
+        // never compacted into a lower level.
+        assert_eq!(
+            compact_status.levels_by_urgency(&levels),
+            vec![(2, 4.0), (1, 1.0)]
+        );
+
+        levels[0].table_infos = generate_tables(17..19, 0..100, 1, 1);
+        let levels_by_urgency = compact_status.levels_by_urgency(&levels);
+        assert_eq!(
+            levels_by_urgency
+                .iter()
+                .map(|(level_idx, _)| *level_idx)
+                .collect_vec(),
+            vec![2, 0, 1]
+        );
+        assert!(levels_by_urgency
+            .windows(2)
+            .all(|pair| pair[0].1 >= pair[1].1));
+    }
+
+    #[test]
+    fn test_compact_task_metrics() {
+        let mut compact_status = CompactStatus::new(Arc::new(CompactionConfig {
+            max_level: 1,
+            level0_trigger_number: 2,
+            ..Default::default()
+        }));
+        let sst = |id, left, right, file_size| {
+            let mut sst = generate_table(id, 1, left, right, id);
+            sst.file_size = file_size;
+            sst
+        };
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![
+                    sst(1, 100, 200, BYTES_PER_GB),
+                    sst(2, 100, 200, BYTES_PER_GB / 2),
+                ],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![sst(3, 0, 300, BYTES_PER_GB / 4)],
+            },
+        ];
+        let compact_task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(
+            compact_task.metrics.unwrap(),
+            CompactMetrics {
+                read_level_n: Some(TableSetStatistics {
+                    level_idx: 0,
+                    size_gb: 1.5,
+                    cnt: 2,
+                }),
+                read_level_nplus1: Some(TableSetStatistics {
+                    level_idx: 1,
+                    size_gb: 0.25,
+                    cnt: 1,
+                }),
+                write: Some(TableSetStatistics {
+                    level_idx: 1,
+                    size_gb: 1.75,
+                    cnt: 3,
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn test_total_metrics() {
+        let mut compact_status = CompactStatus::with_selector(
+            Arc::new(CompactionConfig::default()),
+            Box::new(L0Selector),
+        );
+        let mut levels = vec![Level {
+            level_idx: 0,
+            level_type: LevelType::Overlapping as i32,
+            table_infos: vec![],
+        }];
+        let statistics = |size_gb, cnt| {
+            Some(TableSetStatistics {
+                level_idx: 0,
+                size_gb,
+                cnt,
+            })
+        };
+        for (sst_id, read_level_n, read_level_nplus1, write) in [
+            (1, (1.0, 2), (0.5, 1), (1.5, 3)),
+            (2, (2.0, 4), (1.0, 2), (2.5, 5)),
+        ] {
+            levels[0]
+                .table_infos
+                .push(generate_table(sst_id, 1, 100, 200, sst_id));
+            let mut compact_task = compact_status
+                .get_compact_task(&levels, HummockEpoch::MAX)
+                .unwrap();
+            compact_task.metrics = Some(CompactMetrics {
+                read_level_n: statistics(read_level_n.0, read_level_n.1),
+                read_level_nplus1: statistics(read_level_nplus1.0, read_level_nplus1.1),
+                write: statistics(write.0, write.1),
+            });
+            compact_task.task_status = true;
+            compact_status.report_compact_task(&compact_task);
+        }
+        let expected = CompactMetrics {
+            read_level_n: statistics(3.0, 6),
+            read_level_nplus1: statistics(1.5, 3),
+            write: statistics(4.0, 8),
+        };
+        assert_eq!(compact_status.total_metrics(), &expected);
+
+        let compact_status_pb = risingwave_pb::hummock::CompactStatus::from(&compact_status);
+        let compact_status = CompactStatus::from(&compact_status_pb);
+        assert_eq!(compact_status.total_metrics(), &expected);
+    }
+
+    #[test]
+    fn test_task_id_exhausted() {
+        let mut compact_status = CompactStatus::with_selector(
+            Arc::new(CompactionConfig::default()),
+            Box::new(L0Selector),
+        );
+        compact_status.next_compact_task_id = u64::MAX;
+        let levels = vec![Level {
+            level_idx: 0,
+            level_type: LevelType::Overlapping as i32,
+            table_infos: vec![generate_table(1, 1, 100, 200, 1)],
+        }];
+        assert!(compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .is_none());
+        assert_eq!(compact_status.next_compact_task_id, u64::MAX);
+        assert_eq!(compact_status.tasks_issued, 0);
+        assert_eq!(compact_status.level_handlers[0].get_pending_file_count(), 0);
+
+        // Ids below the last one are still issued.
+        compact_status.next_compact_task_id = u64::MAX - 1;
+        let compact_task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(compact_task.task_id, u64::MAX - 1);
+        compact_status.report_compact_task(&compact_task);
+        assert!(compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .is_none());
+    }
+
+    #[test]
+    fn test_compact_task_watermark() {
+        let mut compact_status = CompactStatus::with_selector(
+            Arc::new(CompactionConfig::default()),
+            Box::new(L0Selector),
+        );
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![generate_table(1, 1, 100, 200, 1)],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![],
+            },
+        ];
+        // A snapshot is pinned at epoch 100.
+        let mut compact_task = compact_status.get_compact_task(&levels, 100).unwrap();
+        assert_eq!(compact_task.watermark, 100);
+
+        compact_task.sorted_output_ssts = vec![generate_table(2, 1, 100, 200, 1)];
+        let version = HummockVersion {
+            levels,
+            safe_epoch: 10,
+            ..Default::default()
+        };
+        let version = CompactStatus::apply_compact_result(&compact_task, version).unwrap();
+        assert_eq!(version.safe_epoch, 100);
+    }
+
+    #[test]
+    fn test_key_range_splits() {
+        let config = CompactionConfig {
+            key_range_splits: 3,
+            ..Default::default()
+        };
+        let mut compact_status =
+            CompactStatus::with_selector(Arc::new(config), Box::new(L0Selector));
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![
+                    generate_table(1, 1, 100, 200, 1),
+                    generate_table(2, 1, 150, 400, 1),
+                ],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![],
+            },
+        ];
+        let compact_task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(compact_task.splits.len(), 3);
+        assert!(compact_task.splits[0].left.is_empty());
+        assert!(compact_task.splits[2].right.is_empty());
+        assert_eq!(compact_task.splits[0].right, compact_task.splits[1].left);
+        assert_eq!(compact_task.splits[1].right, compact_task.splits[2].left);
+        // Every input key, including the max one, is compacted by exactly one split.
+        for sst in levels[0].table_infos.iter() {
+            for key in [
+                &sst.key_range.as_ref().unwrap().left,
+                &sst.key_range.as_ref().unwrap().right,
+            ] {
+                let key_sst = SstableInfo {
+                    key_range: Some(risingwave_pb::hummock::KeyRange {
+                        left: key.clone(),
+                        right: key.clone(),
+                        inf: false,
+                    }),
+                    ..Default::default()
+                };
+                assert_eq!(
+                    compact_task
+                        .splits
+                        .iter()
+                        .filter(|split| split_contains(split, &key_sst))
+                        .count(),
+                    1
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_should_stop_writes() {
+        let config = CompactionConfig {
+            level0_stop_writes_file_number: 3,
+            ..Default::default()
+        };
+        let compact_status = CompactStatus::new(Arc::new(config.clone()));
+        let mut levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: generate_tables(0..2, 0..100, 1, 10),
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(2..10, 0..1000, 1, 100),
+            },
+        ];
+        assert!(!compact_status.should_stop_writes(&levels));
+
+        levels[0].table_infos = generate_tables(0..3, 0..100, 1, 10);
+        assert!(compact_status.should_stop_writes(&levels));
+
+        // No ceiling by default.
+        let compact_status = CompactStatus::new(Arc::new(CompactionConfig::default()));
+        assert!(!compact_status.should_stop_writes(&levels));
+
+        // Nor without any level.
+        let compact_status = CompactStatus::new(Arc::new(config));
+        assert!(!compact_status.should_stop_writes(&[]));
+    }
+
+    #[test]
+    fn test_preview_next_io_cost() {
+        let config = CompactionConfig {
+            max_level: 2,
+            level0_trigger_number: 1,
+            ..Default::default()
+        };
+        let mut compact_status = CompactStatus::new(Arc::new(config));
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![generate_table(1, 1, 100, 200, 1)],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![
+                    generate_table(2, 1, 100, 150, 1),
+                    generate_table(3, 1, 160, 200, 1),
+                ],
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![],
+            },
+        ];
+        let cost = compact_status
+            .preview_next_io_cost(&levels, HummockEpoch::MAX)
+            .unwrap();
+        // The preview doesn't issue the task.
+        assert_eq!(compact_status.pending_task_count(), 0);
+        assert_eq!(
+            compact_status.preview_next_io_cost(&levels, HummockEpoch::MAX),
+            Some(cost)
+        );
+
+        let compact_task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        let metrics = compact_task.metrics.as_ref().unwrap();
+        let metrics_gb = metrics.read_level_n.as_ref().unwrap().size_gb
+            + metrics.read_level_nplus1.as_ref().unwrap().size_gb
+            + metrics.write.as_ref().unwrap().size_gb;
+        assert_eq!(cost, 6);
+        assert_eq!(cost as f64, metrics_gb * BYTES_PER_GB as f64);
+
+        // All the SSTs are taken by the task.
+        assert!(compact_status
+            .preview_next_io_cost(&levels, HummockEpoch::MAX)
+            .is_none());
+    }
+
+    #[test]
+    fn test_preview_next_io_cost_space_reclaim_first() {
+        let config = CompactionConfig {
+            max_level: 2,
+            level0_trigger_number: 1,
+            space_reclaim_first: true,
+            ..Default::default()
+        };
+        let mut compact_status = CompactStatus::new(Arc::new(config));
+        let mut obsolete_sst = generate_table(3, 1, 500, 600, 1);
+        obsolete_sst.file_size = 10;
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![
+                    generate_table(1, 1, 100, 200, 20),
+                    generate_table(2, 1, 150, 250, 21),
+                ],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![obsolete_sst],
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![],
+            },
+        ];
+        let io_bytes = |compact_task: &CompactTask| {
+            let metrics = compact_task.metrics.as_ref().unwrap();
+            let metrics_gb = metrics.read_level_n.as_ref().unwrap().size_gb
+                + metrics.read_level_nplus1.as_ref().unwrap().size_gb
+                + metrics.write.as_ref().unwrap().size_gb;
+            metrics_gb * BYTES_PER_GB as f64
+        };
+
+        // The preview describes the space reclaim task issued ahead of the selector's.
+        let cost = compact_status.preview_next_io_cost(&levels, 5).unwrap();
+        assert_eq!(cost, 20);
+        let compact_task = compact_status.get_compact_task(&levels, 5).unwrap();
+        assert_eq!(compact_task.input_ssts[0].table_infos[0].id, 3);
+        assert_eq!(cost as f64, io_bytes(&compact_task));
+
+        // Then the one of the selector.
+        let cost = compact_status.preview_next_io_cost(&levels, 5).unwrap();
+        let compact_task = compact_status.get_compact_task(&levels, 5).unwrap();
+        assert_eq!(compact_task.input_ssts[0].level_idx, 0);
+        assert_eq!(cost as f64, io_bytes(&compact_task));
+    }
+
+    #[test]
+    fn test_advance_safe_epoch() {
+        let version = HummockVersion {
+            levels: vec![Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![generate_table(1, 1, 100, 200, 1)],
+            }],
+            max_committed_epoch: 100,
+            safe_epoch: 10,
+            ..Default::default()
+        };
+        let new_version = CompactStatus::advance_safe_epoch(version.clone(), 50).unwrap();
+        assert_eq!(new_version.safe_epoch, 50);
+        assert_eq!(new_version.levels, version.levels);
+        assert_eq!(
+            CompactStatus::advance_safe_epoch(version.clone(), 10)
+                .unwrap()
+                .safe_epoch,
+            10
+        );
+
+        assert!(CompactStatus::advance_safe_epoch(new_version, 20).is_err());
+        assert!(CompactStatus::advance_safe_epoch(version, 101).is_err());
+    }
+
+    #[test]
+    fn test_pending_task_count() {
+        let config = CompactionConfig {
+            max_level: 2,
+            level0_trigger_number: 1,
+            ..Default::default()
+        };
+        let mut compact_status = CompactStatus::new(Arc::new(config));
+        let mut levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![generate_table(1, 1, 100, 200, 1)],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![generate_table(2, 1, 100, 200, 1)],
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![],
+            },
+        ];
+        assert_eq!(compact_status.pending_task_count(), 0);
+        assert!(!compact_status.is_level_overlapping_task(0));
+
+        let first_task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        levels[0]
+            .table_infos
+            .push(generate_table(3, 1, 300, 400, 2));
+        let second_task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(compact_status.pending_task_count(), 2);
+        assert!(compact_status.is_level_overlapping_task(0));
+        assert!(compact_status.is_level_overlapping_task(1));
+        assert!(!compact_status.is_level_overlapping_task(2));
+        assert!(!compact_status.is_level_overlapping_task(3));
+
+        compact_status.report_compact_task(&first_task);
+        assert_eq!(compact_status.pending_task_count(), 1);
+        compact_status.report_compact_task(&second_task);
+        assert_eq!(compact_status.pending_task_count(), 0);
+        assert!(!compact_status.is_level_overlapping_task(1));
+    }
+
+    #[test]
+    fn test_level_eligibility() {
+        let config = CompactionConfig {
+            max_bytes_for_level_base: 100,
+            max_level: 2,
+            max_bytes_for_level_multiplier: 5,
+            level0_trigger_number: 1,
+            ..Default::default()
+        };
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: generate_tables(1..3, 0..100, 2, 10),
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(3..13, 0..1000, 1, 100),
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: generate_tables(13..15, 0..1000, 1, 100),
+            },
+        ];
+
+        let compact_status = CompactStatus::new(Arc::new(config.clone()));
+        assert_eq!(
+            compact_status.level_eligibility(&levels),
+            vec![true, true, false]
+        );
+
+        // L1 is still over its target, but it's never compacted when only L0 is.
+        let tier_only_compact_status = CompactStatus::new(Arc::new(CompactionConfig {
+            tier_only: true,
+            ..config.clone()
+        }));
+        assert_eq!(
+            tier_only_compact_status.level_eligibility(&levels),
+            vec![true, false, false]
+        );
+
+        // L0 is not eligible while all its SSTs are being compacted.
+        let mut compact_status =
+            CompactStatus::with_selector(Arc::new(config), Box::new(L0Selector));
+        compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(
+            compact_status.level_eligibility(&levels),
+            vec![false, true, false]
+        );
+
+        // A level beyond `max_level` is neither eligible nor stuck.
+        let mut levels = levels;
+        levels.push(Level {
+            level_idx: 3,
+            level_type: LevelType::Nonoverlapping as i32,
+            table_infos: generate_tables(15..17, 0..1000, 1, 100),
+        });
+        assert!(!compact_status.level_eligibility(&levels)[3]);
+        assert!(!compact_status.stuck_levels(&levels, u64::MAX).contains(&3));
+    }
+
+    #[test]
+    fn test_cancel_and_finish_compact_task() {
+        let mut compact_status = CompactStatus::with_selector(
+            Arc::new(CompactionConfig::default()),
+            Box::new(L0Selector),
+        );
+        let levels = vec![Level {
+            level_idx: 0,
+            level_type: LevelType::Overlapping as i32,
+            table_infos: vec![
+                generate_table(1, 1, 100, 200, 1),
+                generate_table(2, 1, 150, 250, 2),
+            ],
+        }];
+
+        let compact_task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(compact_status.level_handlers[0].get_pending_file_count(), 2);
+        compact_status.cancel_compact_task(&compact_task);
+        assert_eq!(compact_status.level_handlers[0].get_pending_file_count(), 0);
+        assert!(compact_status.level_handlers[0]
+            .pending_tasks_ids()
+            .is_empty());
+        assert_eq!(compact_status.last_compacted_epoch(), INVALID_EPOCH);
+        assert_eq!(compact_status.total_metrics(), &CompactMetrics::default());
+
+        // The canceled SSTs can be picked again.
+        let compact_task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        assert_eq!(compact_status.level_handlers[0].get_pending_file_count(), 2);
+        compact_status.finish_compact_task(&compact_task);
+        assert_eq!(compact_status.level_handlers[0].get_pending_file_count(), 0);
+        assert!(compact_status.level_handlers[0]
+            .pending_tasks_ids()
+            .is_empty());
+        assert_ne!(compact_status.last_compacted_epoch(), INVALID_EPOCH);
+        assert_eq!(
+            compact_status
+                .total_metrics()
+                .read_level_n
+                .as_ref()
+                .unwrap()
+                .cnt,
+            2
+        );
+    }
+
+    #[test]
+    fn test_last_compacted_epoch() {
+        let mut compact_status = CompactStatus::with_selector(
+            Arc::new(CompactionConfig::default()),
+            Box::new(L0Selector),
+        );
+        let mut levels = vec![Level {
+            level_idx: 0,
+            level_type: LevelType::Overlapping as i32,
+            table_infos: vec![generate_table(1, 1, 100, 200, 1)],
+        }];
+        assert_eq!(compact_status.last_compacted_epoch(), INVALID_EPOCH);
+
+        // A canceled task leaves the epoch untouched.
+        let compact_task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        compact_status.report_compact_task(&compact_task);
+        assert_eq!(compact_status.last_compacted_epoch(), INVALID_EPOCH);
+
+        let before = Epoch::now().0;
+        let mut compact_task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        compact_task.task_status = true;
+        compact_status.report_compact_task(&compact_task);
+        let last_compacted_epoch = compact_status.last_compacted_epoch();
+        assert!(last_compacted_epoch >= before);
+
+        levels[0].table_infos = vec![generate_table(2, 1, 100, 200, 2)];
+        let compact_task = compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        compact_status.report_compact_task(&compact_task);
+        assert_eq!(compact_status.last_compacted_epoch(), last_compacted_epoch);
+
+        let compact_status_pb = risingwave_pb::hummock::CompactStatus::from(&compact_status);
+        assert_eq!(compact_status_pb.last_compacted_epoch, last_compacted_epoch);
+        let compact_status = CompactStatus::from(&compact_status_pb);
+        assert_eq!(compact_status.last_compacted_epoch(), last_compacted_epoch);
+    }
+
+    #[test]
+    fn test_report() {
+        let mut compact_status = CompactStatus::with_selector(
+            Arc::new(CompactionConfig::default()),
+            Box::new(L0Selector),
+        );
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![
+                    generate_table(1, 1, 100, 200, 2),
+                    generate_table(2, 1, 150, 250, 2),
+                ],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![generate_table(3, 1, 0, 300, 1)],
+            },
+        ];
+        compact_status
+            .get_compact_task(&levels, HummockEpoch::MAX)
+            .unwrap();
+        let report = compact_status.report(&levels);
+        assert!(report.contains("next compact task id: 2\n"));
+        assert!(report.contains("tasks issued: 1\n"));
+        assert!(report.contains("in-flight tasks: 1\n"));
+        assert!(report.contains("L0: 2 files, 2 bytes, 2 files compacting\n"));
+        assert!(report.contains("L1: 1 files, 1 bytes, 0 files compacting\n"));
+        assert!(report.contains("config: CompactionConfig {"));
+        assert!(report.contains("level0_trigger_number: 16"));
+    }
+
+    #[test]
+    fn test_recommend_flush_size() {
+        let config = CompactionConfig {
+            level0_trigger_number: 4,
+            ..Default::default()
+        };
+        let compact_status = CompactStatus::new(Arc::new(config));
+        let default_size = 32 * 1024 * 1024;
+        let mut levels = vec![Level {
+            level_idx: 0,
+            level_type: LevelType::Overlapping as i32,
+            table_infos: vec![],
+        }];
+        assert_eq!(
+            compact_status.recommend_flush_size(&levels, default_size),
+            default_size
+        );
+
+        // Files not overlapping with each other don't congest L0.
+        levels[0].table_infos = (0..8)
+            .map(|i| generate_table(i, 1, i as usize * 100, i as usize * 100 + 99, 1))
+            .collect_vec();
+        assert_eq!(
+            compact_status.recommend_flush_size(&levels, default_size),
+            default_size
+        );
+
+        levels[0].table_infos = (0..8)
+            .map(|i| generate_table(i, 1, 0, 1000, i + 1))
+            .collect_vec();
+        assert_eq!(
+            compact_status.recommend_flush_size(&levels, default_size),
+            default_size * 2
+        );
+
+        levels[0].table_infos = (0..100)
+            .map(|i| generate_table(i, 1, 0, 1000, i + 1))
+            .collect_vec();
+        assert_eq!(
+            compact_status.recommend_flush_size(&levels, default_size),
+            default_size * MAX_FLUSH_SIZE_MULTIPLIER
+        );
+
+        // Nothing is congested without any level.
+        assert_eq!(
+            compact_status.recommend_flush_size(&[], default_size),
+            default_size
+        );
+    }
+
+    #[test]
+    fn test_gc_hints() {
+        let compact_task = CompactTask {
+            input_ssts: vec![
+                Level {
+                    level_idx: 0,
+                    level_type: LevelType::Overlapping as i32,
+                    table_infos: vec![generate_table(1, 1, 100, 200, 10)],
+                },
+                Level {
+                    level_idx: 1,
+                    level_type: LevelType::Nonoverlapping as i32,
+                    table_infos: vec![generate_table(2, 1, 100, 200, 3)],
+                },
+            ],
+            sorted_output_ssts: vec![generate_table(3, 1, 100, 200, 10)],
+            watermark: 5,
+            task_id: 1,
+            target_level: 1,
+            ..Default::default()
+        };
+        let version = HummockVersion {
+            levels: compact_task.input_ssts.clone(),
+            max_committed_epoch: 10,
+            ..Default::default()
+        };
+        let (new_version, gc_hints) =
+            CompactStatus::apply_compact_result_with_gc_hints(&compact_task, version.clone())
+                .unwrap();
+        assert_eq!(new_version.safe_epoch, 5);
+        assert!(new_version.levels[0].table_infos.is_empty());
+        assert_eq!(new_version.levels[1].table_infos[0].id, 3);
+        // The output holds no data newer than the input.
+        assert_eq!(new_version.levels[1].table_infos[0].max_epoch, 10);
+        // SST 1 has data above the safe epoch and must linger, while SST 2 can be deleted
+        // immediately.
+        assert_eq!(gc_hints, vec![(1, false), (2, true)]);
+
+        // A watermark beyond the committed data doesn't make SST 2 garbage, as its data is not
+        // below `max_committed_epoch`.
+        let version = HummockVersion {
+            max_committed_epoch: 3,
+            ..version
+        };
+        let (_, gc_hints) =
+            CompactStatus::apply_compact_result_with_gc_hints(&compact_task, version.clone())
+                .unwrap();
+        assert_eq!(gc_hints, vec![(1, false), (2, false)]);
+
+        // The hints are judged by the max epoch of the SSTs rather than the epochs of their key
+        // ranges, as a key in the middle may have a newer version. SSTs with an unknown max epoch
+        // always linger.
+        let mut compact_task = compact_task;
+        compact_task.input_ssts[1].table_infos = vec![
+            SstableInfo {
+                max_epoch: 6,
+                ..generate_table(2, 1, 100, 200, 3)
+            },
+            SstableInfo {
+                max_epoch: 0,
+                ..generate_table(4, 1, 300, 400, 3)
+            },
+        ];
+        let version = HummockVersion {
+            levels: compact_task.input_ssts.clone(),
+            max_committed_epoch: 10,
+            ..version
+        };
+        let (new_version, gc_hints) =
+            CompactStatus::apply_compact_result_with_gc_hints(&compact_task, version).unwrap();
+        assert_eq!(gc_hints, vec![(1, false), (2, false), (4, false)]);
+        // The max epoch of the output is unknown as that of an input is.
+        assert_eq!(new_version.levels[1].table_infos[0].max_epoch, 0);
+    }
+
+    #[test]
+    fn test_space_reclaim_first() {
+        let config = CompactionConfig {
+            max_level: 2,
+            level0_trigger_number: 1,
+            space_reclaim_first: true,
+            ..Default::default()
+        };
+        let mut obsolete_sst = generate_table(3, 1, 500, 600, 1);
+        obsolete_sst.file_size = 10;
+        let mut live_sst = generate_table(4, 1, 700, 800, 10);
+        live_sst.file_size = 100;
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![
+                    generate_table(1, 1, 100, 200, 20),
+                    generate_table(2, 1, 150, 250, 21),
+                ],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![obsolete_sst, live_sst],
+            },
+            Level {
+                level_idx: 2,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![],
+            },
+        ];
+
+        // L0 is over its target, but the obsolete SST is reclaimed first.
+        let mut compact_status = CompactStatus::new(Arc::new(config.clone()));
+        let compact_task = compact_status.get_compact_task(&levels, 5).unwrap();
+        assert_eq!(compact_task.input_ssts[0].level_idx, 1);
+        assert_eq!(compact_task.input_ssts[0].table_infos[0].id, 3);
+        assert_eq!(compact_task.target_level, 2);
+
+        // Then the selector picks as usual once nothing is left to reclaim.
+        let compact_task = compact_status.get_compact_task(&levels, 5).unwrap();
+        assert_eq!(compact_task.input_ssts[0].level_idx, 0);
+
+        // Nothing is reclaimed first unless enabled.
+        let mut compact_status = CompactStatus::new(Arc::new(CompactionConfig {
+            space_reclaim_first: false,
+            ..config.clone()
+        }));
+        let compact_task = compact_status.get_compact_task(&levels, 5).unwrap();
+        assert_eq!(compact_task.input_ssts[0].level_idx, 0);
+
+        // An SST whose key range bounds are old but which holds newer data is not reclaimed, and
+        // the selector picks as usual.
+        let mut levels = levels;
+        levels[1].table_infos[0].max_epoch = 8;
+        let mut compact_status = CompactStatus::new(Arc::new(config));
+        let compact_task = compact_status.get_compact_task(&levels, 5).unwrap();
+        assert_eq!(compact_task.input_ssts[0].level_idx, 0);
+    }
+
+    #[test]
+    fn test_estimate_manual_reclaim() {
+        let compact_status = CompactStatus::new(Arc::new(CompactionConfig::default()));
+        let sst = |id, left, right, epoch, file_size| {
+            let mut sst = generate_table(id, 1, left, right, epoch);
+            sst.file_size = file_size;
+            sst
+        };
+        let mut no_key_range_sst = sst(4, 100, 200, 3, 800);
+        no_key_range_sst.key_range = None;
+        // The keys at the bounds of SST 5 are old, but a key in the middle has a newer version.
+        let mut newer_middle_sst = sst(5, 120, 220, 3, 1600);
+        newer_middle_sst.max_epoch = 8;
+        let mut unknown_epoch_sst = sst(6, 120, 220, 3, 3200);
+        unknown_epoch_sst.max_epoch = 0;
+        let levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![
+                    sst(1, 150, 250, 10, 100),
+                    no_key_range_sst,
+                    newer_middle_sst,
+                    unknown_epoch_sst,
+                ],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![sst(2, 100, 200, 3, 200), sst(3, 500, 600, 3, 400)],
+            },
+        ];
+        let key_range = KeyRange::new(
+            Bytes::from(iterator_test_key_of_epoch(1, 100, HummockEpoch::MAX)),
+            Bytes::from(iterator_test_key_of_epoch(1, 300, 0)),
+        );
+
+        // SSTs 1 and 5 have data above the safe epoch, SST 3 is out of the range, SST 4 has no
+        // range, and SST 6 has no known max epoch.
+        assert_eq!(
+            compact_status.estimate_manual_reclaim(&levels, &key_range, 5),
+            200
+        );
+        assert_eq!(
+            compact_status.estimate_manual_reclaim(&levels, &key_range, 20),
+            1900
+        );
+        assert_eq!(
+            compact_status.estimate_manual_reclaim(&levels, &key_range, 1),
+            0
+        );
+    }
+
+    #[test]
+    fn test_apply_compact_result_with_missing_input() {
+        let compact_task = CompactTask {
+            input_ssts: vec![
+                Level {
+                    level_idx: 0,
+                    level_type: LevelType::Overlapping as i32,
+                    table_infos: vec![generate_table(1, 1, 100, 200, 2)],
+                },
+                Level {
+                    level_idx: 1,
+                    level_type: LevelType::Nonoverlapping as i32,
+                    table_infos: vec![generate_table(2, 1, 100, 200, 1)],
+                },
+            ],
+            sorted_output_ssts: vec![generate_table(4, 1, 100, 200, 2)],
+            task_id: 1,
+            target_level: 1,
+            ..Default::default()
+        };
+        let mut version = HummockVersion {
+            levels: compact_task.input_ssts.clone(),
+            ..Default::default()
+        };
+        // SST 2 has been removed by another operation.
+        version.levels[1].table_infos = vec![generate_table(3, 1, 300, 400, 1)];
+
+        let err = CompactStatus::apply_compact_result(&compact_task, version).unwrap_err();
+        assert!(err.to_string().contains("[2]"));
+    }
+
+    #[test]
+    fn test_apply_compact_result_with_invalid_output() {
+        let mut compact_task = CompactTask {
+            input_ssts: vec![
+                Level {
+                    level_idx: 0,
+                    level_type: LevelType::Overlapping as i32,
+                    table_infos: vec![generate_table(1, 1, 100, 200, 2)],
+                },
+                Level {
+                    level_idx: 1,
+                    level_type: LevelType::Nonoverlapping as i32,
+                    table_infos: vec![generate_table(2, 1, 100, 200, 1)],
+                },
+            ],
+            sorted_output_ssts: vec![
+                generate_table(4, 1, 100, 150, 2),
+                generate_table(4, 1, 151, 200, 2),
+            ],
+            task_id: 1,
+            target_level: 1,
+            ..Default::default()
+        };
+        let mut version = HummockVersion {
+            levels: compact_task.input_ssts.clone(),
+            ..Default::default()
+        };
+        version.levels[1]
+            .table_infos
+            .push(generate_table(3, 1, 300, 400, 1));
+
+        // Duplicate ids within the output.
+        assert!(CompactStatus::apply_compact_result(&compact_task, version.clone()).is_err());
+
+        // Collision with an SST remaining in the target level.
+        compact_task.sorted_output_ssts[1].id = 3;
+        assert!(CompactStatus::apply_compact_result(&compact_task, version.clone()).is_err());
+
+        compact_task.sorted_output_ssts[1].id = 5;
+        let version = CompactStatus::apply_compact_result(&compact_task, version).unwrap();
+        assert!(version.levels[0].table_infos.is_empty());
+        assert_eq!(
+            version.levels[1]
+                .table_infos
+                .iter()
+                .map(|sst| sst.id)
+                .collect_vec(),
+            vec![4, 5, 3]
+        );
+    }
+
+    #[test]
+    fn test_report_partial_compact_task() {
+        let mut compact_status = CompactStatus::default();
+        let select_level = Level {
+            level_idx: 0,
+            level_type: LevelType::Overlapping as i32,
+            table_infos: vec![
+                generate_table(1, 1, 100, 200, 2),
+                generate_table(2, 1, 300, 400, 2),
+            ],
+        };
+        let target_level = Level {
+            level_idx: 1,
+            level_type: LevelType::Nonoverlapping as i32,
+            table_infos: vec![
+                generate_table(3, 1, 100, 200, 1),
+                generate_table(4, 1, 300, 400, 1),
+            ],
+        };
+        compact_status.level_handlers[0].add_pending_task(1, &select_level.table_infos);
+        compact_status.level_handlers[1].add_pending_task(1, &target_level.table_infos);
+        let split_key: Bytes = FullKey::from_user_key_slice(
+            user_key(&iterator_test_key_of_epoch(1, 300, 1)),
+            HummockEpoch::MAX,
+        )
+        .into_inner()
+        .into();
+        let first_split = KeyRange::new(Bytes::new(), split_key.clone());
+        let second_split = KeyRange::new(split_key, Bytes::new());
+        let mut compact_task = CompactTask {
+            input_ssts: vec![select_level.clone(), target_level.clone()],
+            splits: vec![first_split.clone().into(), second_split.clone().into()],
+            sorted_output_ssts: vec![
+                generate_table(5, 1, 100, 200, 2),
+                generate_table(6, 1, 300, 400, 2),
+            ],
+            task_id: 1,
+            target_level: 1,
+            ..Default::default()
+        };
+
+        let partial_task =
+            compact_status.report_partial_compact_task(&mut compact_task, &[first_split]);
+        let ids = |level: &Level| level.table_infos.iter().map(|sst| sst.id).collect_vec();
+        assert_eq!(ids(&partial_task.input_ssts[0]), vec![1]);
+        assert_eq!(ids(&partial_task.input_ssts[1]), vec![3]);
+        assert_eq!(partial_task.sorted_output_ssts[0].id, 5);
+        assert_eq!(partial_task.sorted_output_ssts.len(), 1);
+        assert_eq!(partial_task.splits.len(), 1);
+        assert_eq!(ids(&compact_task.input_ssts[0]), vec![2]);
+        assert_eq!(ids(&compact_task.input_ssts[1]), vec![4]);
+        assert_eq!(compact_task.sorted_output_ssts[0].id, 6);
+        assert_eq!(compact_task.splits.len(), 1);
+        assert!(!compact_status.level_handlers[0].is_pending_compact(&1));
+        assert!(compact_status.level_handlers[0].is_pending_compact(&2));
+        assert!(!compact_status.level_handlers[1].is_pending_compact(&3));
+        assert!(compact_status.level_handlers[1].is_pending_compact(&4));
+
+        let version = HummockVersion {
+            levels: vec![select_level, target_level],
+            ..Default::default()
+        };
+        let version = CompactStatus::apply_compact_result(&partial_task, version).unwrap();
+        assert_eq!(ids(&version.levels[0]), vec![2]);
+        assert_eq!(ids(&version.levels[1]), vec![5, 4]);
+
+        let partial_task =
+            compact_status.report_partial_compact_task(&mut compact_task, &[second_split]);
+        let version = CompactStatus::apply_compact_result(&partial_task, version).unwrap();
+        assert!(version.levels[0].table_infos.is_empty());
+        assert_eq!(ids(&version.levels[1]), vec![5, 6]);
+        assert!(compact_task.splits.is_empty());
+        assert_eq!(compact_status.level_handlers[0].get_pending_file_count(), 0);
+        assert_eq!(compact_status.level_handlers[1].get_pending_file_count(), 0);
+    }
+
+    #[test]
+    fn test_scheduling_fairness() {
+        let config = CompactionConfig {
+            max_bytes_for_level_base: 100,
+            max_level: 4,
+            max_bytes_for_level_multiplier: 5,
+            max_compaction_bytes: 10,
+            level0_max_file_number: 4,
+            level0_trigger_number: 2,
+            max_output_writers: 0,
+            level_stuck_timeout_sec: 0,
+            intra_level_trigger_number: 0,
+            tier_only: false,
+            min_l0_sublevels_to_compact: 0,
+            level_selection_policy: LevelSelectionPolicy::LargestFirst,
+            max_files_per_level: 0,
+            level_overlap_strategies: vec![],
+            target_write_amp: None,
+            parallelism: 0,
+            min_compaction_bytes: 0,
+            key_range_splits: 0,
+            level0_stop_writes_file_number: 0,
+            base_level_size_bytes: 0,
+            cold_level: 0,
+            space_reclaim_first: false,
+        };
+        let mut compact_status = CompactStatus::new(Arc::new(config));
+        // A hot group flushing many SSTs should not starve a cold one.
+        let mut workloads = vec![GroupWorkload::new(1, 8, 4), GroupWorkload::new(2, 1, 4)];
+        let iterations = 20;
+        let task_counts = simulate_scheduling(&mut compact_status, &mut workloads, iterations);
+        assert!(task_counts[0] >= iterations);
+        assert_eq!(task_counts[1], iterations);
+        for level_handler in &compact_status.level_handlers {
+            assert_eq!(level_handler.get_pending_file_count(), 0);
+        }
+    }
 }
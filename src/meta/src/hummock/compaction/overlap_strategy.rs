@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use itertools::Itertools;
 use risingwave_hummock_sdk::key::user_key;
 use risingwave_hummock_sdk::key_range::KeyRange;
@@ -120,6 +123,154 @@ impl OverlapStrategy for RangeOverlapStrategy {
     }
 }
 
+/// Takes every pair of SSTs as overlapping, which is the cheapest check and always safe, at the
+/// cost of compacting more SSTs than necessary.
+#[derive(Default)]
+pub struct ConservativeOverlapInfo {
+    has_table: bool,
+}
+
+impl OverlapInfo for ConservativeOverlapInfo {
+    fn check_overlap(&self, _a: &SstableInfo) -> bool {
+        self.has_table
+    }
+
+    fn check_multiple_overlap(&self, others: &[SstableInfo]) -> Vec<SstableInfo> {
+        if self.has_table {
+            others.to_vec()
+        } else {
+            vec![]
+        }
+    }
+
+    fn update(&mut self, _table: &SstableInfo) {
+        self.has_table = true;
+    }
+}
+
+#[derive(Default)]
+pub struct ConservativeOverlapStrategy {}
+
+impl OverlapStrategy for ConservativeOverlapStrategy {
+    fn check_overlap(&self, _a: &SstableInfo, _b: &SstableInfo) -> bool {
+        true
+    }
+
+    fn create_overlap_info(&self) -> Box<dyn OverlapInfo> {
+        Box::new(ConservativeOverlapInfo::default())
+    }
+}
+
+/// Takes SSTs as overlapping only if their key ranges overlap and they share a virtual node of
+/// some table, since keys of different virtual nodes never collide. It fits the levels of
+/// hash-distributed tables, whose SSTs span similar key ranges but hold different virtual nodes.
+/// The SSTs taken from a non-overlapping level are still contiguous, so that the output of the
+/// compaction doesn't overlap with the SSTs left in the level. An SST without vnode bitmaps, or
+/// with an empty bitmap of a table, is taken as holding all virtual nodes of it.
+#[derive(Default)]
+pub struct HashOverlapInfo {
+    range_info: RangeOverlapInfo,
+    /// Whether any SST holds all virtual nodes.
+    all_vnodes: bool,
+    /// Union of the vnode bitmaps of each table. An empty bitmap holds all virtual nodes.
+    vnode_bitmaps: HashMap<u32, Vec<u8>>,
+}
+
+impl HashOverlapInfo {
+    fn check_vnode_overlap(&self, table: &SstableInfo) -> bool {
+        if self.all_vnodes || table.vnode_bitmaps.is_empty() {
+            return true;
+        }
+        table.vnode_bitmaps.iter().any(|vnode_bitmap| {
+            match self.vnode_bitmaps.get(&vnode_bitmap.table_id) {
+                Some(bitmap) if bitmap.is_empty() || vnode_bitmap.bitmap.is_empty() => true,
+                Some(bitmap) => bitmap
+                    .iter()
+                    .zip(vnode_bitmap.bitmap.iter())
+                    .any(|(a, b)| a & b != 0),
+                None => false,
+            }
+        })
+    }
+}
+
+impl OverlapInfo for HashOverlapInfo {
+    fn check_overlap(&self, a: &SstableInfo) -> bool {
+        self.range_info.check_overlap(a) && self.check_vnode_overlap(a)
+    }
+
+    fn check_multiple_overlap(&self, others: &[SstableInfo]) -> Vec<SstableInfo> {
+        let tables = self.range_info.check_multiple_overlap(others);
+        let first = tables
+            .iter()
+            .position(|table| self.check_vnode_overlap(table));
+        let last = tables
+            .iter()
+            .rposition(|table| self.check_vnode_overlap(table));
+        match (first, last) {
+            (Some(first), Some(last)) => tables[first..=last].to_vec(),
+            _ => vec![],
+        }
+    }
+
+    fn update(&mut self, table: &SstableInfo) {
+        self.range_info.update(table);
+        if table.vnode_bitmaps.is_empty() {
+            self.all_vnodes = true;
+        }
+        for vnode_bitmap in &table.vnode_bitmaps {
+            let bitmap = self
+                .vnode_bitmaps
+                .entry(vnode_bitmap.table_id)
+                .or_insert_with(|| vnode_bitmap.bitmap.clone());
+            if bitmap.is_empty() || vnode_bitmap.bitmap.is_empty() {
+                bitmap.clear();
+            } else {
+                if bitmap.len() < vnode_bitmap.bitmap.len() {
+                    bitmap.resize(vnode_bitmap.bitmap.len(), 0);
+                }
+                for (a, b) in bitmap.iter_mut().zip(vnode_bitmap.bitmap.iter()) {
+                    *a |= b;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct HashOverlapStrategy {}
+
+impl OverlapStrategy for HashOverlapStrategy {
+    fn check_overlap(&self, a: &SstableInfo, b: &SstableInfo) -> bool {
+        let mut info = HashOverlapInfo::default();
+        info.update(a);
+        info.check_overlap(b)
+    }
+
+    fn create_overlap_info(&self) -> Box<dyn OverlapInfo> {
+        Box::new(HashOverlapInfo::default())
+    }
+}
+
+/// Tag of an overlap strategy, to choose the strategy in `CompactionConfig`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverlapStrategyType {
+    /// `RangeOverlapStrategy`
+    Range,
+    /// `ConservativeOverlapStrategy`
+    Conservative,
+    /// `HashOverlapStrategy`
+    Hash,
+}
+
+pub fn create_overlap_strategy(strategy_type: OverlapStrategyType) -> Arc<dyn OverlapStrategy> {
+    match strategy_type {
+        OverlapStrategyType::Range => Arc::new(RangeOverlapStrategy::default()),
+        OverlapStrategyType::Conservative => Arc::new(ConservativeOverlapStrategy::default()),
+        OverlapStrategyType::Hash => Arc::new(HashOverlapStrategy::default()),
+    }
+}
+
 fn check_table_overlap(key_range: &KeyRange, table: &SstableInfo) -> bool {
     let other = KeyRange::from(table.key_range.as_ref().unwrap());
     key_range.full_key_overlap(&other)
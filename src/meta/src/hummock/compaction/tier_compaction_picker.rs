@@ -83,9 +83,17 @@ impl CompactionPicker for TierCompactionPicker {
         let target_level = self.target_level;
 
         let next_task_id = self.compact_task_id;
-        if levels[select_level].table_infos.is_empty() {
+        if levels[select_level].table_infos.is_empty()
+            || levels[select_level].table_infos.len() < self.config.min_l0_sublevels_to_compact
+        {
             return None;
         }
+        if self.config.tier_only {
+            return self.pick_intra_l0_compaction(
+                &levels[select_level],
+                &mut level_handlers[select_level],
+            );
+        }
         let (select_level_inputs, target_level_inputs) = self.select_input_files(
             &levels[select_level],
             &levels[target_level],
@@ -98,6 +106,9 @@ impl CompactionPicker for TierCompactionPicker {
                 &mut level_handlers[select_level],
             );
         }
+        if !self.reach_min_compaction_bytes(&select_level_inputs) {
+            return None;
+        }
 
         level_handlers[select_level].add_pending_task(next_task_id, &select_level_inputs);
         level_handlers[target_level].add_pending_task(next_task_id, &target_level_inputs);
@@ -148,6 +159,14 @@ impl TierCompactionPicker {
         }
     }
 
+    /// Whether the L0 SSTs selected for a task sum up to more than `min_compaction_bytes`. The
+    /// overlapping SSTs of the target level are not counted, since they hold no new data to
+    /// compact, and a large one would otherwise make any tiny L0 input reach the threshold.
+    fn reach_min_compaction_bytes(&self, select_tables: &[SstableInfo]) -> bool {
+        let compaction_bytes: u64 = select_tables.iter().map(|table| table.file_size).sum();
+        self.config.min_compaction_bytes == 0 || compaction_bytes > self.config.min_compaction_bytes
+    }
+
     fn pick_target_level_overlap_files(
         &self,
         select_tables: &[SstableInfo],
@@ -203,7 +222,9 @@ impl TierCompactionPicker {
             compaction_bytes += table.file_size;
             select_level_inputs.push(table.clone());
         }
-        if select_level_inputs.len() < self.config.level0_trigger_number {
+        if select_level_inputs.len() < self.config.level0_trigger_number
+            || !self.reach_min_compaction_bytes(&select_level_inputs)
+        {
             return None;
         }
         level0_handler.add_pending_task(self.compact_task_id, &select_level_inputs);
@@ -335,6 +356,7 @@ pub mod tests {
             }),
             file_size: 1,
             vnode_bitmaps: vec![],
+            max_epoch: epoch,
         }
     }
 
@@ -430,6 +452,97 @@ pub mod tests {
         assert!(ret.target_level.table_infos.is_empty());
     }
 
+    #[test]
+    fn test_min_l0_sublevels_to_compact() {
+        let config = CompactionConfig {
+            min_l0_sublevels_to_compact: 3,
+            ..Default::default()
+        };
+        let picker = TierCompactionPicker::new(
+            0,
+            1,
+            Arc::new(config),
+            Arc::new(RangeOverlapStrategy::default()),
+        );
+        let mut levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![
+                    generate_table(1, 1, 100, 200, 2),
+                    generate_table(2, 1, 150, 250, 3),
+                ],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![generate_table(3, 1, 0, 300, 1)],
+            },
+        ];
+        let mut levels_handler = vec![LevelHandler::new(0), LevelHandler::new(1)];
+        assert!(picker
+            .pick_compaction(&levels, &mut levels_handler)
+            .is_none());
+
+        levels[0]
+            .table_infos
+            .push(generate_table(4, 1, 200, 300, 4));
+        let ret = picker
+            .pick_compaction(&levels, &mut levels_handler)
+            .unwrap();
+        assert_eq!(ret.select_level.table_infos.len(), 3);
+        assert_eq!(ret.target_level.table_infos[0].id, 3);
+    }
+
+    #[test]
+    fn test_min_compaction_bytes() {
+        let config = CompactionConfig {
+            min_compaction_bytes: 2,
+            ..Default::default()
+        };
+        let picker = TierCompactionPicker::new(
+            0,
+            1,
+            Arc::new(config),
+            Arc::new(RangeOverlapStrategy::default()),
+        );
+        let mut levels = vec![
+            Level {
+                level_idx: 0,
+                level_type: LevelType::Overlapping as i32,
+                table_infos: vec![generate_table(1, 1, 100, 200, 2)],
+            },
+            Level {
+                level_idx: 1,
+                level_type: LevelType::Nonoverlapping as i32,
+                table_infos: vec![generate_table(2, 1, 0, 300, 1)],
+            },
+        ];
+        let mut levels_handler = vec![LevelHandler::new(0), LevelHandler::new(1)];
+        assert!(picker
+            .pick_compaction(&levels, &mut levels_handler)
+            .is_none());
+        assert_eq!(levels_handler[0].get_pending_file_count(), 0);
+        assert_eq!(levels_handler[1].get_pending_file_count(), 0);
+
+        // The SST of L1 doesn't count, so 2 bytes of L0 still don't exceed the threshold.
+        levels[0]
+            .table_infos
+            .push(generate_table(3, 1, 150, 250, 3));
+        assert!(picker
+            .pick_compaction(&levels, &mut levels_handler)
+            .is_none());
+
+        levels[0]
+            .table_infos
+            .push(generate_table(4, 1, 120, 220, 4));
+        let ret = picker
+            .pick_compaction(&levels, &mut levels_handler)
+            .unwrap();
+        assert_eq!(ret.select_level.table_infos.len(), 3);
+        assert_eq!(ret.target_level.table_infos[0].id, 2);
+    }
+
     #[test]
     fn test_selecting_key_range_overlap() {
         // When picking L0->L1, all L1 files overlapped with selecting_key_range should be picked.
@@ -131,6 +131,7 @@ impl CompactorManager {
 
 #[cfg(test)]
 mod tests {
+    use risingwave_pb::hummock::compact_task::OutputTier;
     use risingwave_pb::hummock::{CompactMetrics, CompactTask, TableSetStatistics};
     use tokio::sync::mpsc::error::TryRecvError;
 
@@ -173,6 +174,10 @@ mod tests {
             task_status: false,
             prefix_pairs: vec![],
             vnode_mappings: vec![],
+            max_output_writers: 0,
+            priority: 0,
+            vnode_bitmaps: vec![],
+            output_tier: OutputTier::Hot as i32,
         }
     }
 
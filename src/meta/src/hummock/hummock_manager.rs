@@ -51,6 +51,9 @@ use crate::model::{
 use crate::rpc::metrics::MetaMetrics;
 use crate::storage::{MetaStore, Transaction};
 
+/// Interval to check whether writes can be resumed while they're stopped.
+const STOP_WRITES_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
 // Update to states are performed as follow:
 // - Initialize ValTransaction for the meta state to update
 // - Make changes on the ValTransaction.
@@ -142,6 +145,24 @@ where
         env: MetaSrvEnv<S>,
         cluster_manager: ClusterManagerRef<S>,
         metrics: Arc<MetaMetrics>,
+    ) -> Result<HummockManager<S>> {
+        // TODO: load it from etcd or configuration file.
+        Self::with_config(
+            env,
+            cluster_manager,
+            metrics,
+            Arc::new(CompactionConfig::default()),
+        )
+        .await
+    }
+
+    /// Creates a `HummockManager` compacting with `config`, which also applies to the compact
+    /// status loaded from the meta store.
+    pub async fn with_config(
+        env: MetaSrvEnv<S>,
+        cluster_manager: ClusterManagerRef<S>,
+        metrics: Arc<MetaMetrics>,
+        config: Arc<CompactionConfig>,
     ) -> Result<HummockManager<S>> {
         let instance = HummockManager {
             env,
@@ -160,8 +181,7 @@ where
             metrics,
             cluster_manager,
             compaction_scheduler: parking_lot::RwLock::new(None),
-            // TODO: load it from etcd or configuration file.
-            config: Arc::new(CompactionConfig::default()),
+            config,
         };
 
         instance.load_meta_store_state().await?;
@@ -178,9 +198,12 @@ where
         let config = self.config.clone();
         let mut compaction_guard = self.compaction.write().await;
 
-        compaction_guard.compact_status = CompactStatus::get(self.env.meta_store())
-            .await?
-            .unwrap_or_else(|| CompactStatus::new(config));
+        let mut compact_status = CompactStatus::new(config);
+        // The config isn't persisted with the status, so the configured one is kept.
+        if let Some(stored) = CompactStatus::get(self.env.meta_store()).await? {
+            compact_status.restore(stored);
+        }
+        compaction_guard.compact_status = compact_status;
 
         compaction_guard.compact_task_assignment =
             CompactTaskAssignment::list(self.env.meta_store())
@@ -366,6 +389,15 @@ where
         sstables: Vec<SstableInfo>,
         epoch: HummockEpoch,
     ) -> Result<HummockVersion> {
+        // Hold back new SSTs until compaction catches up on L0.
+        while self.should_stop_writes().await {
+            tracing::warn!(
+                "Writes are stopped until compaction reduces the files in L0, epoch {}",
+                epoch
+            );
+            tokio::time::sleep(STOP_WRITES_CHECK_INTERVAL).await;
+        }
+
         let mut versioning_guard = self.versioning.write().await;
 
         let versioning = versioning_guard.deref_mut();
@@ -412,6 +444,15 @@ where
             );
         }
 
+        // All data of the SSTs is written in `epoch` or before.
+        let sstables = sstables
+            .into_iter()
+            .map(|sst| SstableInfo {
+                max_epoch: epoch,
+                ..sst
+            })
+            .collect_vec();
+
         current_version_id.increase();
         let mut new_hummock_version = hummock_versions
             .new_entry_txn_or_default(current_version_id.id(), current_hummock_version);
@@ -558,24 +599,24 @@ where
         let compaction = compaction_guard.deref_mut();
         let mut compact_status = VarTransaction::new(&mut compaction.compact_status);
         let current_version = self.versioning.read().await.current_version();
-        let compact_task = compact_status.get_compact_task(&current_version.levels);
+        let safe_epoch = {
+            let versioning_guard = self.versioning.read().await;
+            let current_version_id = versioning_guard.current_version_id.id();
+            let max_committed_epoch = versioning_guard
+                .hummock_versions
+                .get(&current_version_id)
+                .unwrap()
+                .max_committed_epoch;
+            versioning_guard
+                .pinned_snapshots
+                .values()
+                .flat_map(|v| v.snapshot_id.clone())
+                .fold(max_committed_epoch, std::cmp::min)
+        };
+        let compact_task = compact_status.get_compact_task(&current_version.levels, safe_epoch);
         let ret = match compact_task {
             None => Ok(None),
             Some(mut compact_task) => {
-                compact_task.watermark = {
-                    let versioning_guard = self.versioning.read().await;
-                    let current_version_id = versioning_guard.current_version_id.id();
-                    let max_committed_epoch = versioning_guard
-                        .hummock_versions
-                        .get(&current_version_id)
-                        .unwrap()
-                        .max_committed_epoch;
-                    versioning_guard
-                        .pinned_snapshots
-                        .values()
-                        .flat_map(|v| v.snapshot_id.clone())
-                        .fold(max_committed_epoch, std::cmp::min)
-                };
                 let table_ids = compact_task
                     .input_ssts
                     .iter()
@@ -700,7 +741,7 @@ where
                     .id
                     .extend(level.table_infos.iter().map(|sst| sst.id).collect_vec());
             }
-            let mut new_version = CompactStatus::apply_compact_result(compact_task, old_version);
+            let mut new_version = CompactStatus::apply_compact_result(compact_task, old_version)?;
             current_version_id.increase();
             new_version.id = current_version_id.id();
             hummock_versions.insert(new_version.id, new_version);
@@ -1276,6 +1317,22 @@ where
         self.versioning.read().await.current_version()
     }
 
+    #[cfg(test)]
+    pub async fn get_compact_status(&self) -> CompactStatus {
+        self.compaction.read().await.compact_status.clone()
+    }
+
+    /// Returns whether ingestion should stop writing new SSTs until compaction catches up, see
+    /// `CompactStatus::should_stop_writes`.
+    pub async fn should_stop_writes(&self) -> bool {
+        let current_version = self.versioning.read().await.current_version();
+        self.compaction
+            .read()
+            .await
+            .compact_status
+            .should_stop_writes(&current_version.levels)
+    }
+
     pub fn set_compaction_scheduler(&self, sender: UnboundedSender<CompactionGroupId>) {
         *self.compaction_scheduler.write() = Some(sender);
     }
@@ -13,9 +13,11 @@
 // limitations under the License.
 
 use std::cmp::Ordering;
+use std::sync::Arc;
 use std::time::Duration;
 
 use itertools::Itertools;
+use prost::Message;
 use risingwave_common::util::epoch::INVALID_EPOCH;
 use risingwave_hummock_sdk::compact::compact_task_to_string;
 use risingwave_hummock_sdk::{
@@ -27,10 +29,14 @@ use risingwave_pb::hummock::{
     HummockVersionRefId,
 };
 
+use crate::hummock::compaction::{CompactStatus, CompactionConfig};
 use crate::hummock::error::Error;
 use crate::hummock::model::CurrentHummockVersionId;
 use crate::hummock::test_utils::*;
+use crate::hummock::HummockManager;
 use crate::model::MetadataModel;
+use crate::rpc::metrics::MetaMetrics;
+use crate::storage::MetaStore;
 
 fn pin_versions_sum(pin_versions: &[HummockPinnedVersion]) -> usize {
     pin_versions.iter().map(|p| p.version_id.len()).sum()
@@ -1018,3 +1024,112 @@ async fn test_mark_orphan_ssts() {
         .unwrap_err();
     assert!(matches!(error, Error::InternalError(_)));
 }
+
+#[tokio::test]
+async fn test_corrupted_compact_status() {
+    let (env, _hummock_manager, cluster_manager, _worker_node) = setup_compute_env(80).await;
+    env.meta_store()
+        .put_cf(
+            CompactStatus::cf_name(),
+            CompactStatus::key().as_bytes().to_vec(),
+            vec![0xff; 8],
+        )
+        .await
+        .unwrap();
+    // Loading the corrupted status on restart is an error instead of a panic.
+    let error = HummockManager::new(env, cluster_manager, Arc::new(MetaMetrics::new()))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::InternalError(_)));
+}
+
+#[tokio::test]
+async fn test_reload_compact_status_with_config() {
+    let (env, _hummock_manager, cluster_manager, _worker_node) = setup_compute_env(80).await;
+    let mut stored = CompactStatus::new(Arc::new(CompactionConfig::default()));
+    stored.next_compact_task_id = 4;
+    env.meta_store()
+        .put_cf(
+            CompactStatus::cf_name(),
+            CompactStatus::key().as_bytes().to_vec(),
+            risingwave_pb::hummock::CompactStatus::from(&stored).encode_to_vec(),
+        )
+        .await
+        .unwrap();
+    let config = CompactionConfig {
+        space_reclaim_first: true,
+        max_output_writers: 2,
+        ..Default::default()
+    };
+    let hummock_manager = HummockManager::with_config(
+        env,
+        cluster_manager,
+        Arc::new(MetaMetrics::new()),
+        Arc::new(config.clone()),
+    )
+    .await
+    .unwrap();
+    // The stored progress is loaded, and the configured knobs survive the reload.
+    let compact_status = hummock_manager.get_compact_status().await;
+    assert_eq!(compact_status, stored);
+    assert_eq!(compact_status.config(), &config);
+}
+
+#[tokio::test]
+async fn test_stop_writes() {
+    let (env, _hummock_manager, cluster_manager, worker_node) = setup_compute_env(80).await;
+    let context_id = worker_node.id;
+    let hummock_manager = HummockManager::with_config(
+        env,
+        cluster_manager,
+        Arc::new(MetaMetrics::new()),
+        Arc::new(CompactionConfig {
+            level0_trigger_number: 1,
+            level0_stop_writes_file_number: 2,
+            ..Default::default()
+        }),
+    )
+    .await
+    .unwrap();
+
+    // Fill L0 up to the ceiling.
+    let epoch = 1;
+    let test_tables = generate_test_tables(epoch, get_sst_ids(&hummock_manager, 2).await);
+    hummock_manager
+        .add_tables(context_id, test_tables, epoch)
+        .await
+        .unwrap();
+    hummock_manager.commit_epoch(epoch).await.unwrap();
+    assert!(hummock_manager.should_stop_writes().await);
+
+    // New SSTs are held back.
+    let epoch = epoch + 1;
+    let test_tables_2 = generate_test_tables(epoch, get_sst_ids(&hummock_manager, 1).await);
+    assert!(tokio::time::timeout(
+        Duration::from_millis(500),
+        hummock_manager.add_tables(context_id, test_tables_2.clone(), epoch),
+    )
+    .await
+    .is_err());
+
+    // And accepted once compaction moves the files out of L0.
+    let mut compact_task = hummock_manager.get_compact_task().await.unwrap().unwrap();
+    assert_eq!(compact_task.input_ssts[0].level_idx, 0);
+    assert_ne!(compact_task.target_level, 0);
+    hummock_manager
+        .assign_compaction_task(&compact_task, context_id, async { true })
+        .await
+        .unwrap();
+    compact_task.sorted_output_ssts =
+        generate_test_tables(1, get_sst_ids(&hummock_manager, 1).await);
+    compact_task.task_status = true;
+    assert!(hummock_manager
+        .report_compact_task(&compact_task)
+        .await
+        .unwrap());
+    assert!(!hummock_manager.should_stop_writes().await);
+    hummock_manager
+        .add_tables(context_id, test_tables_2, epoch)
+        .await
+        .unwrap();
+}
@@ -26,6 +26,7 @@ pub struct SSTableInfo {
     pub table_id: HummockSSTableId,
     pub file_size: u64,
     pub vnode_bitmaps: Vec<VNodeBitmap>,
+    pub max_epoch: u64,
 }
 
 impl From<&SstableInfo> for SSTableInfo {
@@ -35,6 +36,7 @@ impl From<&SstableInfo> for SSTableInfo {
             table_id: sst.id,
             file_size: sst.file_size,
             vnode_bitmaps: sst.vnode_bitmaps.clone(),
+            max_epoch: sst.max_epoch,
         }
     }
 }
@@ -46,6 +48,7 @@ impl From<SSTableInfo> for SstableInfo {
             id: info.table_id,
             file_size: info.file_size,
             vnode_bitmaps: info.vnode_bitmaps,
+            max_epoch: info.max_epoch,
         }
     }
 }
@@ -88,6 +91,20 @@ impl LevelHandler {
             .retain(|(task_id, _)| *task_id != target_task_id);
     }
 
+    /// Unlocks `ssts` of the task `target_task_id` while its other SSTs stay locked.
+    pub fn remove_ssts_of_task(&mut self, target_task_id: u64, ssts: &[HummockSSTableId]) {
+        for (task_id, task_ssts) in &mut self.pending_tasks {
+            if *task_id == target_task_id {
+                task_ssts.retain(|sst| !ssts.contains(sst));
+            }
+        }
+        for sst in ssts {
+            if self.compacting_files.get(sst) == Some(&target_task_id) {
+                self.compacting_files.remove(sst);
+            }
+        }
+    }
+
     pub fn is_pending_compact(&self, sst_id: &HummockSSTableId) -> bool {
         self.compacting_files.contains_key(sst_id)
     }
@@ -101,6 +118,11 @@ impl LevelHandler {
         self.pending_tasks.push((task_id, table_ids));
     }
 
+    /// Returns the id of the task compacting `sst_id`, if any.
+    pub fn compacting_task_id(&self, sst_id: &HummockSSTableId) -> Option<u64> {
+        self.compacting_files.get(sst_id).copied()
+    }
+
     pub fn get_pending_file_count(&self) -> usize {
         self.compacting_files.len()
     }
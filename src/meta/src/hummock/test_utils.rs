@@ -106,6 +106,7 @@ pub fn generate_test_tables(epoch: u64, sst_ids: Vec<HummockSSTableId>) -> Vec<S
                     bitmap: vec![],
                 },
             ],
+            max_epoch: epoch,
         });
     }
     sst_info
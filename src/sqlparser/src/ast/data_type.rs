@@ -11,22 +11,31 @@
 // limitations under the License.
 
 #[cfg(not(feature = "std"))]
-use alloc::boxed::Box;
+use alloc::{boxed::Box, string::String};
 use core::fmt;
 
+use risingwave_common::array::{ListValue, StructValue};
+use risingwave_common::error::{ErrorCode, Result};
+pub use risingwave_common::types::ScalarVariant;
+use risingwave_common::types::{
+    DataType as Common_Data_Type, Decimal, IntervalUnit, NaiveDateTimeWrapper, NaiveDateWrapper,
+    NaiveTimeWrapper, OrderedF32, OrderedF64, ScalarImpl,
+};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::ast::ObjectName;
+use crate::ast::value::escape_single_quote_string;
+use crate::ast::{display_comma_separated, DateTimeField, Ident, ObjectName};
 
 /// SQL data types
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DataType {
-    /// Fixed-length character type e.g. CHAR(10)
-    Char(Option<u64>),
-    /// Variable-length character type e.g. VARCHAR(10)
-    Varchar(Option<u64>),
+    /// Fixed-length character type with optional character set e.g. CHAR(10) CHARACTER SET utf8
+    Char(Option<u64>, Option<CharacterSet>),
+    /// Variable-length character type with optional character set e.g. VARCHAR(10) CHARACTER SET
+    /// utf8 COLLATE utf8_bin
+    Varchar(Option<u64>, Option<CharacterSet>),
     /// Uuid type
     Uuid,
     /// Large character object e.g. CLOB(1000)
@@ -39,6 +48,8 @@ pub enum DataType {
     Blob(u64),
     /// Decimal type with optional precision and scale e.g. DECIMAL(10,2)
     Decimal(Option<u64>, Option<u64>),
+    /// Currency amount with an optional ISO 4217 currency code e.g. MONEY('USD')
+    Money(Option<String>),
     /// Floating point with optional precision e.g. FLOAT(8)
     Float(Option<u64>),
     /// Tiny integer with optional display width e.g. TINYINT or TINYINT(3)
@@ -49,6 +60,14 @@ pub enum DataType {
     Int(Option<u64>),
     /// Big integer with optional display width e.g. BIGINT or BIGINT(20)
     BigInt(Option<u64>),
+    /// Unsigned tiny integer with optional display width e.g. TINYINT UNSIGNED
+    UnsignedTinyInt(Option<u64>),
+    /// Unsigned small integer with optional display width e.g. SMALLINT UNSIGNED
+    UnsignedSmallInt(Option<u64>),
+    /// Unsigned integer with optional display width e.g. INT UNSIGNED or INT(10) UNSIGNED
+    UnsignedInt(Option<u64>),
+    /// Unsigned big integer with optional display width e.g. BIGINT UNSIGNED
+    UnsignedBigInt(Option<u64>),
     /// Floating point e.g. REAL
     Real,
     /// Double e.g. DOUBLE PRECISION
@@ -57,32 +76,1064 @@ pub enum DataType {
     Boolean,
     /// Date
     Date,
-    /// Time with optional time zone
-    Time(bool),
-    /// Timestamp with optional time zone
-    Timestamp(bool),
-    /// Interval
-    Interval,
+    /// Time with optional fractional seconds precision and time zone e.g. TIME(3) WITH TIME ZONE
+    Time(Option<u64>, bool),
+    /// Timestamp with optional fractional seconds precision and time zone e.g. TIMESTAMP(3) WITH
+    /// TIME ZONE
+    Timestamp(Option<u64>, bool),
+    /// Interval with optional fields qualifier and fractional seconds precision e.g. INTERVAL DAY
+    /// TO SECOND(6) or INTERVAL(3)
+    Interval(Option<IntervalFields>, Option<u64>),
     /// Regclass used in postgresql serial
     Regclass,
+    /// Name used by postgresql catalogs for identifiers of at most 63 bytes
+    Name,
+    /// Single-byte internal character type "char" used by postgresql catalogs, which is
+    /// different from CHAR(1)
+    InternalChar,
     /// Text
     Text,
     /// String
     String,
     /// Bytea
     Bytea,
+    /// JSON stored as text
+    Json,
+    /// JSON stored in a decomposed binary format
+    Jsonb,
     /// Custom type such as enums
     Custom(ObjectName),
-    /// Arrays
+    /// Void, i.e. no value, returned by procedure-like functions. It's not the type of any value
+    Void,
+    /// Type of an untyped `NULL` literal, which adopts the type of its context, e.g. the other
+    /// elements in `ARRAY[NULL, 1]`. It can't be written in SQL.
+    Unknown,
+    /// Range of a subtype e.g. RANGE<INT>, like `int4range` in postgresql
+    Range(Box<DataType>),
+    /// Struct with named fields e.g. STRUCT<a INT, b VARCHAR>
+    Struct(Vec<StructField>),
+    /// Arrays. The `Box` is transparent to serde, so e.g. `INT[]` is serialized to JSON as
+    /// `{"Array":{"Int":null}}`.
     Array(Box<DataType>),
 }
 
+/// Max precision of `DECIMAL(p, s)` accepted by `DataType::to_data_type`.
+pub const MAX_DECIMAL_PRECISION: u64 = 38;
+
+/// Max precision of `DECIMAL(p, s)` whose values fit in a 64-bit integer after being scaled by
+/// `10^s`.
+pub const MAX_SCALED_INT_DECIMAL_PRECISION: u64 = 18;
+
+/// Max nesting depth of the types accepted by `DataType::to_data_type`, see
+/// `DataType::nesting_depth`.
+pub const DEFAULT_MAX_NESTING_DEPTH: usize = 32;
+
+/// Options of converting AST data types to the data types used in execution.
+#[derive(Debug, Clone)]
+pub struct DataTypeConversionConfig {
+    /// Whether to hint storage to keep `DECIMAL(p, s)` with small precision as scaled integers.
+    pub enable_scaled_int_decimal: bool,
+    /// Types nested deeper than this are rejected, since the conversion is recursive.
+    pub max_nesting_depth: usize,
+}
+
+impl Default for DataTypeConversionConfig {
+    fn default() -> Self {
+        Self {
+            enable_scaled_int_decimal: false,
+            max_nesting_depth: DEFAULT_MAX_NESTING_DEPTH,
+        }
+    }
+}
+
+/// Catalog of user-defined types, which `DataType::to_data_type_with_catalog` resolves `Custom`
+/// types with.
+pub trait TypeCatalog {
+    /// Returns the base type of the domain named `name` e.g. `INT` for `positive_int` created by
+    /// `CREATE DOMAIN positive_int AS INT`, or `None` if there is no such domain.
+    fn domain_base_type(&self, name: &ObjectName) -> Option<DataType>;
+}
+
+/// Hint to storage about how values of a converted data type can be encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageHint {
+    /// Decimals stored as int64 values scaled by `10^scale`, instead of the generic decimal codec.
+    ScaledInt64 { scale: u64 },
+}
+
+/// Modifiers declared with a type, like the type modifier (`typmod`) in PostgreSQL.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeModifier {
+    /// Fractional seconds precision of a timestamp or interval type e.g. 3 for `TIMESTAMP(3)` or
+    /// `INTERVAL(3)`
+    pub precision: Option<u64>,
+    /// Fields qualifier of an interval type e.g. `DAY TO SECOND` for `INTERVAL DAY TO SECOND`
+    pub interval_fields: Option<IntervalFields>,
+    /// Whether the elements of an array type can be null, which they always can as in PostgreSQL,
+    /// e.g. a `BOOLEAN[]` holds true, false and null elements under three-valued logic
+    pub nullable_elements: bool,
+    /// Length to blank-pad the values of a `CHAR(n)` type to, whose values are compared with
+    /// trailing spaces ignored unlike `VARCHAR`. `None` for other types.
+    pub blank_padded_length: Option<u64>,
+    /// ISO 4217 currency code of a `MONEY` type to format its values with e.g. `USD` for
+    /// `MONEY('USD')`. `None` for other types or `MONEY` without a code.
+    pub currency_code: Option<String>,
+}
+
+/// Fields qualifier of an interval type, which restricts the fields stored in the interval e.g.
+/// `DAY TO SECOND`. The value is still normalized to months, days and microseconds in execution.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct IntervalFields {
+    pub leading_field: DateTimeField,
+    /// Precision of the leading field other than `SECOND` e.g. 3 for `DAY(3) TO SECOND`
+    pub leading_precision: Option<u64>,
+    pub last_field: Option<DateTimeField>,
+}
+
+impl fmt::Display for IntervalFields {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.leading_field)?;
+        if let Some(leading_precision) = self.leading_precision {
+            write!(f, "({})", leading_precision)?;
+        }
+        if let Some(last_field) = &self.last_field {
+            write!(f, " TO {}", last_field)?;
+        }
+        Ok(())
+    }
+}
+
+/// Named field of a struct type e.g. `a INT` in `STRUCT<a INT, b VARCHAR>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StructField {
+    pub name: Ident,
+    pub data_type: DataType,
+}
+
+impl fmt::Display for StructField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.data_type)
+    }
+}
+
+/// Character set of a character type, with the collation optionally following it e.g.
+/// `CHARACTER SET utf8 COLLATE utf8_bin`. A `COLLATE` without a character set is parsed as the
+/// collation of the column instead.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CharacterSet {
+    pub name: ObjectName,
+    pub collation: Option<ObjectName>,
+}
+
+impl fmt::Display for CharacterSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CHARACTER SET {}", self.name)?;
+        if let Some(collation) = &self.collation {
+            write!(f, " COLLATE {}", collation)?;
+        }
+        Ok(())
+    }
+}
+
+impl DataType {
+    /// Converts the AST data type to the data type used in execution. Character sets and
+    /// collations are not supported in execution, so they are ignored. Types nested deeper than
+    /// `DEFAULT_MAX_NESTING_DEPTH` are rejected.
+    pub fn to_data_type(&self) -> Result<Common_Data_Type> {
+        self.check_nesting_depth(DEFAULT_MAX_NESTING_DEPTH)?;
+        self.to_data_type_inner()
+    }
+
+    /// Returns how deeply the type is nested, i.e. 0 for scalar types, 1 for arrays, ranges and
+    /// structs of scalars, and so on. Computed without recursion so that it can't overflow the
+    /// stack on however deep a type.
+    pub fn nesting_depth(&self) -> usize {
+        let mut max_depth = 0;
+        let mut stack = vec![(self, 0)];
+        while let Some((data_type, depth)) = stack.pop() {
+            max_depth = max_depth.max(depth);
+            match data_type {
+                DataType::Array(inner) | DataType::Range(inner) => {
+                    stack.push((inner.as_ref(), depth + 1));
+                }
+                DataType::Struct(fields) => {
+                    stack.extend(fields.iter().map(|field| (&field.data_type, depth + 1)));
+                }
+                _ => {}
+            }
+        }
+        max_depth
+    }
+
+    fn check_nesting_depth(&self, max_nesting_depth: usize) -> Result<()> {
+        let depth = self.nesting_depth();
+        if depth > max_nesting_depth {
+            return Err(ErrorCode::BindError(format!(
+                "type nested {} levels deep exceeds the limit of {}",
+                depth, max_nesting_depth
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    fn to_data_type_inner(&self) -> Result<Common_Data_Type> {
+        let data_type = match self {
+            DataType::Boolean => Common_Data_Type::Boolean,
+            // There is no 8-bit integer type in execution.
+            DataType::TinyInt(None) => Common_Data_Type::Int16,
+            DataType::SmallInt(None) => Common_Data_Type::Int16,
+            DataType::Int(None) => Common_Data_Type::Int32,
+            DataType::BigInt(None) => Common_Data_Type::Int64,
+            // Unsigned integers are widened to the next wider signed type to hold their range,
+            // and the unsigned 64-bit one to decimal as there is no wider integer type.
+            DataType::UnsignedTinyInt(None) => Common_Data_Type::Int16,
+            DataType::UnsignedSmallInt(None) => Common_Data_Type::Int32,
+            DataType::UnsignedInt(None) => Common_Data_Type::Int64,
+            DataType::UnsignedBigInt(None) => Common_Data_Type::Decimal,
+            DataType::Real | DataType::Float(Some(1..=24)) => Common_Data_Type::Float32,
+            DataType::Double | DataType::Float(Some(25..=53) | None) => Common_Data_Type::Float64,
+            DataType::Decimal(None, None) => Common_Data_Type::Decimal,
+            DataType::Decimal(Some(precision), scale) => {
+                check_decimal_precision_scale(*precision, *scale)?;
+                Common_Data_Type::Decimal
+            }
+            // The currency code is only used to format the amounts, see
+            // `to_data_type_with_modifier`.
+            DataType::Money(currency_code) => {
+                if let Some(currency_code) = currency_code {
+                    check_currency_code(currency_code)?;
+                }
+                Common_Data_Type::Decimal
+            }
+            DataType::Varchar(length, _) => {
+                check_char_length("VARCHAR", *length)?;
+                Common_Data_Type::Varchar
+            }
+            // There are no fixed-length strings in execution, so `CHAR(n)` is taken as a
+            // `VARCHAR`, whose values are neither truncated nor blank-padded to the length. See
+            // `to_data_type_with_modifier` for the padding semantics.
+            DataType::Char(length, _) => {
+                check_char_length("CHAR", *length)?;
+                Common_Data_Type::Varchar
+            }
+            // `NAME` is a `VARCHAR(64)` in PostgreSQL, i.e. 63 bytes plus the terminator. The
+            // length is not enforced here, just like the one of `VARCHAR`.
+            DataType::Name => Common_Data_Type::Varchar,
+            // `"char"` holds a single byte in PostgreSQL, and is output as a string of that byte.
+            // It's taken as a `VARCHAR` without enforcing the length.
+            DataType::InternalChar => Common_Data_Type::Varchar,
+            DataType::Date => Common_Data_Type::Date,
+            DataType::Time(_, false) => Common_Data_Type::Time,
+            // There is no time with time zone in execution, and PostgreSQL discourages it as well
+            // since the offset of a time zone depends on the date.
+            DataType::Time(_, true) => {
+                return Err(ErrorCode::NotImplemented(
+                    "TIME WITH TIME ZONE is not supported, please use TIME or TIMESTAMP WITH TIME \
+                     ZONE instead"
+                        .to_string(),
+                    None.into(),
+                )
+                .into())
+            }
+            DataType::Timestamp(_, false) => Common_Data_Type::Timestamp,
+            DataType::Timestamp(_, true) => Common_Data_Type::Timestampz,
+            DataType::Interval(..) => Common_Data_Type::Interval,
+            DataType::Array(datatype) => Common_Data_Type::List {
+                datatype: Box::new(datatype.to_data_type_inner()?),
+            },
+            // A range is represented as a struct of its lower and upper bounds, where a null bound
+            // means unbounded. Whether the bounds are inclusive is not kept, so the bounds are
+            // always taken as `[lower, upper)` like the canonical form in postgresql.
+            DataType::Range(subtype) => {
+                let subtype = subtype.to_data_type_inner()?;
+                Common_Data_Type::Struct {
+                    fields: vec![subtype.clone(), subtype].into(),
+                }
+            }
+            // Fields are accessed by their indices in execution, so the names are not kept. They
+            // must be unique so that a field can be resolved by its name unambiguously.
+            DataType::Struct(fields) => {
+                let names: Vec<_> = fields.iter().map(|field| fold_ident(&field.name)).collect();
+                for (i, name) in names.iter().enumerate() {
+                    if names[..i].contains(name) {
+                        return Err(ErrorCode::BindError(format!(
+                            "duplicate field name \"{}\" in struct type",
+                            name
+                        ))
+                        .into());
+                    }
+                }
+                Common_Data_Type::Struct {
+                    fields: fields
+                        .iter()
+                        .map(|field| field.data_type.to_data_type_inner())
+                        .collect::<Result<Vec<_>>>()?
+                        .into(),
+                }
+            }
+            // There is no void type in execution, as no value can be of it.
+            DataType::Void => {
+                return Err(ErrorCode::BindError(
+                    "VOID is only valid as the return type of a function".to_string(),
+                )
+                .into())
+            }
+            DataType::Unknown => {
+                return Err(ErrorCode::BindError(
+                    "cannot determine the type of NULL without context".to_string(),
+                )
+                .into())
+            }
+            DataType::Json | DataType::Jsonb => {
+                return Err(ErrorCode::NotImplemented(
+                    format!("{} is not supported yet, please use VARCHAR instead", self),
+                    None.into(),
+                )
+                .into())
+            }
+            // The length of `BINARY` and `VARBINARY` is neither enforced nor padded to, same as
+            // `VARCHAR`.
+            DataType::Bytea | DataType::Binary(_) | DataType::Varbinary(_) => {
+                Common_Data_Type::Bytea
+            }
+            DataType::Blob(_) | DataType::Clob(_) => {
+                return Err(ErrorCode::NotImplemented(
+                    format!(
+                        "large object type {} is not supported, please use BYTEA or VARCHAR \
+                         instead",
+                        self
+                    ),
+                    None.into(),
+                )
+                .into())
+            }
+            _ => {
+                return Err(ErrorCode::NotImplemented(
+                    format!("unsupported data type: {:?}", self),
+                    None.into(),
+                )
+                .into())
+            }
+        };
+        Ok(data_type)
+    }
+
+    /// Converts the AST data type like `to_data_type`, and also returns warnings about the
+    /// information lost in the conversion, e.g. `TINYINT` widened to `SMALLINT` or the length of
+    /// `VARCHAR(n)` which is not enforced.
+    pub fn to_data_type_checked(&self) -> Result<(Common_Data_Type, Vec<String>)> {
+        let data_type = self.to_data_type()?;
+        let mut warnings = vec![];
+        self.collect_conversion_warnings(&mut warnings);
+        Ok((data_type, warnings))
+    }
+
+    fn collect_conversion_warnings(&self, warnings: &mut Vec<String>) {
+        match self {
+            DataType::TinyInt(_) => warnings.push("TINYINT is widened to SMALLINT".to_string()),
+            DataType::UnsignedTinyInt(_)
+            | DataType::UnsignedSmallInt(_)
+            | DataType::UnsignedInt(_)
+            | DataType::UnsignedBigInt(_) => warnings.push(format!(
+                "{} is converted to a signed type, which doesn't reject negative values",
+                self
+            )),
+            DataType::Decimal(None, None) => {
+                warnings.push("DECIMAL without precision doesn't bound its values".to_string())
+            }
+            DataType::Decimal(Some(_), _) => {
+                warnings.push(format!("precision and scale of {} are not enforced", self))
+            }
+            DataType::Char(length, charset) => {
+                warnings.push(format!(
+                    "blank-padding of CHAR({}) is not enforced",
+                    length.unwrap_or(1)
+                ));
+                if let Some(charset) = charset {
+                    warnings.push(format!("{} is ignored", charset));
+                }
+            }
+            DataType::Varchar(length, charset) => {
+                if let Some(length) = length {
+                    warnings.push(format!(
+                        "length {} of CHARACTER VARYING is not enforced",
+                        length
+                    ));
+                }
+                if let Some(charset) = charset {
+                    warnings.push(format!("{} is ignored", charset));
+                }
+            }
+            DataType::Array(inner) | DataType::Range(inner) => {
+                inner.collect_conversion_warnings(warnings)
+            }
+            DataType::Struct(fields) => {
+                for field in fields {
+                    field.data_type.collect_conversion_warnings(warnings);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Converts the AST data type of column `col_name` like `to_data_type`. The column name is
+    /// prefixed to the error message if the conversion fails.
+    pub fn to_data_type_for_column(&self, col_name: &str) -> Result<Common_Data_Type> {
+        self.to_data_type().map_err(|e| {
+            match e.inner() {
+                ErrorCode::NotImplemented(msg, issue) => {
+                    ErrorCode::NotImplemented(format!("column \"{}\": {}", col_name, msg), *issue)
+                }
+                _ => ErrorCode::BindError(format!("column \"{}\": {}", col_name, e)),
+            }
+            .into()
+        })
+    }
+
+    /// Returns the tag of the `ScalarImpl` variant holding the values of the type, so that
+    /// literals of the type can be built without matching on the type again. It fails if the type
+    /// can't be converted by `to_data_type`.
+    pub fn scalar_variant(&self) -> Result<ScalarVariant> {
+        Ok(self.to_data_type()?.scalar_variant())
+    }
+
+    /// Returns the fields qualifier declared with an interval type, or `None` for a bare `INTERVAL`
+    /// or other types.
+    pub fn interval_fields(&self) -> Option<IntervalFields> {
+        match self {
+            DataType::Interval(fields, _) => fields.clone(),
+            _ => None,
+        }
+    }
+
+    /// Converts the AST data type like `to_data_type`, and also returns the modifiers declared
+    /// with the type, or implied by it like the nullability of array elements, which the data type
+    /// used in execution doesn't keep. `CHAR(n)` is converted to a `VARCHAR` as well, with the
+    /// blank-padding semantics in the modifier, since comparing its values as a plain `VARCHAR`
+    /// would take trailing spaces into account.
+    pub fn to_data_type_with_modifier(&self) -> Result<(Common_Data_Type, TypeModifier)> {
+        let modifier = match self {
+            // `CHAR` without a length is `CHAR(1)`.
+            DataType::Char(size, _) => TypeModifier {
+                blank_padded_length: Some(size.unwrap_or(1)),
+                ..Default::default()
+            },
+            DataType::Time(precision, _) | DataType::Timestamp(precision, _) => TypeModifier {
+                precision: *precision,
+                ..Default::default()
+            },
+            DataType::Interval(fields, precision) => TypeModifier {
+                precision: *precision,
+                interval_fields: fields.clone(),
+                ..Default::default()
+            },
+            DataType::Array(_) => TypeModifier {
+                nullable_elements: true,
+                ..Default::default()
+            },
+            DataType::Money(currency_code) => TypeModifier {
+                currency_code: currency_code.clone(),
+                ..Default::default()
+            },
+            _ => TypeModifier::default(),
+        };
+        Ok((self.to_data_type()?, modifier))
+    }
+
+    /// Converts the AST data type like `to_data_type`, and also returns the storage hint of the
+    /// type under `config`. When scaled integer decimals are enabled, `DECIMAL(p, s)` is accepted
+    /// and hinted as `StorageHint::ScaledInt64` if `p` is small enough, or falls back to the
+    /// generic decimal otherwise. The max nesting depth is taken from `config` as well.
+    pub fn to_data_type_with_hint(
+        &self,
+        config: &DataTypeConversionConfig,
+    ) -> Result<(Common_Data_Type, Option<StorageHint>)> {
+        self.check_nesting_depth(config.max_nesting_depth)?;
+        match self {
+            DataType::Decimal(Some(precision), scale) if config.enable_scaled_int_decimal => {
+                // Validated the same as without the hint.
+                let data_type = self.to_data_type_inner()?;
+                let hint = (*precision <= MAX_SCALED_INT_DECIMAL_PRECISION).then(|| {
+                    StorageHint::ScaledInt64 {
+                        scale: scale.unwrap_or(0),
+                    }
+                });
+                Ok((data_type, hint))
+            }
+            _ => Ok((self.to_data_type_inner()?, None)),
+        }
+    }
+
+    /// Converts the AST data type like `to_data_type`, after resolving `Custom` types that are
+    /// domains in `catalog` to their base types, following domains over other domains. The
+    /// constraints of domains are not enforced here.
+    pub fn to_data_type_with_catalog(&self, catalog: &dyn TypeCatalog) -> Result<Common_Data_Type> {
+        self.check_nesting_depth(DEFAULT_MAX_NESTING_DEPTH)?;
+        self.resolve_domains(catalog, &mut vec![])?.to_data_type()
+    }
+
+    /// Replaces the domains in `self` with their base types. `resolving` holds the domains being
+    /// resolved, to detect a domain defined in terms of itself.
+    fn resolve_domains(
+        &self,
+        catalog: &dyn TypeCatalog,
+        resolving: &mut Vec<ObjectName>,
+    ) -> Result<DataType> {
+        let data_type = match self {
+            DataType::Custom(name) => {
+                let base_type = match catalog.domain_base_type(name) {
+                    Some(base_type) => base_type,
+                    // Not a domain, e.g. an enum, which is left to `to_data_type` to reject.
+                    None => return Ok(self.clone()),
+                };
+                if resolving
+                    .iter()
+                    .any(|domain| same_object_name(domain, name))
+                {
+                    return Err(ErrorCode::BindError(format!(
+                        "domain {} is defined in terms of itself",
+                        name
+                    ))
+                    .into());
+                }
+                resolving.push(name.clone());
+                let base_type = base_type.resolve_domains(catalog, resolving)?;
+                resolving.pop();
+                base_type
+            }
+            DataType::Array(inner) => {
+                DataType::Array(Box::new(inner.resolve_domains(catalog, resolving)?))
+            }
+            DataType::Range(inner) => {
+                DataType::Range(Box::new(inner.resolve_domains(catalog, resolving)?))
+            }
+            DataType::Struct(fields) => DataType::Struct(
+                fields
+                    .iter()
+                    .map(|field| {
+                        Ok(StructField {
+                            name: field.name.clone(),
+                            data_type: field.data_type.resolve_domains(catalog, resolving)?,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            _ => self.clone(),
+        };
+        Ok(data_type)
+    }
+
+    /// Returns the integer type of the buckets when a time-partitioned table is partitioned by a
+    /// column of this type, or `None` if the type can't be a time partition key. Only points in
+    /// time, i.e. dates and timestamps, can, whose buckets are numbered by `BIGINT`.
+    pub fn partition_bucket_type(&self) -> Option<DataType> {
+        match self {
+            DataType::Date | DataType::Timestamp(..) => Some(DataType::BigInt(None)),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the values of `self` can be widened to `target` losslessly, e.g. `INT` to
+    /// `BIGINT`, so that changing a column to `target` doesn't need to rewrite the table. A type
+    /// can always be widened to the same type.
+    pub fn can_widen_to(&self, target: &DataType) -> bool {
+        fn int_rank(data_type: &DataType) -> Option<u8> {
+            match data_type {
+                DataType::TinyInt(_) => Some(0),
+                DataType::SmallInt(_) => Some(1),
+                DataType::Int(_) => Some(2),
+                DataType::BigInt(_) => Some(3),
+                _ => None,
+            }
+        }
+
+        fn float_rank(data_type: &DataType) -> Option<u8> {
+            match data_type {
+                DataType::Real | DataType::Float(Some(1..=24)) => Some(0),
+                DataType::Double | DataType::Float(Some(25..=53) | None) => Some(1),
+                _ => None,
+            }
+        }
+
+        if self.same_type(target) {
+            return true;
+        }
+        if let (Some(from), Some(to)) = (int_rank(self), int_rank(target)) {
+            return from <= to;
+        }
+        if let (Some(from), Some(to)) = (float_rank(self), float_rank(target)) {
+            return from <= to;
+        }
+        match (self, target) {
+            // Changing the character set is not a widening.
+            (DataType::Varchar(from, from_charset), DataType::Varchar(to, to_charset)) => {
+                from_charset == to_charset
+                    && match (from, to) {
+                        (_, None) => true,
+                        (None, Some(_)) => false,
+                        (Some(from), Some(to)) => from <= to,
+                    }
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the common supertype which values of both `self` and `other` can be converted to,
+    /// or `None` if they are incompatible. Besides widening, integers are promoted to decimals and
+    /// both are promoted to floating points, like the type resolution of `UNION` in PostgreSQL.
+    pub fn common_supertype(&self, other: &DataType) -> Option<DataType> {
+        fn numeric_rank(data_type: &DataType) -> Option<u8> {
+            match data_type {
+                DataType::TinyInt(_)
+                | DataType::SmallInt(_)
+                | DataType::Int(_)
+                | DataType::BigInt(_) => Some(0),
+                DataType::Decimal(..) => Some(1),
+                DataType::Real | DataType::Double | DataType::Float(_) => Some(2),
+                _ => None,
+            }
+        }
+
+        // A NULL fits any type.
+        if *other == DataType::Unknown {
+            return Some(self.clone());
+        }
+        if *self == DataType::Unknown {
+            return Some(other.clone());
+        }
+        if other.can_widen_to(self) {
+            return Some(self.clone());
+        }
+        if self.can_widen_to(other) {
+            return Some(other.clone());
+        }
+        match (numeric_rank(self), numeric_rank(other)) {
+            // Decimals of different precision or scale.
+            (Some(1), Some(1)) => Some(DataType::Decimal(None, None)),
+            (Some(rank), Some(other_rank)) if rank > other_rank => Some(self.clone()),
+            (Some(rank), Some(other_rank)) if rank < other_rank => Some(other.clone()),
+            _ => None,
+        }
+    }
+
+    /// Infers the type of an `ARRAY[...]` constructor from the types of its `elements`, i.e. an
+    /// array of their common supertype. `NULL` elements of `Unknown` type adopt the type of the
+    /// others, and the element type stays `Unknown` if all of them are `NULL`.
+    pub fn infer_array_type(elements: &[DataType]) -> Result<DataType> {
+        let (first, rest) = elements.split_first().ok_or_else(|| {
+            ErrorCode::BindError("cannot determine type of empty array".to_string())
+        })?;
+        let element_type = rest.iter().try_fold(first.clone(), |supertype, element| {
+            supertype.common_supertype(element).ok_or_else(|| {
+                ErrorCode::BindError(format!(
+                    "ARRAY types {} and {} cannot be matched",
+                    supertype, element
+                ))
+            })
+        })?;
+        Ok(DataType::Array(Box::new(element_type)))
+    }
+
+    /// Returns whether elements of type `value_element` can be inserted into an array column of
+    /// type `self`, e.g. `ARRAY[1, 2]` of `INT` elements into a `BIGINT[]` column. Like the
+    /// assignment of scalars, an element is assignable if it converts to the element type of the
+    /// column without loss, i.e. is widened or promoted to it as by `common_supertype`. Strings are
+    /// assignable to strings of any length since the length is not enforced, and `NULL` elements
+    /// to anything. Returns false if `self` is not an array.
+    pub fn array_element_assignable(&self, value_element: &DataType) -> bool {
+        match self {
+            DataType::Array(column_element) => value_element.assignable_to(column_element),
+            _ => false,
+        }
+    }
+
+    fn assignable_to(&self, target: &DataType) -> bool {
+        fn is_string(data_type: &DataType) -> bool {
+            matches!(
+                data_type,
+                DataType::Char(..) | DataType::Varchar(..) | DataType::Text | DataType::String
+            )
+        }
+
+        match (self, target) {
+            (DataType::Array(value), DataType::Array(target)) => value.assignable_to(target),
+            _ if is_string(self) && is_string(target) => true,
+            _ => match self.common_supertype(target) {
+                Some(supertype) => supertype.same_type(target),
+                None => false,
+            },
+        }
+    }
+
+    /// Returns the index of the field `name` of a struct type, or `None` if there's no such field
+    /// or the type is not a struct. `name` is matched against the field names after SQL case
+    /// folding, so it should be folded already, e.g. by `Ident::real_value`.
+    pub fn struct_field_index(&self, name: &str) -> Option<usize> {
+        match self {
+            DataType::Struct(fields) => fields
+                .iter()
+                .position(|field| fold_ident(&field.name) == name),
+            _ => None,
+        }
+    }
+
+    /// Returns the type of the field `name` of a struct type like `struct_field_index`.
+    pub fn struct_field_type(&self, name: &str) -> Option<&DataType> {
+        match self {
+            DataType::Struct(fields) => self
+                .struct_field_index(name)
+                .map(|index| &fields[index].data_type),
+            _ => None,
+        }
+    }
+
+    /// Returns the default value of the type, i.e. zero for numbers, `false`, the empty string,
+    /// `1970-01-01 00:00:00` for points in time, a zero interval, an empty array, and a struct of
+    /// the defaults of its fields. Fails if the type can't be converted by `to_data_type`.
+    pub fn default_scalar(&self) -> Result<ScalarImpl> {
+        Ok(default_scalar_of(&self.to_data_type()?))
+    }
+
+    /// Returns the default values of `types` like `default_scalar`, e.g. as a sample row to
+    /// preview the schema of a table with columns of `types`. Fails if any type has no default.
+    pub fn default_row(types: &[DataType]) -> Result<Vec<ScalarImpl>> {
+        types.iter().map(DataType::default_scalar).collect()
+    }
+
+    /// Returns whether `self` and `other` denote the same type. Unlike `==`, names of `Custom`
+    /// types are compared after SQL case folding, i.e. unquoted identifiers are matched
+    /// case-insensitively while quoted ones must match exactly.
+    pub fn same_type(&self, other: &DataType) -> bool {
+        match (self, other) {
+            (DataType::Custom(a), DataType::Custom(b)) => same_object_name(a, b),
+            (DataType::Array(a), DataType::Array(b)) => a.same_type(b),
+            _ => self == other,
+        }
+    }
+
+    /// Returns the spelling of `self` used in generated DDL. `FLOAT` without precision is spelled
+    /// as `DOUBLE PRECISION`, which is the same type, while `FLOAT(n)` is kept as written rather
+    /// than folded into `REAL` or `DOUBLE PRECISION`, so that the DDL shows what the user wrote.
+    pub fn canonical(&self) -> DataType {
+        match self {
+            DataType::Float(None) => DataType::Double,
+            DataType::Array(ty) => DataType::Array(Box::new(ty.canonical())),
+            DataType::Range(ty) => DataType::Range(Box::new(ty.canonical())),
+            DataType::Struct(fields) => DataType::Struct(
+                fields
+                    .iter()
+                    .map(|field| StructField {
+                        name: field.name.clone(),
+                        data_type: field.data_type.canonical(),
+                    })
+                    .collect(),
+            ),
+            _ => self.clone(),
+        }
+    }
+
+    /// Returns whether values of `self` and `other` can be ordered against each other without an
+    /// explicit cast, e.g. by `a < b` in a predicate. Numbers of any type are comparable with each
+    /// other, while a number and a string are not. Strings, binary strings and points in time, i.e.
+    /// dates and timestamps, are comparable within their kind, and other types only with the same
+    /// type. Arrays and structs are comparable if their elements are. A `NULL` is comparable with
+    /// anything, and `VOID` with nothing.
+    pub fn is_comparable_with(&self, other: &DataType) -> bool {
+        #[derive(PartialEq)]
+        enum Kind {
+            Numeric,
+            String,
+            Binary,
+            PointInTime,
+        }
+
+        fn kind(data_type: &DataType) -> Option<Kind> {
+            let kind = match data_type {
+                DataType::TinyInt(_)
+                | DataType::SmallInt(_)
+                | DataType::Int(_)
+                | DataType::BigInt(_)
+                | DataType::UnsignedTinyInt(_)
+                | DataType::UnsignedSmallInt(_)
+                | DataType::UnsignedInt(_)
+                | DataType::UnsignedBigInt(_)
+                | DataType::Decimal(..)
+                | DataType::Real
+                | DataType::Double
+                | DataType::Float(_) => Kind::Numeric,
+                DataType::Char(..)
+                | DataType::Varchar(..)
+                | DataType::Clob(_)
+                | DataType::Name
+                | DataType::InternalChar
+                | DataType::Text
+                | DataType::String => Kind::String,
+                DataType::Binary(_)
+                | DataType::Varbinary(_)
+                | DataType::Blob(_)
+                | DataType::Bytea => Kind::Binary,
+                DataType::Date | DataType::Timestamp(..) => Kind::PointInTime,
+                _ => return None,
+            };
+            Some(kind)
+        }
+
+        match (self, other) {
+            (DataType::Void, _) | (_, DataType::Void) => false,
+            (DataType::Unknown, _) | (_, DataType::Unknown) => true,
+            (DataType::Array(a), DataType::Array(b)) => a.is_comparable_with(b),
+            (DataType::Struct(a), DataType::Struct(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b)
+                        .all(|(a, b)| a.data_type.is_comparable_with(&b.data_type))
+            }
+            (DataType::Time(..), DataType::Time(..)) => true,
+            (DataType::Interval(..), DataType::Interval(..)) => true,
+            _ => match (kind(self), kind(other)) {
+                (Some(a), Some(b)) => a == b,
+                _ => self.same_type(other),
+            },
+        }
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl DataType {
+    /// Returns the Arrow type for exporting the columns of this type to Arrow consumers. Types
+    /// without a lossless Arrow counterpart, e.g. `NUMERIC` without precision, are rejected.
+    /// Arrays and structs are converted recursively, with all of their fields nullable.
+    pub fn to_arrow_type(&self) -> Result<arrow::datatypes::DataType> {
+        use std::sync::Arc;
+
+        use arrow::datatypes::{
+            DataType as ArrowDataType, Field, IntervalUnit, TimeUnit, DECIMAL128_MAX_PRECISION,
+            DECIMAL256_MAX_PRECISION,
+        };
+
+        let data_type = match self {
+            DataType::Boolean => ArrowDataType::Boolean,
+            DataType::TinyInt(_) => ArrowDataType::Int8,
+            DataType::SmallInt(_) => ArrowDataType::Int16,
+            DataType::Int(_) => ArrowDataType::Int32,
+            DataType::BigInt(_) => ArrowDataType::Int64,
+            DataType::UnsignedTinyInt(_) => ArrowDataType::UInt8,
+            DataType::UnsignedSmallInt(_) => ArrowDataType::UInt16,
+            DataType::UnsignedInt(_) => ArrowDataType::UInt32,
+            DataType::UnsignedBigInt(_) => ArrowDataType::UInt64,
+            DataType::Real | DataType::Float(Some(1..=24)) => ArrowDataType::Float32,
+            DataType::Double | DataType::Float(Some(25..=53) | None) => ArrowDataType::Float64,
+            DataType::Decimal(Some(precision), scale)
+                if *precision <= DECIMAL128_MAX_PRECISION as u64 =>
+            {
+                ArrowDataType::Decimal128(*precision as u8, scale.unwrap_or(0) as i8)
+            }
+            DataType::Decimal(Some(precision), scale)
+                if *precision <= DECIMAL256_MAX_PRECISION as u64 =>
+            {
+                ArrowDataType::Decimal256(*precision as u8, scale.unwrap_or(0) as i8)
+            }
+            DataType::Char(..)
+            | DataType::Varchar(..)
+            | DataType::Name
+            | DataType::InternalChar
+            | DataType::Text
+            | DataType::String => ArrowDataType::Utf8,
+            DataType::Uuid => ArrowDataType::FixedSizeBinary(16),
+            DataType::Binary(size) => ArrowDataType::FixedSizeBinary(*size as i32),
+            DataType::Varbinary(_) | DataType::Blob(_) | DataType::Bytea => ArrowDataType::Binary,
+            DataType::Date => ArrowDataType::Date32,
+            DataType::Time(_, false) => ArrowDataType::Time64(TimeUnit::Microsecond),
+            DataType::Timestamp(_, false) => ArrowDataType::Timestamp(TimeUnit::Microsecond, None),
+            DataType::Timestamp(_, true) => {
+                ArrowDataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into()))
+            }
+            DataType::Interval(..) => ArrowDataType::Interval(IntervalUnit::MonthDayNano),
+            DataType::Array(datatype) => ArrowDataType::List(Arc::new(Field::new(
+                "item",
+                datatype.to_arrow_type()?,
+                true,
+            ))),
+            DataType::Struct(fields) => ArrowDataType::Struct(
+                fields
+                    .iter()
+                    .map(|field| {
+                        Ok(Field::new(
+                            &field.name.value,
+                            field.data_type.to_arrow_type()?,
+                            true,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?
+                    .into(),
+            ),
+            _ => {
+                return Err(ErrorCode::NotImplemented(
+                    format!("cannot convert data type {} to Arrow type", self),
+                    None.into(),
+                )
+                .into())
+            }
+        };
+        Ok(data_type)
+    }
+}
+
+/// Physical representation of the values of a data type, see `DataType::same_physical_layout`.
+#[derive(Debug, PartialEq, Eq)]
+enum PhysicalLayout {
+    Bool,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+    Decimal,
+    Interval,
+    Utf8,
+    Bytes,
+    List(Box<PhysicalLayout>),
+}
+
+impl DataType {
+    /// Returns whether values of `a` and `b` are stored identically, so that one can be
+    /// reinterpreted as the other without conversion, e.g. `TIMESTAMP` and `BIGINT` are both
+    /// 64-bit integers (microseconds since epoch for the former), and `DATE` and `INT` are both
+    /// 32-bit integers (days since epoch for the former). Types whose layout is unknown don't
+    /// share the layout with any type.
+    pub fn same_physical_layout(a: &DataType, b: &DataType) -> bool {
+        match (a.physical_layout(), b.physical_layout()) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Returns whether values of the type take a fixed number of bytes, so that they can be
+    /// packed tightly in columnar formats instead of being addressed by offsets.
+    pub fn is_fixed_width(&self) -> bool {
+        matches!(
+            self.physical_layout(),
+            Some(
+                PhysicalLayout::Bool
+                    | PhysicalLayout::Int8
+                    | PhysicalLayout::Int16
+                    | PhysicalLayout::Int32
+                    | PhysicalLayout::Int64
+                    | PhysicalLayout::Float32
+                    | PhysicalLayout::Float64
+                    | PhysicalLayout::Decimal
+                    | PhysicalLayout::Interval
+            )
+        )
+    }
+
+    fn physical_layout(&self) -> Option<PhysicalLayout> {
+        let layout = match self {
+            DataType::Boolean => PhysicalLayout::Bool,
+            DataType::TinyInt(_) => PhysicalLayout::Int8,
+            DataType::SmallInt(_) => PhysicalLayout::Int16,
+            DataType::Int(_) | DataType::Date => PhysicalLayout::Int32,
+            DataType::BigInt(_) | DataType::Time(..) | DataType::Timestamp(..) => {
+                PhysicalLayout::Int64
+            }
+            // Unsigned integers are stored as the wider types they're converted to.
+            DataType::UnsignedTinyInt(_) => PhysicalLayout::Int16,
+            DataType::UnsignedSmallInt(_) => PhysicalLayout::Int32,
+            DataType::UnsignedInt(_) => PhysicalLayout::Int64,
+            DataType::UnsignedBigInt(_) => PhysicalLayout::Decimal,
+            DataType::Real | DataType::Float(Some(1..=24)) => PhysicalLayout::Float32,
+            DataType::Double | DataType::Float(Some(25..=53) | None) => PhysicalLayout::Float64,
+            DataType::Decimal(..) | DataType::Money(_) => PhysicalLayout::Decimal,
+            DataType::Interval(..) => PhysicalLayout::Interval,
+            DataType::Char(..)
+            | DataType::Varchar(..)
+            | DataType::Name
+            | DataType::InternalChar
+            | DataType::Text
+            | DataType::String => PhysicalLayout::Utf8,
+            DataType::Binary(_) | DataType::Varbinary(_) | DataType::Blob(_) | DataType::Bytea => {
+                PhysicalLayout::Bytes
+            }
+            DataType::Array(datatype) => {
+                PhysicalLayout::List(Box::new(datatype.physical_layout()?))
+            }
+            _ => return None,
+        };
+        Some(layout)
+    }
+}
+
+/// Converts a type of execution back to the canonical SQL type that `DataType::to_data_type`
+/// converts to it, e.g. to display the type of a stored column. The names of struct fields are not
+/// kept in execution, so they are named `f1`, `f2`, ... by their positions like the columns of an
+/// anonymous record in PostgreSQL.
+impl From<&Common_Data_Type> for DataType {
+    fn from(data_type: &Common_Data_Type) -> Self {
+        match data_type {
+            Common_Data_Type::Boolean => DataType::Boolean,
+            Common_Data_Type::Int16 => DataType::SmallInt(None),
+            Common_Data_Type::Int32 => DataType::Int(None),
+            Common_Data_Type::Int64 => DataType::BigInt(None),
+            Common_Data_Type::Float32 => DataType::Real,
+            Common_Data_Type::Float64 => DataType::Double,
+            Common_Data_Type::Decimal => DataType::Decimal(None, None),
+            Common_Data_Type::Date => DataType::Date,
+            Common_Data_Type::Varchar => DataType::Varchar(None, None),
+            Common_Data_Type::Time => DataType::Time(None, false),
+            Common_Data_Type::Timestamp => DataType::Timestamp(None, false),
+            Common_Data_Type::Timestampz => DataType::Timestamp(None, true),
+            Common_Data_Type::Interval => DataType::Interval(None, None),
+            Common_Data_Type::Bytea => DataType::Bytea,
+            Common_Data_Type::Struct { fields } => DataType::Struct(
+                fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, field)| StructField {
+                        name: Ident::new(format!("f{}", i + 1)),
+                        data_type: field.into(),
+                    })
+                    .collect(),
+            ),
+            Common_Data_Type::List { datatype } => {
+                DataType::Array(Box::new(datatype.as_ref().into()))
+            }
+        }
+    }
+}
+
+/// Returns whether `a` and `b` name the same object after SQL case folding.
+fn same_object_name(a: &ObjectName, b: &ObjectName) -> bool {
+    a.0.len() == b.0.len()
+        && a.0
+            .iter()
+            .zip(&b.0)
+            .all(|(a, b)| fold_ident(a) == fold_ident(b))
+}
+
+/// Folds an unquoted identifier to lower case as PostgreSQL does. Quoted identifiers are kept
+/// as they are.
+fn fold_ident(ident: &Ident) -> String {
+    match ident.quote_style {
+        None => ident.value.to_lowercase(),
+        Some(_) => ident.value.clone(),
+    }
+}
+
 impl fmt::Display for DataType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            DataType::Char(size) => format_type_with_optional_length(f, "CHAR", size),
-            DataType::Varchar(size) => {
-                format_type_with_optional_length(f, "CHARACTER VARYING", size)
+            DataType::Char(size, charset) => {
+                format_type_with_optional_length(f, "CHAR", size)?;
+                format_optional_character_set(f, charset)
+            }
+            DataType::Varchar(size, charset) => {
+                format_type_with_optional_length(f, "CHARACTER VARYING", size)?;
+                format_optional_character_set(f, charset)
             }
             DataType::Uuid => write!(f, "UUID"),
             DataType::Clob(size) => write!(f, "CLOB({})", size),
@@ -96,6 +1147,13 @@ impl fmt::Display for DataType {
                     format_type_with_optional_length(f, "NUMERIC", precision)
                 }
             }
+            DataType::Money(currency_code) => {
+                write!(f, "MONEY")?;
+                if let Some(currency_code) = currency_code {
+                    write!(f, "('{}')", escape_single_quote_string(currency_code))?;
+                }
+                Ok(())
+            }
             DataType::Float(size) => format_type_with_optional_length(f, "FLOAT", size),
             DataType::TinyInt(zerofill) => format_type_with_optional_length(f, "TINYINT", zerofill),
             DataType::SmallInt(zerofill) => {
@@ -103,25 +1161,131 @@ impl fmt::Display for DataType {
             }
             DataType::Int(zerofill) => format_type_with_optional_length(f, "INT", zerofill),
             DataType::BigInt(zerofill) => format_type_with_optional_length(f, "BIGINT", zerofill),
+            DataType::UnsignedTinyInt(zerofill) => {
+                format_type_with_optional_length(f, "TINYINT", zerofill)?;
+                write!(f, " UNSIGNED")
+            }
+            DataType::UnsignedSmallInt(zerofill) => {
+                format_type_with_optional_length(f, "SMALLINT", zerofill)?;
+                write!(f, " UNSIGNED")
+            }
+            DataType::UnsignedInt(zerofill) => {
+                format_type_with_optional_length(f, "INT", zerofill)?;
+                write!(f, " UNSIGNED")
+            }
+            DataType::UnsignedBigInt(zerofill) => {
+                format_type_with_optional_length(f, "BIGINT", zerofill)?;
+                write!(f, " UNSIGNED")
+            }
             DataType::Real => write!(f, "REAL"),
-            DataType::Double => write!(f, "DOUBLE"),
+            DataType::Double => write!(f, "DOUBLE PRECISION"),
             DataType::Boolean => write!(f, "BOOLEAN"),
             DataType::Date => write!(f, "DATE"),
-            DataType::Time(tz) => write!(f, "TIME{}", if *tz { " WITH TIME ZONE" } else { "" }),
-            DataType::Timestamp(tz) => {
-                write!(f, "TIMESTAMP{}", if *tz { " WITH TIME ZONE" } else { "" })
+            DataType::Time(precision, tz) => {
+                format_type_with_optional_length(f, "TIME", precision)?;
+                write!(f, "{}", if *tz { " WITH TIME ZONE" } else { "" })
+            }
+            DataType::Timestamp(precision, tz) => {
+                format_type_with_optional_length(f, "TIMESTAMP", precision)?;
+                write!(f, "{}", if *tz { " WITH TIME ZONE" } else { "" })
+            }
+            DataType::Interval(fields, precision) => {
+                write!(f, "INTERVAL")?;
+                if let Some(fields) = fields {
+                    write!(f, " {}", fields)?;
+                }
+                format_type_with_optional_length(f, "", precision)
             }
-            DataType::Interval => write!(f, "INTERVAL"),
             DataType::Regclass => write!(f, "REGCLASS"),
+            DataType::Name => write!(f, "NAME"),
+            DataType::InternalChar => write!(f, "\"char\""),
             DataType::Text => write!(f, "TEXT"),
             DataType::String => write!(f, "STRING"),
             DataType::Bytea => write!(f, "BYTEA"),
+            DataType::Json => write!(f, "JSON"),
+            DataType::Jsonb => write!(f, "JSONB"),
+            DataType::Range(ty) => write!(f, "RANGE<{}>", ty),
+            DataType::Struct(fields) => write!(f, "STRUCT<{}>", display_comma_separated(fields)),
             DataType::Array(ty) => write!(f, "{}[]", ty),
             DataType::Custom(ty) => write!(f, "{}", ty),
+            DataType::Void => write!(f, "VOID"),
+            DataType::Unknown => write!(f, "UNKNOWN"),
         }
     }
 }
 
+/// Checks the precision and scale of `DECIMAL(p, s)` like PostgreSQL, i.e. `p` in
+/// `1..=MAX_DECIMAL_PRECISION` and `s` in `0..=p`.
+fn check_decimal_precision_scale(precision: u64, scale: Option<u64>) -> Result<()> {
+    if !(1..=MAX_DECIMAL_PRECISION).contains(&precision) {
+        return Err(ErrorCode::InvalidInputSyntax(format!(
+            "NUMERIC precision {} must be between 1 and {}",
+            precision, MAX_DECIMAL_PRECISION
+        ))
+        .into());
+    }
+    match scale {
+        Some(scale) if scale > precision => Err(ErrorCode::InvalidInputSyntax(format!(
+            "NUMERIC scale {} must be between 0 and precision {}",
+            scale, precision
+        ))
+        .into()),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that the length of `CHAR(n)` or `VARCHAR(n)` is at least 1 like PostgreSQL, since a
+/// column of zero-length strings can't hold anything but the empty string.
+fn check_char_length(type_name: &str, length: Option<u64>) -> Result<()> {
+    if length == Some(0) {
+        return Err(ErrorCode::InvalidInputSyntax(format!(
+            "length for type {} must be at least 1",
+            type_name
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// Checks that `currency_code` looks like an ISO 4217 code, i.e. three upper case letters.
+fn check_currency_code(currency_code: &str) -> Result<()> {
+    if currency_code.len() != 3 || !currency_code.bytes().all(|b| b.is_ascii_uppercase()) {
+        return Err(ErrorCode::InvalidInputSyntax(format!(
+            "invalid currency code \"{}\", expected an ISO 4217 code like 'USD'",
+            currency_code
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+fn default_scalar_of(data_type: &Common_Data_Type) -> ScalarImpl {
+    match data_type {
+        Common_Data_Type::Boolean => ScalarImpl::Bool(false),
+        Common_Data_Type::Int16 => ScalarImpl::Int16(0),
+        Common_Data_Type::Int32 => ScalarImpl::Int32(0),
+        Common_Data_Type::Int64 => ScalarImpl::Int64(0),
+        Common_Data_Type::Float32 => ScalarImpl::Float32(OrderedF32::from(0.0)),
+        Common_Data_Type::Float64 => ScalarImpl::Float64(OrderedF64::from(0.0)),
+        Common_Data_Type::Decimal => ScalarImpl::Decimal(Decimal::default()),
+        Common_Data_Type::Date => ScalarImpl::NaiveDate(NaiveDateWrapper::default()),
+        Common_Data_Type::Varchar => ScalarImpl::Utf8(String::new()),
+        Common_Data_Type::Time => ScalarImpl::NaiveTime(NaiveTimeWrapper::default()),
+        Common_Data_Type::Timestamp => ScalarImpl::NaiveDateTime(NaiveDateTimeWrapper::default()),
+        // `TIMESTAMP WITH TIME ZONE` is stored as microseconds since the epoch.
+        Common_Data_Type::Timestampz => ScalarImpl::Int64(0),
+        Common_Data_Type::Interval => ScalarImpl::Interval(IntervalUnit::default()),
+        Common_Data_Type::Bytea => ScalarImpl::Bytea(Box::new([])),
+        Common_Data_Type::Struct { fields } => ScalarImpl::Struct(StructValue::new(
+            fields
+                .iter()
+                .map(|field| Some(default_scalar_of(field)))
+                .collect(),
+        )),
+        Common_Data_Type::List { .. } => ScalarImpl::List(ListValue::new(vec![])),
+    }
+}
+
 fn format_type_with_optional_length(
     f: &mut fmt::Formatter,
     sql_type: &'static str,
@@ -133,3 +1297,1563 @@ fn format_type_with_optional_length(
     }
     Ok(())
 }
+
+fn format_optional_character_set(
+    f: &mut fmt::Formatter,
+    charset: &Option<CharacterSet>,
+) -> fmt::Result {
+    if let Some(charset) = charset {
+        write!(f, " {}", charset)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use crate::tokenizer::Tokenizer;
+
+    fn custom(idents: Vec<Ident>) -> DataType {
+        DataType::Custom(ObjectName(idents))
+    }
+
+    #[test]
+    fn test_unsigned_int() {
+        let cases = [
+            (DataType::Int(None), "INT", Common_Data_Type::Int32),
+            (
+                DataType::UnsignedInt(None),
+                "INT UNSIGNED",
+                Common_Data_Type::Int64,
+            ),
+            (DataType::BigInt(None), "BIGINT", Common_Data_Type::Int64),
+            (
+                DataType::UnsignedBigInt(None),
+                "BIGINT UNSIGNED",
+                Common_Data_Type::Decimal,
+            ),
+            (
+                DataType::UnsignedSmallInt(None),
+                "SMALLINT UNSIGNED",
+                Common_Data_Type::Int32,
+            ),
+            (
+                DataType::UnsignedTinyInt(None),
+                "TINYINT UNSIGNED",
+                Common_Data_Type::Int16,
+            ),
+        ];
+        for (data_type, display, expected) in cases {
+            assert_eq!(data_type.to_string(), display);
+            assert_eq!(data_type.to_data_type().unwrap(), expected, "{}", data_type);
+        }
+        assert_eq!(
+            DataType::UnsignedInt(Some(10)).to_string(),
+            "INT(10) UNSIGNED"
+        );
+        assert!(!DataType::UnsignedInt(None).same_type(&DataType::Int(None)));
+        assert!(DataType::same_physical_layout(
+            &DataType::UnsignedInt(None),
+            &DataType::BigInt(None)
+        ));
+    }
+
+    #[test]
+    fn test_partition_bucket_type() {
+        for data_type in [
+            DataType::Date,
+            DataType::Timestamp(None, false),
+            DataType::Timestamp(Some(3), true),
+        ] {
+            assert_eq!(
+                data_type.partition_bucket_type(),
+                Some(DataType::BigInt(None)),
+                "{}",
+                data_type
+            );
+        }
+        for data_type in [
+            DataType::Time(None, false),
+            DataType::Interval(None, None),
+            DataType::BigInt(None),
+            DataType::Varchar(None, None),
+            DataType::Array(Box::new(DataType::Date)),
+        ] {
+            assert_eq!(data_type.partition_bucket_type(), None, "{}", data_type);
+        }
+    }
+
+    #[test]
+    fn test_can_widen_to() {
+        let widenings = [
+            (DataType::SmallInt(None), DataType::Int(None)),
+            (DataType::Int(None), DataType::BigInt(None)),
+            (DataType::SmallInt(None), DataType::BigInt(None)),
+            (DataType::Real, DataType::Double),
+            (DataType::Float(Some(10)), DataType::Float(Some(40))),
+            (DataType::Real, DataType::Float(None)),
+            (
+                DataType::Varchar(Some(10), None),
+                DataType::Varchar(Some(20), None),
+            ),
+            (
+                DataType::Varchar(Some(10), None),
+                DataType::Varchar(None, None),
+            ),
+            (DataType::Int(None), DataType::Int(None)),
+            (DataType::Float(Some(30)), DataType::Double),
+        ];
+        for (from, to) in widenings {
+            assert!(from.can_widen_to(&to), "{} to {}", from, to);
+        }
+
+        let utf8 = CharacterSet {
+            name: ObjectName(vec![Ident::new("utf8")]),
+            collation: None,
+        };
+        let rejected = [
+            (DataType::BigInt(None), DataType::Int(None)),
+            (DataType::Int(None), DataType::SmallInt(None)),
+            (DataType::Double, DataType::Real),
+            (
+                DataType::Varchar(Some(20), None),
+                DataType::Varchar(Some(10), None),
+            ),
+            (
+                DataType::Varchar(None, None),
+                DataType::Varchar(Some(10), None),
+            ),
+            (
+                DataType::Varchar(Some(10), None),
+                DataType::Varchar(Some(20), Some(utf8)),
+            ),
+            // Integers can't be represented exactly by floats of the same width.
+            (DataType::Int(None), DataType::Real),
+            (DataType::BigInt(None), DataType::Double),
+            (DataType::Int(None), DataType::Varchar(None, None)),
+        ];
+        for (from, to) in rejected {
+            assert!(!from.can_widen_to(&to), "{} to {}", from, to);
+        }
+    }
+
+    #[test]
+    fn test_array_of_struct() {
+        let field = |name: &str, data_type| StructField {
+            name: Ident::new(name),
+            data_type,
+        };
+        let int_varchar = Common_Data_Type::Struct {
+            fields: vec![Common_Data_Type::Int32, Common_Data_Type::Varchar].into(),
+        };
+        // `STRUCT<a INT, b VARCHAR>[]` is a list of structs.
+        let array_of_struct = DataType::Array(Box::new(DataType::Struct(vec![
+            field("a", DataType::Int(None)),
+            field("b", DataType::Varchar(None, None)),
+        ])));
+        assert_eq!(
+            array_of_struct.to_data_type().unwrap(),
+            Common_Data_Type::List {
+                datatype: Box::new(int_varchar.clone()),
+            }
+        );
+        // While `STRUCT<a INT[], b VARCHAR>` is a struct with a list field.
+        let struct_of_array = DataType::Struct(vec![
+            field("a", DataType::Array(Box::new(DataType::Int(None)))),
+            field("b", DataType::Varchar(None, None)),
+        ]);
+        assert_eq!(
+            struct_of_array.to_data_type().unwrap(),
+            Common_Data_Type::Struct {
+                fields: vec![
+                    Common_Data_Type::List {
+                        datatype: Box::new(Common_Data_Type::Int32),
+                    },
+                    Common_Data_Type::Varchar,
+                ]
+                .into(),
+            }
+        );
+        assert_ne!(
+            array_of_struct.to_data_type().unwrap(),
+            struct_of_array.to_data_type().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_struct_field() {
+        let field = |name: &str, data_type| StructField {
+            name: Ident::new(name),
+            data_type,
+        };
+        let point = DataType::Struct(vec![
+            field("x", DataType::Int(None)),
+            field("y", DataType::Int(None)),
+        ]);
+        let data_type = DataType::Struct(vec![
+            field("Name", DataType::Varchar(None, None)),
+            field("location", point.clone()),
+        ]);
+        assert_eq!(
+            data_type.to_string(),
+            "STRUCT<Name CHARACTER VARYING, location STRUCT<x INT, y INT>>"
+        );
+
+        assert_eq!(data_type.struct_field_index("name"), Some(0));
+        assert_eq!(
+            data_type.struct_field_type("name"),
+            Some(&DataType::Varchar(None, None))
+        );
+        assert_eq!(data_type.struct_field_index("location"), Some(1));
+        assert_eq!(data_type.struct_field_type("location"), Some(&point));
+        assert_eq!(
+            data_type
+                .struct_field_type("location")
+                .and_then(|location| location.struct_field_type("y")),
+            Some(&DataType::Int(None))
+        );
+
+        // Unquoted field names are folded to lower case.
+        assert_eq!(data_type.struct_field_index("Name"), None);
+        assert_eq!(data_type.struct_field_type("z"), None);
+        assert_eq!(DataType::Int(None).struct_field_type("x"), None);
+
+        assert_eq!(
+            data_type.to_data_type().unwrap(),
+            Common_Data_Type::Struct {
+                fields: vec![
+                    Common_Data_Type::Varchar,
+                    Common_Data_Type::Struct {
+                        fields: vec![Common_Data_Type::Int32, Common_Data_Type::Int32].into(),
+                    },
+                ]
+                .into(),
+            }
+        );
+
+        let empty = DataType::Struct(vec![]);
+        assert_eq!(empty.to_string(), "STRUCT<>");
+        assert_eq!(
+            empty.to_data_type().unwrap(),
+            Common_Data_Type::Struct {
+                fields: vec![].into()
+            }
+        );
+
+        let tagged = DataType::Struct(vec![field(
+            "tags",
+            DataType::Array(Box::new(DataType::Varchar(None, None))),
+        )]);
+        assert_eq!(tagged.to_string(), "STRUCT<tags CHARACTER VARYING[]>");
+        assert_eq!(
+            tagged.to_data_type().unwrap(),
+            Common_Data_Type::Struct {
+                fields: vec![Common_Data_Type::List {
+                    datatype: Box::new(Common_Data_Type::Varchar),
+                }]
+                .into(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_to_data_type_checked() {
+        let (data_type, warnings) = DataType::TinyInt(None).to_data_type_checked().unwrap();
+        assert_eq!(data_type, Common_Data_Type::Int16);
+        assert_eq!(warnings, vec!["TINYINT is widened to SMALLINT".to_string()]);
+
+        let (data_type, warnings) = DataType::Decimal(None, None)
+            .to_data_type_checked()
+            .unwrap();
+        assert_eq!(data_type, Common_Data_Type::Decimal);
+        assert_eq!(warnings.len(), 1);
+
+        let (data_type, warnings) = DataType::Int(None).to_data_type_checked().unwrap();
+        assert_eq!(data_type, Common_Data_Type::Int32);
+        assert!(warnings.is_empty());
+
+        // Warnings of nested types are collected as well.
+        let (_, warnings) = DataType::Array(Box::new(DataType::Varchar(Some(10), None)))
+            .to_data_type_checked()
+            .unwrap();
+        assert_eq!(
+            warnings,
+            vec!["length 10 of CHARACTER VARYING is not enforced".to_string()]
+        );
+
+        // The plain conversion accepts the same types without warnings.
+        assert_eq!(
+            DataType::TinyInt(None).to_data_type().unwrap(),
+            Common_Data_Type::Int16
+        );
+        assert!(DataType::Clob(10).to_data_type_checked().is_err());
+    }
+
+    #[test]
+    fn test_struct_duplicate_field_names() {
+        let field = |name: &str| StructField {
+            name: Ident::new(name),
+            data_type: DataType::Int(None),
+        };
+        let err = DataType::Struct(vec![field("x"), field("y"), field("x")])
+            .to_data_type()
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("duplicate field name \"x\""),
+            "{}",
+            err
+        );
+        // Unquoted names are compared after case folding.
+        assert!(DataType::Struct(vec![field("x"), field("X")])
+            .to_data_type()
+            .is_err());
+
+        assert_eq!(
+            DataType::Struct(vec![field("x"), field("y")])
+                .to_data_type()
+                .unwrap(),
+            Common_Data_Type::Struct {
+                fields: vec![Common_Data_Type::Int32, Common_Data_Type::Int32].into(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_nesting_depth() {
+        assert_eq!(DataType::Int(None).nesting_depth(), 0);
+        let int_array = DataType::Array(Box::new(DataType::Int(None)));
+        assert_eq!(int_array.nesting_depth(), 1);
+        let data_type = DataType::Struct(vec![
+            StructField {
+                name: Ident::new("a"),
+                data_type: DataType::Int(None),
+            },
+            StructField {
+                name: Ident::new("b"),
+                data_type: DataType::Range(Box::new(int_array)),
+            },
+        ]);
+        assert_eq!(data_type.nesting_depth(), 3);
+
+        let nest = |depth| {
+            (0..depth).fold(DataType::Int(None), |data_type, _| {
+                DataType::Array(Box::new(data_type))
+            })
+        };
+        assert!(nest(DEFAULT_MAX_NESTING_DEPTH).to_data_type().is_ok());
+        assert!(nest(DEFAULT_MAX_NESTING_DEPTH + 1).to_data_type().is_err());
+        assert_eq!(
+            nest(2).to_data_type().unwrap(),
+            Common_Data_Type::List {
+                datatype: Box::new(Common_Data_Type::List {
+                    datatype: Box::new(Common_Data_Type::Int32),
+                }),
+            }
+        );
+        let too_deep = nest(100_000);
+        assert_eq!(too_deep.nesting_depth(), 100_000);
+        assert_eq!(
+            too_deep.to_data_type().unwrap_err().to_string(),
+            format!(
+                "Bind error: type nested 100000 levels deep exceeds the limit of {}",
+                DEFAULT_MAX_NESTING_DEPTH
+            )
+        );
+
+        let config = DataTypeConversionConfig {
+            max_nesting_depth: 2,
+            ..Default::default()
+        };
+        assert!(nest(2).to_data_type_with_hint(&config).is_ok());
+        assert!(nest(3).to_data_type_with_hint(&config).is_err());
+        // Dropping the deep type recursively may overflow the stack as well.
+        core::mem::forget(too_deep);
+    }
+
+    #[test]
+    fn test_infer_array_type() {
+        assert_eq!(
+            DataType::infer_array_type(&[DataType::Int(None), DataType::Int(None)]).unwrap(),
+            DataType::Array(Box::new(DataType::Int(None)))
+        );
+        // ARRAY[1, 2.0]
+        assert_eq!(
+            DataType::infer_array_type(&[DataType::Int(None), DataType::Decimal(None, None)])
+                .unwrap(),
+            DataType::Array(Box::new(DataType::Decimal(None, None)))
+        );
+        assert_eq!(
+            DataType::infer_array_type(&[
+                DataType::SmallInt(None),
+                DataType::BigInt(None),
+                DataType::Double,
+            ])
+            .unwrap(),
+            DataType::Array(Box::new(DataType::Double))
+        );
+        assert_eq!(
+            DataType::infer_array_type(&[
+                DataType::Array(Box::new(DataType::Int(None))),
+                DataType::Array(Box::new(DataType::Int(None))),
+            ])
+            .unwrap(),
+            DataType::Array(Box::new(DataType::Array(Box::new(DataType::Int(None)))))
+        );
+
+        assert!(
+            DataType::infer_array_type(&[DataType::Int(None), DataType::Varchar(None, None)])
+                .is_err()
+        );
+        assert!(DataType::infer_array_type(&[DataType::Boolean, DataType::Date]).is_err());
+        assert!(DataType::infer_array_type(&[]).is_err());
+    }
+
+    #[test]
+    fn test_canonical_float() {
+        let cases = [
+            (DataType::Float(Some(24)), "FLOAT(24)"),
+            (DataType::Float(Some(53)), "FLOAT(53)"),
+            (DataType::Float(None), "DOUBLE PRECISION"),
+            (DataType::Real, "REAL"),
+            (DataType::Double, "DOUBLE PRECISION"),
+            (
+                DataType::Array(Box::new(DataType::Float(Some(10)))),
+                "FLOAT(10)[]",
+            ),
+        ];
+        for (data_type, expected) in cases {
+            assert_eq!(data_type.canonical().to_string(), expected);
+        }
+        // The spelling is kept although `FLOAT(24)` and `REAL` are the same type.
+        assert_eq!(
+            DataType::Float(Some(24)).to_data_type().unwrap(),
+            DataType::Real.to_data_type().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_default_row() {
+        let types = [
+            DataType::Int(None),
+            DataType::Varchar(Some(10), None),
+            DataType::Boolean,
+            DataType::Double,
+            DataType::Timestamp(None, true),
+            DataType::Array(Box::new(DataType::Int(None))),
+            DataType::Struct(vec![StructField {
+                name: Ident::new("a"),
+                data_type: DataType::SmallInt(None),
+            }]),
+        ];
+        assert_eq!(
+            DataType::default_row(&types).unwrap(),
+            vec![
+                ScalarImpl::Int32(0),
+                ScalarImpl::Utf8(String::new()),
+                ScalarImpl::Bool(false),
+                ScalarImpl::Float64(OrderedF64::from(0.0)),
+                ScalarImpl::Int64(0),
+                ScalarImpl::List(ListValue::new(vec![])),
+                ScalarImpl::Struct(StructValue::new(vec![Some(ScalarImpl::Int16(0))])),
+            ]
+        );
+        assert!(DataType::default_row(&[]).unwrap().is_empty());
+        assert!(DataType::default_row(&[DataType::Int(None), DataType::Unknown]).is_err());
+    }
+
+    #[test]
+    fn test_array_element_assignable() {
+        let array = |data_type| DataType::Array(Box::new(data_type));
+        let assignable = [
+            (array(DataType::Int(None)), DataType::Int(None)),
+            (
+                array(DataType::Varchar(None, None)),
+                DataType::Varchar(None, None),
+            ),
+            (array(DataType::BigInt(None)), DataType::SmallInt(None)),
+            (array(DataType::Double), DataType::Int(None)),
+            (
+                array(DataType::Decimal(Some(10), Some(2))),
+                DataType::Int(None),
+            ),
+            (
+                array(DataType::Varchar(Some(10), None)),
+                DataType::Varchar(None, None),
+            ),
+            (array(DataType::Text), DataType::Char(Some(1), None)),
+            (array(DataType::Boolean), DataType::Unknown),
+            (
+                array(array(DataType::BigInt(None))),
+                array(DataType::Int(None)),
+            ),
+        ];
+        for (column, element) in assignable {
+            assert!(
+                column.array_element_assignable(&element),
+                "{} into {}",
+                element,
+                column
+            );
+        }
+
+        let unassignable = [
+            (array(DataType::Int(None)), DataType::Varchar(None, None)),
+            (array(DataType::Int(None)), DataType::BigInt(None)),
+            (array(DataType::Int(None)), DataType::Double),
+            (array(DataType::Real), DataType::Double),
+            (array(DataType::Varchar(None, None)), DataType::Int(None)),
+            (array(DataType::Int(None)), array(DataType::Int(None))),
+            (DataType::Int(None), DataType::Int(None)),
+        ];
+        for (column, element) in unassignable {
+            assert!(
+                !column.array_element_assignable(&element),
+                "{} into {}",
+                element,
+                column
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_comparable_with() {
+        let comparable = [
+            (DataType::Int(None), DataType::BigInt(None)),
+            (
+                DataType::SmallInt(None),
+                DataType::Decimal(Some(10), Some(2)),
+            ),
+            (DataType::Int(None), DataType::Double),
+            (DataType::Varchar(Some(10), None), DataType::Text),
+            (DataType::Date, DataType::Timestamp(None, true)),
+            (DataType::Time(None, false), DataType::Time(Some(3), true)),
+            (DataType::Bytea, DataType::Varbinary(16)),
+            (DataType::Unknown, DataType::Boolean),
+            (
+                DataType::Array(Box::new(DataType::Int(None))),
+                DataType::Array(Box::new(DataType::Real)),
+            ),
+        ];
+        for (a, b) in comparable {
+            assert!(a.is_comparable_with(&b), "{} and {}", a, b);
+            assert!(b.is_comparable_with(&a), "{} and {}", b, a);
+        }
+
+        let incomparable = [
+            (DataType::Int(None), DataType::Varchar(None, None)),
+            (DataType::Double, DataType::Text),
+            (DataType::Date, DataType::Time(None, false)),
+            (DataType::Boolean, DataType::Int(None)),
+            (
+                DataType::Interval(None, None),
+                DataType::Timestamp(None, false),
+            ),
+            (DataType::Text, DataType::Bytea),
+            (
+                DataType::Array(Box::new(DataType::Int(None))),
+                DataType::Array(Box::new(DataType::Text)),
+            ),
+            (DataType::Void, DataType::Void),
+        ];
+        for (a, b) in incomparable {
+            assert!(!a.is_comparable_with(&b), "{} and {}", a, b);
+            assert!(!b.is_comparable_with(&a), "{} and {}", b, a);
+        }
+
+        // Every type but VOID is comparable with itself.
+        for data_type in [
+            DataType::Boolean,
+            DataType::Int(None),
+            DataType::Text,
+            DataType::Uuid,
+            DataType::Date,
+            DataType::Interval(None, None),
+            DataType::Regclass,
+            DataType::Custom(ObjectName(vec![Ident::new("mood")])),
+        ] {
+            assert!(data_type.is_comparable_with(&data_type), "{}", data_type);
+        }
+    }
+
+    #[test]
+    fn test_infer_array_type_with_null() {
+        // ARRAY[NULL, 1]
+        assert_eq!(
+            DataType::infer_array_type(&[DataType::Unknown, DataType::Int(None)]).unwrap(),
+            DataType::Array(Box::new(DataType::Int(None)))
+        );
+        assert_eq!(
+            DataType::infer_array_type(&[DataType::Int(None), DataType::Unknown, DataType::Double])
+                .unwrap(),
+            DataType::Array(Box::new(DataType::Double))
+        );
+        // ARRAY[NULL, NULL]
+        assert_eq!(
+            DataType::infer_array_type(&[DataType::Unknown, DataType::Unknown]).unwrap(),
+            DataType::Array(Box::new(DataType::Unknown))
+        );
+        assert!(DataType::Unknown.to_data_type().is_err());
+    }
+
+    #[test]
+    fn test_array_of_scalars() {
+        let scalars = [
+            (DataType::Boolean, Common_Data_Type::Boolean),
+            (DataType::SmallInt(None), Common_Data_Type::Int16),
+            (DataType::Int(None), Common_Data_Type::Int32),
+            (DataType::BigInt(None), Common_Data_Type::Int64),
+            (DataType::Real, Common_Data_Type::Float32),
+            (DataType::Double, Common_Data_Type::Float64),
+            (DataType::Decimal(None, None), Common_Data_Type::Decimal),
+            (DataType::Varchar(None, None), Common_Data_Type::Varchar),
+            (DataType::Name, Common_Data_Type::Varchar),
+            (DataType::Date, Common_Data_Type::Date),
+            (DataType::Time(None, false), Common_Data_Type::Time),
+            (
+                DataType::Timestamp(None, false),
+                Common_Data_Type::Timestamp,
+            ),
+            (
+                DataType::Timestamp(None, true),
+                Common_Data_Type::Timestampz,
+            ),
+            (DataType::Interval(None, None), Common_Data_Type::Interval),
+        ];
+        for (data_type, expected) in scalars {
+            assert_eq!(data_type.to_data_type().unwrap(), expected);
+            let array = DataType::Array(Box::new(data_type));
+            assert_eq!(
+                array.to_data_type().unwrap(),
+                Common_Data_Type::List {
+                    datatype: Box::new(expected.clone())
+                },
+                "{}",
+                array
+            );
+            assert_eq!(
+                DataType::Array(Box::new(array)).to_data_type().unwrap(),
+                Common_Data_Type::List {
+                    datatype: Box::new(Common_Data_Type::List {
+                        datatype: Box::new(expected)
+                    })
+                }
+            );
+        }
+
+        // The error of an unsupported element is kept.
+        assert!(DataType::Array(Box::new(DataType::Clob(10)))
+            .to_data_type()
+            .is_err());
+    }
+
+    #[test]
+    fn test_scalar_variant() {
+        let cases = [
+            (DataType::Boolean, ScalarVariant::Bool),
+            (DataType::SmallInt(None), ScalarVariant::Int16),
+            (DataType::Int(None), ScalarVariant::Int32),
+            (DataType::BigInt(None), ScalarVariant::Int64),
+            (DataType::Real, ScalarVariant::Float32),
+            (DataType::Double, ScalarVariant::Float64),
+            (DataType::Decimal(None, None), ScalarVariant::Decimal),
+            (DataType::Varchar(None, None), ScalarVariant::Utf8),
+            (DataType::Name, ScalarVariant::Utf8),
+            (DataType::Date, ScalarVariant::NaiveDate),
+            (DataType::Time(None, false), ScalarVariant::NaiveTime),
+            (
+                DataType::Timestamp(None, false),
+                ScalarVariant::NaiveDateTime,
+            ),
+            (DataType::Timestamp(None, true), ScalarVariant::Int64),
+            (DataType::Interval(None, None), ScalarVariant::Interval),
+            (
+                DataType::Range(Box::new(DataType::Int(None))),
+                ScalarVariant::Struct,
+            ),
+            (
+                DataType::Array(Box::new(DataType::Int(None))),
+                ScalarVariant::List,
+            ),
+        ];
+        for (data_type, variant) in cases {
+            assert_eq!(
+                data_type.scalar_variant().unwrap(),
+                variant,
+                "{}",
+                data_type
+            );
+        }
+        assert!(DataType::Clob(10).scalar_variant().is_err());
+    }
+
+    #[test]
+    fn test_interval_fields() {
+        let day_to_second = IntervalFields {
+            leading_field: DateTimeField::Day,
+            leading_precision: None,
+            last_field: Some(DateTimeField::Second),
+        };
+        let data_type = DataType::Interval(Some(day_to_second.clone()), None);
+        assert_eq!(data_type.to_string(), "INTERVAL DAY TO SECOND");
+        assert_eq!(data_type.interval_fields(), Some(day_to_second.clone()));
+        let (common_type, modifier) = data_type.to_data_type_with_modifier().unwrap();
+        assert_eq!(common_type, Common_Data_Type::Interval);
+        assert_eq!(modifier.interval_fields, Some(day_to_second.clone()));
+
+        let data_type = DataType::Interval(None, None);
+        assert_eq!(data_type.to_string(), "INTERVAL");
+        assert_eq!(data_type.interval_fields(), None);
+        let (common_type, modifier) = data_type.to_data_type_with_modifier().unwrap();
+        assert_eq!(common_type, Common_Data_Type::Interval);
+        assert_eq!(modifier, TypeModifier::default());
+
+        assert_eq!(DataType::Int(None).interval_fields(), None);
+
+        let day_to_second = IntervalFields {
+            leading_precision: Some(3),
+            ..day_to_second
+        };
+        let data_type = DataType::Interval(Some(day_to_second.clone()), Some(6));
+        assert_eq!(data_type.to_string(), "INTERVAL DAY(3) TO SECOND(6)");
+        let (_, modifier) = data_type.to_data_type_with_modifier().unwrap();
+        assert_eq!(modifier.precision, Some(6));
+        assert_eq!(modifier.interval_fields, Some(day_to_second));
+        assert_eq!(DataType::Interval(None, Some(6)).to_string(), "INTERVAL(6)");
+    }
+
+    #[test]
+    fn test_to_data_type_for_column() {
+        assert_eq!(
+            DataType::Int(None).to_data_type_for_column("foo").unwrap(),
+            Common_Data_Type::Int32
+        );
+        let err = DataType::Clob(10)
+            .to_data_type_for_column("foo")
+            .unwrap_err();
+        assert!(err.to_string().contains("column \"foo\""));
+        assert!(matches!(err.inner(), ErrorCode::NotImplemented(..)));
+    }
+
+    #[test]
+    fn test_same_type_custom() {
+        let my_enum = custom(vec![Ident::new("MyEnum")]);
+        assert_ne!(my_enum, custom(vec![Ident::new("myenum")]));
+        assert!(my_enum.same_type(&custom(vec![Ident::new("myenum")])));
+        assert!(my_enum.same_type(&custom(vec![Ident::new("MYENUM")])));
+        assert!(!my_enum.same_type(&custom(vec![Ident::new("other_enum")])));
+
+        // Quoted identifiers are case-sensitive.
+        assert!(!my_enum.same_type(&custom(vec![Ident::with_quote('"', "MyEnum")])));
+        assert!(custom(vec![Ident::with_quote('"', "myenum")]).same_type(&my_enum));
+
+        // Qualified names must match part by part.
+        let qualified = custom(vec![Ident::new("Public"), Ident::new("MyEnum")]);
+        assert!(qualified.same_type(&custom(vec![Ident::new("public"), Ident::new("myenum")])));
+        assert!(!qualified.same_type(&my_enum));
+
+        assert!(
+            DataType::Array(Box::new(my_enum.clone())).same_type(&DataType::Array(Box::new(
+                custom(vec![Ident::new("MYENUM")])
+            )))
+        );
+        assert!(!my_enum.same_type(&DataType::Int(None)));
+        assert!(DataType::Int(None).same_type(&DataType::Int(None)));
+    }
+
+    #[test]
+    fn test_character_set() {
+        let utf8 = CharacterSet {
+            name: ObjectName(vec![Ident::new("utf8")]),
+            collation: None,
+        };
+        let varchar = DataType::Varchar(Some(20), Some(utf8.clone()));
+        assert_eq!(
+            varchar.to_string(),
+            "CHARACTER VARYING(20) CHARACTER SET utf8"
+        );
+        assert_eq!(varchar.to_data_type().unwrap(), Common_Data_Type::Varchar);
+
+        let utf8_bin = CharacterSet {
+            collation: Some(ObjectName(vec![Ident::new("utf8_bin")])),
+            ..utf8
+        };
+        let varchar = DataType::Varchar(None, Some(utf8_bin.clone()));
+        assert_eq!(
+            varchar.to_string(),
+            "CHARACTER VARYING CHARACTER SET utf8 COLLATE utf8_bin"
+        );
+        assert_eq!(varchar.to_data_type().unwrap(), Common_Data_Type::Varchar);
+        assert_eq!(
+            DataType::Char(Some(10), Some(utf8_bin)).to_string(),
+            "CHAR(10) CHARACTER SET utf8 COLLATE utf8_bin"
+        );
+        assert_eq!(DataType::Char(Some(10), None).to_string(), "CHAR(10)");
+    }
+
+    #[test]
+    fn test_scaled_int_decimal_hint() {
+        let config = DataTypeConversionConfig {
+            enable_scaled_int_decimal: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            DataType::Decimal(Some(10), Some(2))
+                .to_data_type_with_hint(&config)
+                .unwrap(),
+            (
+                Common_Data_Type::Decimal,
+                Some(StorageHint::ScaledInt64 { scale: 2 })
+            )
+        );
+        assert_eq!(
+            DataType::Decimal(Some(18), None)
+                .to_data_type_with_hint(&config)
+                .unwrap(),
+            (
+                Common_Data_Type::Decimal,
+                Some(StorageHint::ScaledInt64 { scale: 0 })
+            )
+        );
+        assert_eq!(
+            DataType::Decimal(Some(30), Some(2))
+                .to_data_type_with_hint(&config)
+                .unwrap(),
+            (Common_Data_Type::Decimal, None)
+        );
+        assert_eq!(
+            DataType::Decimal(None, None)
+                .to_data_type_with_hint(&config)
+                .unwrap(),
+            (Common_Data_Type::Decimal, None)
+        );
+        assert_eq!(
+            DataType::Int(None).to_data_type_with_hint(&config).unwrap(),
+            (Common_Data_Type::Int32, None)
+        );
+
+        // Invalid precision or scale is rejected either way, even if it would be hinted.
+        for (precision, scale) in [
+            (30, Some(31)),
+            (5, Some(6)),
+            (0, None),
+            (MAX_DECIMAL_PRECISION + 1, None),
+        ] {
+            let data_type = DataType::Decimal(Some(precision), scale);
+            assert!(data_type.to_data_type().is_err());
+            assert!(data_type.to_data_type_with_hint(&config).is_err());
+        }
+
+        // Without the config, the conversion is the same as `to_data_type`.
+        let config = DataTypeConversionConfig::default();
+        assert_eq!(
+            DataType::Decimal(Some(10), Some(2))
+                .to_data_type_with_hint(&config)
+                .unwrap(),
+            (Common_Data_Type::Decimal, None)
+        );
+        assert_eq!(
+            DataType::Decimal(None, None)
+                .to_data_type_with_hint(&config)
+                .unwrap(),
+            (Common_Data_Type::Decimal, None)
+        );
+    }
+
+    #[test]
+    fn test_decimal_precision_scale() {
+        for data_type in [
+            DataType::Decimal(Some(10), Some(2)),
+            DataType::Decimal(Some(10), None),
+            DataType::Decimal(Some(10), Some(10)),
+            DataType::Decimal(Some(MAX_DECIMAL_PRECISION), Some(0)),
+        ] {
+            assert_eq!(
+                data_type.to_data_type().unwrap(),
+                Common_Data_Type::Decimal,
+                "{}",
+                data_type
+            );
+        }
+
+        let err = DataType::Decimal(Some(10), Some(11))
+            .to_data_type()
+            .unwrap_err();
+        assert!(matches!(err.inner(), ErrorCode::InvalidInputSyntax(..)));
+        assert!(err
+            .to_string()
+            .contains("NUMERIC scale 11 must be between 0 and precision 10"));
+
+        let err = DataType::Decimal(Some(39), Some(2))
+            .to_data_type()
+            .unwrap_err();
+        assert!(matches!(err.inner(), ErrorCode::InvalidInputSyntax(..)));
+        assert!(err
+            .to_string()
+            .contains("NUMERIC precision 39 must be between 1 and 38"));
+
+        assert!(DataType::Decimal(Some(0), None).to_data_type().is_err());
+    }
+
+    #[test]
+    fn test_time_precision() {
+        let time = DataType::Time(Some(3), false);
+        assert_eq!(time.to_string(), "TIME(3)");
+        assert_eq!(
+            time.to_data_type_with_modifier().unwrap(),
+            (
+                Common_Data_Type::Time,
+                TypeModifier {
+                    precision: Some(3),
+                    ..Default::default()
+                }
+            )
+        );
+        assert_eq!(DataType::Time(None, false).to_string(), "TIME");
+
+        let timetz = DataType::Time(Some(6), true);
+        assert_eq!(timetz.to_string(), "TIME(6) WITH TIME ZONE");
+        let err = timetz.to_data_type().unwrap_err();
+        assert!(matches!(err.inner(), ErrorCode::NotImplemented(..)));
+        assert!(err.to_string().contains("TIME WITH TIME ZONE"));
+    }
+
+    #[test]
+    fn test_timestamp_precision() {
+        let timestamp = DataType::Timestamp(Some(3), false);
+        assert_eq!(timestamp.to_string(), "TIMESTAMP(3)");
+        assert_eq!(
+            timestamp.to_data_type_with_modifier().unwrap(),
+            (
+                Common_Data_Type::Timestamp,
+                TypeModifier {
+                    precision: Some(3),
+                    ..Default::default()
+                }
+            )
+        );
+
+        let timestamp = DataType::Timestamp(Some(6), true);
+        assert_eq!(timestamp.to_string(), "TIMESTAMP(6) WITH TIME ZONE");
+        assert_eq!(
+            timestamp.to_data_type_with_modifier().unwrap(),
+            (
+                Common_Data_Type::Timestampz,
+                TypeModifier {
+                    precision: Some(6),
+                    ..Default::default()
+                }
+            )
+        );
+
+        let timestamp = DataType::Timestamp(None, false);
+        assert_eq!(timestamp.to_string(), "TIMESTAMP");
+        assert_eq!(
+            timestamp.to_data_type_with_modifier().unwrap(),
+            (Common_Data_Type::Timestamp, TypeModifier::default())
+        );
+    }
+
+    #[test]
+    fn test_nullable_array_elements() {
+        let bool_array = DataType::Array(Box::new(DataType::Boolean));
+        assert_eq!(
+            bool_array.to_data_type_with_modifier().unwrap(),
+            (
+                Common_Data_Type::List {
+                    datatype: Box::new(Common_Data_Type::Boolean),
+                },
+                TypeModifier {
+                    nullable_elements: true,
+                    ..Default::default()
+                }
+            )
+        );
+        let (_, modifier) = DataType::Boolean.to_data_type_with_modifier().unwrap();
+        assert!(!modifier.nullable_elements);
+    }
+
+    #[test]
+    fn test_char_semantics() {
+        assert_eq!(
+            DataType::Char(Some(10), None)
+                .to_data_type_with_modifier()
+                .unwrap(),
+            (
+                Common_Data_Type::Varchar,
+                TypeModifier {
+                    blank_padded_length: Some(10),
+                    ..Default::default()
+                }
+            )
+        );
+        let (_, modifier) = DataType::Char(None, None)
+            .to_data_type_with_modifier()
+            .unwrap();
+        assert_eq!(modifier.blank_padded_length, Some(1));
+        assert_eq!(
+            DataType::Varchar(Some(10), None)
+                .to_data_type_with_modifier()
+                .unwrap(),
+            (Common_Data_Type::Varchar, TypeModifier::default())
+        );
+        // The plain conversion takes CHAR as VARCHAR without the semantics.
+        assert_eq!(
+            DataType::Char(Some(10), None).to_data_type().unwrap(),
+            Common_Data_Type::Varchar
+        );
+        let (_, warnings) = DataType::Char(Some(10), None)
+            .to_data_type_checked()
+            .unwrap();
+        assert_eq!(
+            warnings,
+            vec!["blank-padding of CHAR(10) is not enforced".to_string()]
+        );
+    }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn test_to_arrow_type() {
+        use std::sync::Arc;
+
+        use arrow::datatypes::{DataType as ArrowDataType, Field, Fields, TimeUnit};
+
+        assert_eq!(
+            DataType::Int(None).to_arrow_type().unwrap(),
+            ArrowDataType::Int32
+        );
+        assert_eq!(
+            DataType::Varchar(Some(10), None).to_arrow_type().unwrap(),
+            ArrowDataType::Utf8
+        );
+        assert_eq!(
+            DataType::Timestamp(None, false).to_arrow_type().unwrap(),
+            ArrowDataType::Timestamp(TimeUnit::Microsecond, None)
+        );
+        assert_eq!(
+            DataType::Array(Box::new(DataType::Array(Box::new(DataType::BigInt(None)))))
+                .to_arrow_type()
+                .unwrap(),
+            ArrowDataType::List(Arc::new(Field::new(
+                "item",
+                ArrowDataType::List(Arc::new(Field::new("item", ArrowDataType::Int64, true))),
+                true,
+            )))
+        );
+        assert_eq!(
+            DataType::Struct(vec![
+                StructField {
+                    name: Ident::new("a"),
+                    data_type: DataType::Array(Box::new(DataType::Int(None))),
+                },
+                StructField {
+                    name: Ident::new("b"),
+                    data_type: DataType::Struct(vec![StructField {
+                        name: Ident::new("c"),
+                        data_type: DataType::Text,
+                    }]),
+                },
+            ])
+            .to_arrow_type()
+            .unwrap(),
+            ArrowDataType::Struct(Fields::from(vec![
+                Field::new(
+                    "a",
+                    ArrowDataType::List(Arc::new(Field::new("item", ArrowDataType::Int32, true))),
+                    true,
+                ),
+                Field::new(
+                    "b",
+                    ArrowDataType::Struct(Fields::from(vec![Field::new(
+                        "c",
+                        ArrowDataType::Utf8,
+                        true
+                    )])),
+                    true,
+                ),
+            ]))
+        );
+        assert_eq!(
+            DataType::Decimal(Some(10), Some(2))
+                .to_arrow_type()
+                .unwrap(),
+            ArrowDataType::Decimal128(10, 2)
+        );
+        assert_eq!(
+            DataType::Decimal(Some(50), None).to_arrow_type().unwrap(),
+            ArrowDataType::Decimal256(50, 0)
+        );
+        assert!(DataType::Decimal(Some(100), None).to_arrow_type().is_err());
+        assert!(DataType::Decimal(None, None).to_arrow_type().is_err());
+        // Nested types fail if any of their element types can't be converted.
+        assert!(DataType::Array(Box::new(DataType::Decimal(None, None)))
+            .to_arrow_type()
+            .is_err());
+        assert!(DataType::Struct(vec![StructField {
+            name: Ident::new("a"),
+            data_type: DataType::Decimal(None, None),
+        }])
+        .to_arrow_type()
+        .is_err());
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!(DataType::Name.to_string(), "NAME");
+        assert_eq!(
+            DataType::Name.to_data_type().unwrap(),
+            Common_Data_Type::Varchar
+        );
+        assert!(!DataType::Name.same_type(&DataType::Varchar(Some(64), None)));
+    }
+
+    #[test]
+    fn test_money() {
+        assert_eq!(DataType::Money(None).to_string(), "MONEY");
+        assert_eq!(
+            DataType::Money(Some("USD".to_string())).to_string(),
+            "MONEY('USD')"
+        );
+
+        assert_eq!(
+            DataType::Money(None).to_data_type_with_modifier().unwrap(),
+            (Common_Data_Type::Decimal, TypeModifier::default())
+        );
+        assert_eq!(
+            DataType::Money(Some("EUR".to_string()))
+                .to_data_type_with_modifier()
+                .unwrap(),
+            (
+                Common_Data_Type::Decimal,
+                TypeModifier {
+                    currency_code: Some("EUR".to_string()),
+                    ..Default::default()
+                }
+            )
+        );
+        assert!(DataType::same_physical_layout(
+            &DataType::Money(Some("USD".to_string())),
+            &DataType::Decimal(None, None)
+        ));
+        assert!(DataType::Money(Some("usd".to_string()))
+            .to_data_type()
+            .is_err());
+        assert!(DataType::Money(Some("DOLLAR".to_string()))
+            .to_data_type()
+            .is_err());
+    }
+
+    #[test]
+    fn test_binary_string_types() {
+        for data_type in [
+            DataType::Bytea,
+            DataType::Binary(16),
+            DataType::Varbinary(16),
+        ] {
+            assert_eq!(data_type.to_data_type().unwrap(), Common_Data_Type::Bytea);
+            assert_eq!(data_type.scalar_variant().unwrap(), ScalarVariant::Bytea);
+        }
+        for data_type in [DataType::Blob(1024), DataType::Clob(1024)] {
+            let err = data_type.to_data_type().unwrap_err();
+            assert!(matches!(err.inner(), ErrorCode::NotImplemented(..)));
+            assert!(err.to_string().contains("use BYTEA or VARCHAR"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_from_common_data_type() {
+        let scalars = [
+            Common_Data_Type::Boolean,
+            Common_Data_Type::Int16,
+            Common_Data_Type::Int32,
+            Common_Data_Type::Int64,
+            Common_Data_Type::Float32,
+            Common_Data_Type::Float64,
+            Common_Data_Type::Decimal,
+            Common_Data_Type::Date,
+            Common_Data_Type::Varchar,
+            Common_Data_Type::Time,
+            Common_Data_Type::Timestamp,
+            Common_Data_Type::Timestampz,
+            Common_Data_Type::Interval,
+            Common_Data_Type::Bytea,
+        ];
+        let mut data_types = scalars.to_vec();
+        // Nest every type in a list and a struct, and the nested types once more.
+        for _ in 0..2 {
+            let nested = data_types
+                .iter()
+                .flat_map(|data_type| {
+                    [
+                        Common_Data_Type::List {
+                            datatype: Box::new(data_type.clone()),
+                        },
+                        Common_Data_Type::Struct {
+                            fields: vec![data_type.clone(), Common_Data_Type::Int32].into(),
+                        },
+                    ]
+                })
+                .collect::<Vec<_>>();
+            data_types.extend(nested);
+        }
+        data_types.push(Common_Data_Type::Struct {
+            fields: vec![].into(),
+        });
+
+        for data_type in data_types {
+            let ast = DataType::from(&data_type);
+            assert_eq!(ast.to_data_type().unwrap(), data_type, "{}", ast);
+            // The displayed type is parsed back to the same type.
+            let sql = ast.to_string();
+            let tokens = Tokenizer::new(&sql).tokenize().unwrap();
+            assert_eq!(
+                Parser::new(tokens).parse_data_type().unwrap(),
+                ast,
+                "{}",
+                sql
+            );
+        }
+        assert_eq!(
+            DataType::from(&Common_Data_Type::Struct {
+                fields: vec![
+                    Common_Data_Type::Int32,
+                    Common_Data_Type::List {
+                        datatype: Box::new(Common_Data_Type::Timestampz),
+                    },
+                ]
+                .into(),
+            })
+            .to_string(),
+            "STRUCT<f1 INT, f2 TIMESTAMP WITH TIME ZONE[]>"
+        );
+    }
+
+    #[test]
+    fn test_to_data_type_with_catalog() {
+        struct MockCatalog(Vec<(&'static str, DataType)>);
+
+        impl TypeCatalog for MockCatalog {
+            fn domain_base_type(&self, name: &ObjectName) -> Option<DataType> {
+                self.0
+                    .iter()
+                    .find(|(domain, _)| {
+                        same_object_name(&ObjectName(vec![Ident::new(*domain)]), name)
+                    })
+                    .map(|(_, base_type)| base_type.clone())
+            }
+        }
+
+        let domain = |name: &str| custom(vec![Ident::new(name)]);
+        let catalog = MockCatalog(vec![
+            ("positive_int", DataType::Int(None)),
+            ("small_positive_int", domain("positive_int")),
+            (
+                "positive_ints",
+                DataType::Array(Box::new(domain("positive_int"))),
+            ),
+            ("ping", domain("pong")),
+            ("pong", domain("ping")),
+        ]);
+
+        assert_eq!(
+            domain("positive_int")
+                .to_data_type_with_catalog(&catalog)
+                .unwrap(),
+            Common_Data_Type::Int32
+        );
+        // Domain names are case-folded like other identifiers.
+        assert_eq!(
+            domain("Positive_Int")
+                .to_data_type_with_catalog(&catalog)
+                .unwrap(),
+            Common_Data_Type::Int32
+        );
+        assert_eq!(
+            domain("small_positive_int")
+                .to_data_type_with_catalog(&catalog)
+                .unwrap(),
+            Common_Data_Type::Int32
+        );
+        let int_list = Common_Data_Type::List {
+            datatype: Box::new(Common_Data_Type::Int32),
+        };
+        assert_eq!(
+            DataType::Array(Box::new(domain("positive_int")))
+                .to_data_type_with_catalog(&catalog)
+                .unwrap(),
+            int_list
+        );
+        assert_eq!(
+            DataType::Struct(vec![StructField {
+                name: Ident::new("a"),
+                data_type: domain("positive_ints"),
+            }])
+            .to_data_type_with_catalog(&catalog)
+            .unwrap(),
+            Common_Data_Type::Struct {
+                fields: vec![int_list].into(),
+            }
+        );
+
+        // Other custom types are still not supported.
+        assert!(domain("mood").to_data_type_with_catalog(&catalog).is_err());
+        assert!(domain("ping")
+            .to_data_type_with_catalog(&catalog)
+            .unwrap_err()
+            .to_string()
+            .contains("domain ping is defined in terms of itself"));
+    }
+
+    #[test]
+    fn test_char_length() {
+        for (data_type, type_name) in [
+            (DataType::Varchar(Some(0), None), "VARCHAR"),
+            (DataType::Char(Some(0), None), "CHAR"),
+        ] {
+            let err = data_type.to_data_type().unwrap_err();
+            assert!(matches!(err.inner(), ErrorCode::InvalidInputSyntax(..)));
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "Invalid input syntax: length for type {} must be at least 1",
+                    type_name
+                )
+            );
+        }
+        assert!(DataType::Char(Some(0), None)
+            .to_data_type_with_modifier()
+            .is_err());
+
+        for data_type in [
+            DataType::Varchar(Some(1), None),
+            DataType::Varchar(None, None),
+            DataType::Char(Some(1), None),
+            DataType::Char(None, None),
+        ] {
+            assert_eq!(data_type.to_data_type().unwrap(), Common_Data_Type::Varchar);
+        }
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!(DataType::Json.to_string(), "JSON");
+        assert_eq!(DataType::Jsonb.to_string(), "JSONB");
+        assert!(!DataType::Json.same_type(&DataType::Jsonb));
+        let err = DataType::Jsonb.to_data_type().unwrap_err();
+        assert!(matches!(err.inner(), ErrorCode::NotImplemented(..)));
+        assert!(err.to_string().contains("JSONB"));
+    }
+
+    #[test]
+    fn test_internal_char() {
+        assert_eq!(DataType::InternalChar.to_string(), r#""char""#);
+        assert_eq!(DataType::Char(None, None).to_string(), "CHAR");
+        assert_eq!(
+            DataType::InternalChar.to_data_type().unwrap(),
+            Common_Data_Type::Varchar
+        );
+        assert!(!DataType::InternalChar.same_type(&DataType::Char(Some(1), None)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_internal_char_serde() {
+        let json = serde_json::to_string(&DataType::InternalChar).unwrap();
+        assert_eq!(json, r#""InternalChar""#);
+        assert_eq!(
+            serde_json::from_str::<DataType>(&json).unwrap(),
+            DataType::InternalChar
+        );
+
+        let json = serde_json::to_string(&DataType::Char(Some(1), None)).unwrap();
+        assert_eq!(json, r#"{"Char":[1,null]}"#);
+        assert_eq!(
+            serde_json::from_str::<DataType>(&json).unwrap(),
+            DataType::Char(Some(1), None)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_name_serde() {
+        let json = serde_json::to_string(&DataType::Name).unwrap();
+        assert_eq!(json, r#""Name""#);
+        assert_eq!(
+            serde_json::from_str::<DataType>(&json).unwrap(),
+            DataType::Name
+        );
+    }
+
+    #[test]
+    fn test_void() {
+        assert_eq!(DataType::Void.to_string(), "VOID");
+        let err = DataType::Void.to_data_type().unwrap_err();
+        assert!(matches!(err.inner(), ErrorCode::BindError(_)));
+        assert!(err.to_string().contains("return type of a function"));
+        assert!(DataType::Array(Box::new(DataType::Void))
+            .to_data_type()
+            .is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_void_serde() {
+        let json = serde_json::to_string(&DataType::Void).unwrap();
+        assert_eq!(json, r#""Void""#);
+        assert_eq!(
+            serde_json::from_str::<DataType>(&json).unwrap(),
+            DataType::Void
+        );
+    }
+
+    #[test]
+    fn test_range() {
+        let int_range = DataType::Range(Box::new(DataType::Int(None)));
+        assert_eq!(int_range.to_string(), "RANGE<INT>");
+        assert_eq!(
+            int_range.to_data_type().unwrap(),
+            Common_Data_Type::Struct {
+                fields: vec![Common_Data_Type::Int32, Common_Data_Type::Int32].into(),
+            }
+        );
+
+        let timestamp_range = DataType::Range(Box::new(DataType::Timestamp(None, true)));
+        assert_eq!(
+            timestamp_range.to_string(),
+            "RANGE<TIMESTAMP WITH TIME ZONE>"
+        );
+        assert_eq!(
+            timestamp_range.to_data_type().unwrap(),
+            Common_Data_Type::Struct {
+                fields: vec![Common_Data_Type::Timestampz, Common_Data_Type::Timestampz].into(),
+            }
+        );
+
+        assert!(DataType::Range(Box::new(DataType::Clob(10)))
+            .to_data_type()
+            .is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_range_serde() {
+        let cases = [
+            (
+                DataType::Range(Box::new(DataType::Int(None))),
+                r#"{"Range":{"Int":null}}"#,
+            ),
+            (
+                DataType::Range(Box::new(DataType::Timestamp(None, false))),
+                r#"{"Range":{"Timestamp":[null,false]}}"#,
+            ),
+        ];
+        for (data_type, json) in cases {
+            assert_eq!(serde_json::to_string(&data_type).unwrap(), json);
+            assert_eq!(serde_json::from_str::<DataType>(json).unwrap(), data_type);
+        }
+    }
+
+    #[test]
+    fn test_same_physical_layout() {
+        let same = |a: DataType, b: DataType| DataType::same_physical_layout(&a, &b);
+        assert!(same(
+            DataType::Timestamp(None, false),
+            DataType::BigInt(None)
+        ));
+        assert!(same(
+            DataType::Timestamp(Some(3), true),
+            DataType::BigInt(None)
+        ));
+        assert!(same(DataType::Date, DataType::Int(None)));
+        assert!(same(DataType::Real, DataType::Float(Some(24))));
+        assert!(same(DataType::Text, DataType::Varchar(Some(10), None)));
+        assert!(same(
+            DataType::Array(Box::new(DataType::Date)),
+            DataType::Array(Box::new(DataType::Int(None)))
+        ));
+
+        assert!(!same(DataType::Date, DataType::BigInt(None)));
+        assert!(!same(DataType::Int(None), DataType::Real));
+        assert!(!same(DataType::Double, DataType::BigInt(None)));
+        assert!(!same(DataType::Text, DataType::Bytea));
+        assert!(!same(
+            DataType::Array(Box::new(DataType::Int(None))),
+            DataType::Int(None)
+        ));
+        assert!(!same(DataType::Uuid, DataType::Uuid));
+    }
+
+    #[test]
+    fn test_is_fixed_width() {
+        let fixed_width = [
+            DataType::Boolean,
+            DataType::TinyInt(None),
+            DataType::SmallInt(None),
+            DataType::Int(None),
+            DataType::BigInt(None),
+            DataType::Real,
+            DataType::Double,
+            DataType::Float(None),
+            DataType::Decimal(Some(10), Some(2)),
+            DataType::Date,
+            DataType::Time(None, false),
+            DataType::Timestamp(None, false),
+            DataType::Timestamp(Some(3), true),
+            DataType::Interval(None, None),
+        ];
+        for data_type in fixed_width {
+            assert!(data_type.is_fixed_width(), "{}", data_type);
+        }
+
+        let variable_width = [
+            DataType::Char(Some(10), None),
+            DataType::Varchar(None, None),
+            DataType::Text,
+            DataType::String,
+            DataType::Name,
+            DataType::Bytea,
+            DataType::Varbinary(10),
+            DataType::Array(Box::new(DataType::Int(None))),
+            DataType::Range(Box::new(DataType::Int(None))),
+            custom(vec![Ident::new("my_enum")]),
+        ];
+        for data_type in variable_width {
+            assert!(!data_type.is_fixed_width(), "{}", data_type);
+        }
+    }
+
+    /// Persisted catalogs rely on the serialized form of data types, which must not change.
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_array_serde_stability() {
+        let cases = [
+            (
+                DataType::Array(Box::new(DataType::Int(None))),
+                r#"{"Array":{"Int":null}}"#,
+            ),
+            (
+                DataType::Array(Box::new(DataType::Array(Box::new(DataType::Varchar(
+                    Some(10),
+                    None,
+                ))))),
+                r#"{"Array":{"Array":{"Varchar":[10,null]}}}"#,
+            ),
+            (
+                DataType::Array(Box::new(DataType::Timestamp(Some(3), true))),
+                r#"{"Array":{"Timestamp":[3,true]}}"#,
+            ),
+            (
+                DataType::Array(Box::new(DataType::Boolean)),
+                r#"{"Array":"Boolean"}"#,
+            ),
+            (
+                DataType::Array(Box::new(custom(vec![Ident::new("my_enum")]))),
+                r#"{"Array":{"Custom":[{"value":"my_enum","quote_style":null}]}}"#,
+            ),
+        ];
+        for (data_type, json) in cases {
+            assert_eq!(serde_json::to_string(&data_type).unwrap(), json);
+            assert_eq!(serde_json::from_str::<DataType>(json).unwrap(), data_type);
+        }
+    }
+}
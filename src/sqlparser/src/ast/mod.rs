@@ -30,7 +30,11 @@ use itertools::Itertools;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-pub use self::data_type::DataType;
+pub use self::data_type::{
+    CharacterSet, DataType, DataTypeConversionConfig, IntervalFields, ScalarVariant, StorageHint,
+    StructField, TypeCatalog, TypeModifier, DEFAULT_MAX_NESTING_DEPTH,
+    MAX_SCALED_INT_DECIMAL_PRECISION,
+};
 pub use self::ddl::{
     AlterColumnOperation, AlterTableOperation, ColumnDef, ColumnOption, ColumnOptionDef,
     ReferentialAction, TableConstraint,
@@ -251,6 +251,7 @@ define_keywords!(
     ISOLATION,
     JOIN,
     JSON,
+    JSONB,
     KEY,
     LANGUAGE,
     LARGE,
@@ -279,8 +280,10 @@ define_keywords!(
     MOD,
     MODIFIES,
     MODULE,
+    MONEY,
     MONTH,
     MULTISET,
+    NAME,
     NATIONAL,
     NATURAL,
     NCHAR,
@@ -417,6 +420,7 @@ define_keywords!(
     STDIN,
     STORED,
     STRING,
+    STRUCT,
     SUBMULTISET,
     SUBSTRING,
     SUBSTRING_REGEX,
@@ -464,6 +468,7 @@ define_keywords!(
     UNIQUE,
     UNKNOWN,
     UNNEST,
+    UNSIGNED,
     UPDATE,
     UPPER,
     USAGE,
@@ -483,6 +488,7 @@ define_keywords!(
     VIEW,
     VIEWS,
     VIRTUAL,
+    VOID,
     WHEN,
     WHENEVER,
     WHERE,
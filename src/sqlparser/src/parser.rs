@@ -357,7 +357,7 @@ impl Parser {
         // expression that should parse as the column name "date".
         return_ok_if_some!(self.maybe_parse(|parser| {
             match parser.parse_data_type()? {
-                DataType::Interval => parser.parse_literal_interval(),
+                DataType::Interval(None, None) => parser.parse_literal_interval(),
                 // PostgreSQL allows almost any identifier to be used as custom data type name,
                 // and we support that in `parse_data_type()`. But unlike Postgres we don't
                 // have a list of globally reserved keywords (since they vary across dialects),
@@ -855,6 +855,46 @@ impl Parser {
         }
     }
 
+    /// Parse the optional fields qualifier of an interval type, e.g. `DAY TO SECOND`.
+    pub fn parse_optional_interval_fields(
+        &mut self,
+    ) -> Result<Option<IntervalFields>, ParserError> {
+        match self.peek_token() {
+            Token::Word(kw)
+                if [
+                    Keyword::YEAR,
+                    Keyword::MONTH,
+                    Keyword::DAY,
+                    Keyword::HOUR,
+                    Keyword::MINUTE,
+                    Keyword::SECOND,
+                ]
+                .iter()
+                .any(|d| kw.keyword == *d) =>
+            {
+                let leading_field = self.parse_date_time_field()?;
+                // The precision after a leading `SECOND` is the fractional seconds precision of
+                // the type, which is parsed by the caller.
+                let leading_precision = if leading_field == DateTimeField::Second {
+                    None
+                } else {
+                    self.parse_optional_precision()?
+                };
+                let last_field = if self.parse_keyword(Keyword::TO) {
+                    Some(self.parse_date_time_field()?)
+                } else {
+                    None
+                };
+                Ok(Some(IntervalFields {
+                    leading_field,
+                    leading_precision,
+                    last_field,
+                }))
+            }
+            _ => Ok(None),
+        }
+    }
+
     /// Parse an INTERVAL literal.
     ///
     /// Some syntactically valid intervals:
@@ -1316,6 +1356,19 @@ impl Parser {
         }
     }
 
+    /// Consume the `>` closing a type like `STRUCT<...>`. Nested types closed at once, e.g.
+    /// `STRUCT<a STRUCT<b INT>>`, end with `>>` which is tokenized as a shift, so the shift is
+    /// split and its second `>` is left for the outer type.
+    fn expect_closing_angle_bracket(&mut self) -> Result<(), ParserError> {
+        if self.peek_token() == Token::ShiftRight {
+            self.next_token();
+            self.index -= 1;
+            self.tokens[self.index] = Token::Gt;
+            return Ok(());
+        }
+        self.expect_token(&Token::Gt)
+    }
+
     /// Parse a comma-separated list of 1+ items accepted by `F`
     pub fn parse_comma_separated<T, F>(&mut self, mut f: F) -> Result<Vec<T>, ParserError>
     where
@@ -1954,41 +2007,114 @@ impl Parser {
                     let _ = self.parse_keyword(Keyword::PRECISION);
                     Ok(DataType::Double)
                 }
-                Keyword::TINYINT => Ok(DataType::TinyInt(self.parse_optional_precision()?)),
-                Keyword::SMALLINT => Ok(DataType::SmallInt(self.parse_optional_precision()?)),
+                Keyword::TINYINT => {
+                    let precision = self.parse_optional_precision()?;
+                    if self.parse_keyword(Keyword::UNSIGNED) {
+                        Ok(DataType::UnsignedTinyInt(precision))
+                    } else {
+                        Ok(DataType::TinyInt(precision))
+                    }
+                }
+                Keyword::SMALLINT => {
+                    let precision = self.parse_optional_precision()?;
+                    if self.parse_keyword(Keyword::UNSIGNED) {
+                        Ok(DataType::UnsignedSmallInt(precision))
+                    } else {
+                        Ok(DataType::SmallInt(precision))
+                    }
+                }
                 Keyword::INT | Keyword::INTEGER => {
-                    Ok(DataType::Int(self.parse_optional_precision()?))
+                    let precision = self.parse_optional_precision()?;
+                    if self.parse_keyword(Keyword::UNSIGNED) {
+                        Ok(DataType::UnsignedInt(precision))
+                    } else {
+                        Ok(DataType::Int(precision))
+                    }
                 }
-                Keyword::BIGINT => Ok(DataType::BigInt(self.parse_optional_precision()?)),
-                Keyword::VARCHAR => Ok(DataType::Varchar(self.parse_optional_precision()?)),
+                Keyword::BIGINT => {
+                    let precision = self.parse_optional_precision()?;
+                    if self.parse_keyword(Keyword::UNSIGNED) {
+                        Ok(DataType::UnsignedBigInt(precision))
+                    } else {
+                        Ok(DataType::BigInt(precision))
+                    }
+                }
+                Keyword::VARCHAR => Ok(DataType::Varchar(
+                    self.parse_optional_precision()?,
+                    self.parse_optional_character_set()?,
+                )),
                 Keyword::CHAR | Keyword::CHARACTER => {
                     if self.parse_keyword(Keyword::VARYING) {
-                        Ok(DataType::Varchar(self.parse_optional_precision()?))
+                        Ok(DataType::Varchar(
+                            self.parse_optional_precision()?,
+                            self.parse_optional_character_set()?,
+                        ))
                     } else {
-                        Ok(DataType::Char(self.parse_optional_precision()?))
+                        Ok(DataType::Char(
+                            self.parse_optional_precision()?,
+                            self.parse_optional_character_set()?,
+                        ))
                     }
                 }
                 Keyword::UUID => Ok(DataType::Uuid),
+                Keyword::VOID => Ok(DataType::Void),
                 Keyword::DATE => Ok(DataType::Date),
                 Keyword::TIMESTAMP => {
+                    let precision = self.parse_optional_precision()?;
                     let with_time_zone = self.parse_keyword(Keyword::WITH);
                     if with_time_zone || self.parse_keyword(Keyword::WITHOUT) {
                         self.expect_keywords(&[Keyword::TIME, Keyword::ZONE])?;
                     }
-                    Ok(DataType::Timestamp(with_time_zone))
+                    Ok(DataType::Timestamp(precision, with_time_zone))
                 }
                 Keyword::TIME => {
+                    let precision = self.parse_optional_precision()?;
                     let with_time_zone = self.parse_keyword(Keyword::WITH);
                     if with_time_zone || self.parse_keyword(Keyword::WITHOUT) {
                         self.expect_keywords(&[Keyword::TIME, Keyword::ZONE])?;
                     }
-                    Ok(DataType::Time(with_time_zone))
+                    Ok(DataType::Time(precision, with_time_zone))
+                }
+                // Interval types can be followed by a fields qualifier, and a fractional seconds
+                // precision if the fields end with `SECOND` or there are no fields, e.g.
+                // `INTERVAL DAY(3) TO SECOND(6)` or `INTERVAL(6)`.
+                Keyword::INTERVAL => {
+                    let fields = self.parse_optional_interval_fields()?;
+                    let precision = match &fields {
+                        Some(IntervalFields {
+                            leading_field,
+                            last_field,
+                            ..
+                        }) if last_field.as_ref().unwrap_or(leading_field)
+                            != &DateTimeField::Second =>
+                        {
+                            None
+                        }
+                        _ => self.parse_optional_precision()?,
+                    };
+                    Ok(DataType::Interval(fields, precision))
                 }
-                // Interval types can be followed by a complicated interval
-                // qualifier that we don't currently support. See
-                // parse_interval_literal for a taste.
-                Keyword::INTERVAL => Ok(DataType::Interval),
                 Keyword::REGCLASS => Ok(DataType::Regclass),
+                Keyword::NAME => Ok(DataType::Name),
+                Keyword::RANGE => {
+                    self.expect_token(&Token::Lt)?;
+                    let subtype = self.parse_data_type()?;
+                    self.expect_closing_angle_bracket()?;
+                    Ok(DataType::Range(Box::new(subtype)))
+                }
+                // `STRUCT<>` is tokenized as `STRUCT` followed by `<>`.
+                Keyword::STRUCT if self.consume_token(&Token::Neq) => Ok(DataType::Struct(vec![])),
+                Keyword::STRUCT => {
+                    self.expect_token(&Token::Lt)?;
+                    let fields = self.parse_comma_separated(|parser| {
+                        Ok(StructField {
+                            name: parser.parse_identifier()?,
+                            data_type: parser.parse_data_type()?,
+                        })
+                    })?;
+                    self.expect_closing_angle_bracket()?;
+                    Ok(DataType::Struct(fields))
+                }
                 Keyword::STRING => Ok(DataType::String),
                 Keyword::TEXT => {
                     if self.consume_token(&Token::LBracket) {
@@ -2000,10 +2126,30 @@ impl Parser {
                     }
                 }
                 Keyword::BYTEA => Ok(DataType::Bytea),
+                Keyword::JSON => Ok(DataType::Json),
+                Keyword::JSONB => Ok(DataType::Jsonb),
+                Keyword::MONEY => {
+                    let currency_code = if self.consume_token(&Token::LParen) {
+                        let currency_code = match self.next_token() {
+                            Token::SingleQuotedString(s) => s,
+                            unexpected => return self.expected("currency code", unexpected),
+                        };
+                        self.expect_token(&Token::RParen)?;
+                        Some(currency_code)
+                    } else {
+                        None
+                    };
+                    Ok(DataType::Money(currency_code))
+                }
                 Keyword::NUMERIC | Keyword::DECIMAL | Keyword::DEC => {
                     let (precision, scale) = self.parse_optional_precision_scale()?;
                     Ok(DataType::Decimal(precision, scale))
                 }
+                // The internal `"char"` type of postgresql must be quoted, while an unquoted `char`
+                // is `CHAR(1)`.
+                Keyword::NoKeyword if w.quote_style == Some('"') && w.value == "char" => {
+                    Ok(DataType::InternalChar)
+                }
                 _ => {
                     self.prev_token();
                     let type_name = self.parse_object_name()?;
@@ -2165,6 +2311,20 @@ impl Parser {
         }
     }
 
+    /// Parse an optional `CHARACTER SET name [COLLATE collation]` after a character type
+    pub fn parse_optional_character_set(&mut self) -> Result<Option<CharacterSet>, ParserError> {
+        if !self.parse_keywords(&[Keyword::CHARACTER, Keyword::SET]) {
+            return Ok(None);
+        }
+        let name = self.parse_object_name()?;
+        let collation = if self.parse_keyword(Keyword::COLLATE) {
+            Some(self.parse_object_name()?)
+        } else {
+            None
+        };
+        Ok(Some(CharacterSet { name, collation }))
+    }
+
     pub fn parse_optional_precision(&mut self) -> Result<Option<u64>, ParserError> {
         if self.consume_token(&Token::LParen) {
             let n = self.parse_literal_uint()?;
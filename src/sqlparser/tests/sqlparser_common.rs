@@ -1151,6 +1151,249 @@ fn parse_cast() {
         "SELECT CAST(id AS DECIMAL) FROM customer",
         "SELECT CAST(id AS NUMERIC) FROM customer",
     );
+
+    let sql = "SELECT CAST(id AS TIMESTAMP(3) WITH TIME ZONE) FROM customer";
+    let select = verified_only_select(sql);
+    assert_eq!(
+        &Expr::Cast {
+            expr: Box::new(Expr::Identifier(Ident::new("id"))),
+            data_type: DataType::Timestamp(Some(3), true)
+        },
+        expr_from_projection(only(&select.projection))
+    );
+}
+
+#[test]
+fn parse_create_table_with_struct_type() {
+    let sql = "CREATE TABLE t (s STRUCT<a INT, b TEXT>, c INT)";
+    match verified_stmt(sql) {
+        Statement::CreateTable { columns, .. } => {
+            assert_eq!(
+                columns[0].data_type,
+                DataType::Struct(vec![
+                    StructField {
+                        name: Ident::new("a"),
+                        data_type: DataType::Int(None),
+                    },
+                    StructField {
+                        name: Ident::new("b"),
+                        data_type: DataType::Text,
+                    },
+                ])
+            );
+            assert_eq!(columns[1].data_type, DataType::Int(None));
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn parse_create_table_with_nested_struct_type() {
+    let field = |name: &str, data_type| StructField {
+        name: Ident::new(name),
+        data_type,
+    };
+    let sql = "CREATE TABLE t (s STRUCT<a STRUCT<b STRUCT<c INT>>>, e STRUCT<>, \
+               l STRUCT<tags INT[]>, r RANGE<STRUCT<d DATE>>)";
+    match verified_stmt(sql) {
+        Statement::CreateTable { columns, .. } => {
+            assert_eq!(
+                columns[0].data_type,
+                DataType::Struct(vec![field(
+                    "a",
+                    DataType::Struct(vec![field(
+                        "b",
+                        DataType::Struct(vec![field("c", DataType::Int(None))])
+                    )])
+                )])
+            );
+            assert_eq!(columns[1].data_type, DataType::Struct(vec![]));
+            assert_eq!(
+                columns[2].data_type,
+                DataType::Struct(vec![field(
+                    "tags",
+                    DataType::Array(Box::new(DataType::Int(None)))
+                )])
+            );
+            assert_eq!(
+                columns[3].data_type,
+                DataType::Range(Box::new(DataType::Struct(vec![field("d", DataType::Date)])))
+            );
+        }
+        _ => unreachable!(),
+    }
+
+    // A shift in expressions is not split.
+    verified_only_select("SELECT a >> 2 FROM t");
+}
+
+#[test]
+fn parse_double_precision() {
+    let canonical = "SELECT CAST(a AS DOUBLE PRECISION) FROM t";
+    one_statement_parses_to("SELECT CAST(a AS DOUBLE) FROM t", canonical);
+    let select = verified_only_select(canonical);
+    match expr_from_projection(only(&select.projection)) {
+        Expr::Cast { data_type, .. } => {
+            assert_eq!(data_type, &DataType::Double);
+            assert_eq!(
+                data_type.to_data_type().unwrap(),
+                risingwave_common::types::DataType::Float64
+            );
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn parse_create_table_with_unsigned_int() {
+    let sql = "CREATE TABLE t (a INT UNSIGNED, b BIGINT(20) UNSIGNED, c INT, d BIGINT)";
+    match verified_stmt(sql) {
+        Statement::CreateTable { columns, .. } => {
+            assert_eq!(columns[0].data_type, DataType::UnsignedInt(None));
+            assert_eq!(columns[1].data_type, DataType::UnsignedBigInt(Some(20)));
+            assert_eq!(columns[2].data_type, DataType::Int(None));
+            assert_eq!(columns[3].data_type, DataType::BigInt(None));
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn parse_cast_with_interval_fields() {
+    let sql = "SELECT CAST(d AS INTERVAL DAY TO SECOND) FROM customer";
+    let select = verified_only_select(sql);
+    assert_eq!(
+        &Expr::Cast {
+            expr: Box::new(Expr::Identifier(Ident::new("d"))),
+            data_type: DataType::Interval(
+                Some(IntervalFields {
+                    leading_field: DateTimeField::Day,
+                    leading_precision: None,
+                    last_field: Some(DateTimeField::Second),
+                }),
+                None
+            )
+        },
+        expr_from_projection(only(&select.projection))
+    );
+
+    let sql = "SELECT CAST(d AS INTERVAL YEAR) FROM customer";
+    let select = verified_only_select(sql);
+    assert_eq!(
+        &Expr::Cast {
+            expr: Box::new(Expr::Identifier(Ident::new("d"))),
+            data_type: DataType::Interval(
+                Some(IntervalFields {
+                    leading_field: DateTimeField::Year,
+                    leading_precision: None,
+                    last_field: None,
+                }),
+                None
+            )
+        },
+        expr_from_projection(only(&select.projection))
+    );
+
+    let sql = "SELECT CAST(d AS INTERVAL) FROM customer";
+    let select = verified_only_select(sql);
+    assert_eq!(
+        &Expr::Cast {
+            expr: Box::new(Expr::Identifier(Ident::new("d"))),
+            data_type: DataType::Interval(None, None)
+        },
+        expr_from_projection(only(&select.projection))
+    );
+}
+
+#[test]
+fn parse_cast_with_interval_precision() {
+    for (sql, data_type) in [
+        (
+            "SELECT CAST(d AS INTERVAL DAY TO SECOND(6)) FROM customer",
+            DataType::Interval(
+                Some(IntervalFields {
+                    leading_field: DateTimeField::Day,
+                    leading_precision: None,
+                    last_field: Some(DateTimeField::Second),
+                }),
+                Some(6),
+            ),
+        ),
+        (
+            "SELECT CAST(d AS INTERVAL DAY(3) TO SECOND(6)) FROM customer",
+            DataType::Interval(
+                Some(IntervalFields {
+                    leading_field: DateTimeField::Day,
+                    leading_precision: Some(3),
+                    last_field: Some(DateTimeField::Second),
+                }),
+                Some(6),
+            ),
+        ),
+        (
+            "SELECT CAST(d AS INTERVAL SECOND(3)) FROM customer",
+            DataType::Interval(
+                Some(IntervalFields {
+                    leading_field: DateTimeField::Second,
+                    leading_precision: None,
+                    last_field: None,
+                }),
+                Some(3),
+            ),
+        ),
+        (
+            "SELECT CAST(d AS INTERVAL(6)) FROM customer",
+            DataType::Interval(None, Some(6)),
+        ),
+    ] {
+        // Parsing the displayed statement again yields the same type.
+        let select = verified_only_select(sql);
+        assert_eq!(
+            &Expr::Cast {
+                expr: Box::new(Expr::Identifier(Ident::new("d"))),
+                data_type
+            },
+            expr_from_projection(only(&select.projection))
+        );
+    }
+}
+
+#[test]
+fn parse_cast_with_character_set() {
+    let sql = "SELECT CAST(name AS CHARACTER VARYING(20) CHARACTER SET utf8) FROM customer";
+    let select = verified_only_select(sql);
+    let utf8 = CharacterSet {
+        name: ObjectName(vec![Ident::new("utf8")]),
+        collation: None,
+    };
+    assert_eq!(
+        &Expr::Cast {
+            expr: Box::new(Expr::Identifier(Ident::new("name"))),
+            data_type: DataType::Varchar(Some(20), Some(utf8.clone()))
+        },
+        expr_from_projection(only(&select.projection))
+    );
+
+    one_statement_parses_to(
+        "SELECT CAST(name AS VARCHAR(20) CHARACTER SET utf8) FROM customer",
+        sql,
+    );
+
+    let sql = "SELECT CAST(name AS CHAR(10) CHARACTER SET utf8 COLLATE utf8_bin) FROM customer";
+    let select = verified_only_select(sql);
+    assert_eq!(
+        &Expr::Cast {
+            expr: Box::new(Expr::Identifier(Ident::new("name"))),
+            data_type: DataType::Char(
+                Some(10),
+                Some(CharacterSet {
+                    collation: Some(ObjectName(vec![Ident::new("utf8_bin")])),
+                    ..utf8
+                })
+            )
+        },
+        expr_from_projection(only(&select.projection))
+    );
 }
 
 #[test]
@@ -1227,8 +1470,8 @@ fn parse_create_table() {
         sql,
         "CREATE TABLE uk_cities (\
          name CHARACTER VARYING(100) NOT NULL, \
-         lat DOUBLE NULL, \
-         lng DOUBLE, \
+         lat DOUBLE PRECISION NULL, \
+         lng DOUBLE PRECISION, \
          constrained INT NULL CONSTRAINT pkey PRIMARY KEY NOT NULL UNIQUE CHECK (constrained > 0), \
          ref INT REFERENCES othertable (a, b), \
          ref2 INT REFERENCES othertable2 ON DELETE CASCADE ON UPDATE NO ACTION, \
@@ -1252,7 +1495,7 @@ fn parse_create_table() {
                 vec![
                     ColumnDef {
                         name: "name".into(),
-                        data_type: DataType::Varchar(Some(100)),
+                        data_type: DataType::Varchar(Some(100), None),
                         collation: None,
                         options: vec![ColumnOptionDef {
                             name: None,
@@ -1892,7 +2135,7 @@ fn parse_literal_time() {
     let select = verified_only_select(sql);
     assert_eq!(
         &Expr::TypedString {
-            data_type: DataType::Time(false),
+            data_type: DataType::Time(None, false),
             value: "01:23:34".into()
         },
         expr_from_projection(only(&select.projection)),
@@ -1905,7 +2148,7 @@ fn parse_literal_timestamp() {
     let select = verified_only_select(sql);
     assert_eq!(
         &Expr::TypedString {
-            data_type: DataType::Timestamp(false),
+            data_type: DataType::Timestamp(None, false),
             value: "1999-01-01 01:23:34".into()
         },
         expr_from_projection(only(&select.projection)),
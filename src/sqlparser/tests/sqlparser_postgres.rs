@@ -70,7 +70,7 @@ fn parse_create_table_with_defaults() {
                     },
                     ColumnDef {
                         name: "first_name".into(),
-                        data_type: DataType::Varchar(Some(45)),
+                        data_type: DataType::Varchar(Some(45), None),
                         collation: None,
                         options: vec![ColumnOptionDef {
                             name: None,
@@ -79,7 +79,7 @@ fn parse_create_table_with_defaults() {
                     },
                     ColumnDef {
                         name: "last_name".into(),
-                        data_type: DataType::Varchar(Some(45)),
+                        data_type: DataType::Varchar(Some(45), None),
                         collation: Some(ObjectName(vec![Ident::with_quote('"', "es_ES")])),
                         options: vec![ColumnOptionDef {
                             name: None,
@@ -88,7 +88,7 @@ fn parse_create_table_with_defaults() {
                     },
                     ColumnDef {
                         name: "email".into(),
-                        data_type: DataType::Varchar(Some(50)),
+                        data_type: DataType::Varchar(Some(50), None),
                         collation: None,
                         options: vec![],
                     },
@@ -133,7 +133,7 @@ fn parse_create_table_with_defaults() {
                     },
                     ColumnDef {
                         name: "last_update".into(),
-                        data_type: DataType::Timestamp(false),
+                        data_type: DataType::Timestamp(None, false),
                         collation: None,
                         options: vec![
                             ColumnOptionDef {
@@ -224,6 +224,86 @@ fn parse_create_table_with_inherit() {
     verified_stmt(sql);
 }
 
+#[test]
+fn parse_create_table_with_name_type() {
+    match verified_stmt("CREATE TABLE pg_roles (rolname NAME, name NAME)") {
+        Statement::CreateTable { columns, .. } => {
+            assert_eq!(columns[0].name, Ident::new("rolname"));
+            assert_eq!(columns[0].data_type, DataType::Name);
+            assert_eq!(columns[1].name, Ident::new("name"));
+            assert_eq!(columns[1].data_type, DataType::Name);
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn parse_create_table_with_internal_char_type() {
+    match verified_stmt(r#"CREATE TABLE pg_class (relkind "char", relname CHAR)"#) {
+        Statement::CreateTable { columns, .. } => {
+            assert_eq!(columns[0].data_type, DataType::InternalChar);
+            assert_eq!(columns[1].data_type, DataType::Char(None, None));
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn parse_create_table_with_money_type() {
+    match verified_stmt("CREATE TABLE accounts (balance MONEY, fee MONEY('USD'))") {
+        Statement::CreateTable { columns, .. } => {
+            assert_eq!(columns[0].data_type, DataType::Money(None));
+            assert_eq!(
+                columns[1].data_type,
+                DataType::Money(Some("USD".to_string()))
+            );
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn parse_create_table_with_time_precision() {
+    match verified_stmt(
+        "CREATE TABLE shifts (starts TIME(3), ends TIME WITH TIME ZONE, logged TIMESTAMP(6))",
+    ) {
+        Statement::CreateTable { columns, .. } => {
+            assert_eq!(columns[0].data_type, DataType::Time(Some(3), false));
+            assert_eq!(columns[1].data_type, DataType::Time(None, true));
+            assert_eq!(columns[2].data_type, DataType::Timestamp(Some(6), false));
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn parse_create_table_with_json_type() {
+    match verified_stmt("CREATE TABLE events (payload JSON, attrs JSONB)") {
+        Statement::CreateTable { columns, .. } => {
+            assert_eq!(columns[0].data_type, DataType::Json);
+            assert_eq!(columns[1].data_type, DataType::Jsonb);
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn parse_create_table_with_range_type() {
+    match verified_stmt("CREATE TABLE reservations (during RANGE<TIMESTAMP>, seats RANGE<INT>)") {
+        Statement::CreateTable { columns, .. } => {
+            assert_eq!(
+                columns[0].data_type,
+                DataType::Range(Box::new(DataType::Timestamp(None, false)))
+            );
+            assert_eq!(
+                columns[1].data_type,
+                DataType::Range(Box::new(DataType::Int(None)))
+            );
+        }
+        _ => unreachable!(),
+    }
+}
+
 #[test]
 fn parse_create_table_empty() {
     // Zero-column tables are weird, but supported by at least PostgreSQL.
@@ -21,9 +21,10 @@ use futures::Future;
 use itertools::Itertools;
 use risingwave_common::config::StorageConfig;
 use risingwave_hummock_sdk::compact::compact_task_to_string;
-use risingwave_hummock_sdk::key::{get_epoch, Epoch, FullKey};
+use risingwave_hummock_sdk::key::{get_epoch, get_table_id, Epoch, FullKey};
 use risingwave_hummock_sdk::key_range::KeyRange;
 use risingwave_hummock_sdk::VersionedComparator;
+use risingwave_pb::hummock::compact_task::OutputTier;
 use risingwave_pb::hummock::{
     CompactTask, LevelType, SstableInfo, SubscribeCompactTasksResponse, VNodeBitmap, VacuumTask,
 };
@@ -37,6 +38,7 @@ use super::iterator::{
 use super::multi_builder::CapacitySplitTableBuilder;
 use super::shared_buffer::shared_buffer_batch::SharedBufferBatch;
 use super::sstable_store::SstableStoreRef;
+use super::value::HummockValue;
 use super::{
     HummockError, HummockResult, HummockStorage, SSTableBuilder, SSTableIterator, Sstable,
 };
@@ -130,6 +132,10 @@ impl Compactor {
             prefix_pairs: vec![],
             // VNode mappings are not required when compacting shared buffer to L0
             vnode_mappings: vec![],
+            max_output_writers: 0,
+            priority: 0,
+            vnode_bitmaps: vec![],
+            output_tier: OutputTier::Hot as i32,
         };
 
         let parallelism = compact_task.splits.len();
@@ -195,18 +201,24 @@ impl Compactor {
         let parallelism = compact_task.splits.len();
         let mut compact_success = true;
         let mut output_ssts = Vec::with_capacity(parallelism);
-        let mut compaction_futures = vec![];
         let mut compactor = Compactor::new(context, compact_task.clone());
 
-        for (split_index, _) in compact_task.splits.iter().enumerate() {
-            let compactor = compactor.clone();
-            compaction_futures.push(tokio::spawn(async move {
-                let merge_iter = compactor.build_sst_iter().await?;
-                compactor.compact_key_range(split_index, merge_iter).await
-            }));
-        }
-
-        let mut buffered = stream::iter(compaction_futures).buffer_unordered(parallelism);
+        // Splits are spawned lazily so that at most `max_output_writers` of them are compacted
+        // at the same time.
+        let max_output_writers = match compact_task.max_output_writers as usize {
+            0 => parallelism,
+            max_output_writers => std::cmp::min(max_output_writers, parallelism),
+        };
+        let split_compactor = compactor.clone();
+        let mut buffered = stream::iter(0..parallelism)
+            .map(move |split_index| {
+                let compactor = split_compactor.clone();
+                tokio::spawn(async move {
+                    let merge_iter = compactor.build_sst_iter().await?;
+                    compactor.compact_key_range(split_index, merge_iter).await
+                })
+            })
+            .buffer_unordered(max_output_writers);
         while let Some(future_result) = buffered.next().await {
             match future_result.unwrap() {
                 Ok((split_index, ssts)) => {
@@ -268,6 +280,8 @@ impl Compactor {
                     }),
                     file_size: sst.meta.estimated_size as u64,
                     vnode_bitmaps,
+                    // Filled in by the meta service from the input SSTs.
+                    max_epoch: 0,
                 }));
         }
 
@@ -323,6 +337,7 @@ impl Compactor {
             iter,
             !self.compact_task.is_target_ultimate_and_leveling,
             self.compact_task.watermark,
+            &self.compact_task.vnode_bitmaps,
         )
         .await?;
         if let Some(timer) = build_l0_sst_timer {
@@ -536,6 +551,7 @@ impl Compactor {
         mut iter: MergeIterator,
         has_user_key_overlap: bool,
         watermark: Epoch,
+        vnode_bitmaps: &[VNodeBitmap],
     ) -> HummockResult<()>
     where
         B: Clone + Fn() -> F,
@@ -577,6 +593,14 @@ impl Compactor {
                 last_key.extend_from_slice(iter_key);
             }
 
+            // A sub-task split by virtual node only compacts the keys of its own virtual nodes.
+            if !vnode_bitmaps.is_empty()
+                && !vnode_bitmaps_contain(vnode_bitmaps, iter_key, iter.value())
+            {
+                iter.next().await?;
+                continue;
+            }
+
             let epoch = get_epoch(iter_key);
 
             // Among keys with same user key, only retain keys which satisfy `epoch` >= `watermark`,
@@ -599,3 +623,27 @@ impl Compactor {
         Ok(())
     }
 }
+
+/// Returns whether the virtual node of a key with `value` is set in the bitmap of its table in
+/// `vnode_bitmaps`. A key not of the listed tables is checked against the first bitmap, as a
+/// sub-task covers the same virtual nodes of all tables.
+fn vnode_bitmaps_contain(
+    vnode_bitmaps: &[VNodeBitmap],
+    full_key: &[u8],
+    value: HummockValue<&[u8]>,
+) -> bool {
+    let vnode = match value {
+        HummockValue::Put(meta, _) | HummockValue::Delete(meta) => meta.vnode as usize,
+    };
+    let bitmap = get_table_id(full_key)
+        .and_then(|table_id| {
+            vnode_bitmaps
+                .iter()
+                .find(|bitmap| bitmap.table_id == table_id)
+        })
+        .unwrap_or(&vnode_bitmaps[0]);
+    bitmap
+        .bitmap
+        .get(vnode >> 3)
+        .map_or(false, |byte| byte & (1 << (vnode & 0b111)) != 0)
+}
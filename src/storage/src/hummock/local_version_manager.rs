@@ -596,6 +596,7 @@ mod tests {
             }),
             file_size: batches.len() as u64,
             vnode_bitmaps: vec![],
+            max_epoch: 0,
         }
     }
 
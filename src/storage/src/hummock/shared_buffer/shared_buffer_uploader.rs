@@ -179,6 +179,8 @@ impl SharedBufferUploader {
                 }),
                 file_size: sst.meta.estimated_size as u64,
                 vnode_bitmaps,
+                // Filled in by the meta service on commit.
+                max_epoch: 0,
             })
             .collect();
 
@@ -609,6 +609,12 @@ impl<B: Buf> Deserializer<B> {
         let nsecs = self.input.get_u32();
         Ok((secs, nsecs))
     }
+
+    /// Deserialize a byte array serialized by `serialize_bytes`, without going through a
+    /// `Visitor`.
+    pub fn deserialize_bytes_owned(&mut self) -> Result<Vec<u8>> {
+        self.read_bytes()
+    }
 }
 
 #[cfg(test)]
@@ -822,6 +828,21 @@ mod tests {
         deserializer.deserialize_naivedate().unwrap()
     }
 
+    #[test]
+    fn test_bytes_owned() {
+        for bytes in [
+            &b""[..],
+            &b"\x00"[..],
+            &b"\xff\x00\x01\x02\x03\x04\x05\x06\x07\x08"[..],
+        ] {
+            let mut serializer = crate::Serializer::new(vec![]);
+            serde::Serializer::serialize_bytes(&mut serializer, bytes).unwrap();
+            let serialized = serializer.into_inner();
+            let mut deserializer = Deserializer::new(serialized.as_slice());
+            assert_eq!(deserializer.deserialize_bytes_owned().unwrap(), bytes);
+        }
+    }
+
     #[test]
     fn test_naivetime() {
         let (secs, nano) = (23 * 3600 + 59 * 60 + 59, 1234_5678);